@@ -1,6 +1,24 @@
 //! Quadrature-based decoder.
 
+mod absolute;
+mod biss;
+mod distance_coded;
+mod hall;
+mod hall_assisted;
 mod incremental;
 mod indexed;
+mod multi_turn;
+mod pwm;
+mod ssi;
 
-pub use self::{incremental::IncrementalDecoder, indexed::IndexedIncrementalDecoder};
+pub use self::{
+    absolute::{AbsoluteGrayDecoder, Coding},
+    biss::{BissDecoder, BissFrame},
+    distance_coded::DistanceCodedDecoder,
+    hall::HallDecoder,
+    hall_assisted::{Alignment, HallAssistedDecoder},
+    incremental::IncrementalDecoder,
+    indexed::IndexedIncrementalDecoder,
+    pwm::{PwmDecoder, PwmFormat},
+    ssi::{Parity, SsiDecoder},
+};
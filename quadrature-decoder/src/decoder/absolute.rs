@@ -0,0 +1,280 @@
+//! Parallel absolute (i.e. gray-code) decoder.
+
+use num_traits::{SaturatingAdd, SaturatingMul, Zero};
+
+use crate::{validator::GrayValidator, AbsoluteError, Change};
+
+/// The coding of an absolute encoder's parallel output.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Coding {
+    /// Gray-code, i.e. consecutive positions differ by a single bit.
+    #[default]
+    Gray,
+    /// Natural binary code.
+    Binary,
+}
+
+/// A decoder for parallel-output absolute rotary encoders
+/// (e.g. the Bourns EAW series) with a resolution of `bits` bits.
+///
+/// Consecutive readings are expected to differ by at most a single position
+/// (i.e. a single bit in gray-code), with anything else being reported as an error.
+/// Wraps from the last position of a turn to the first one (and vice versa)
+/// get tracked as (multi-)turns.
+///
+/// ```plain
+/// Position:  0   1   2   3   4   5   6   7
+///    Bit 2:  0   0   0   0   1   1   1   1
+///    Bit 1:  0   0   1   1   1   1   0   0
+///    Bit 0:  0   1   1   0   0   1   1   0
+/// ```
+#[derive(Debug)]
+pub struct AbsoluteGrayDecoder<T = i32> {
+    bits: u8,
+    coding: Coding,
+    validator: GrayValidator,
+    angle: Option<u16>,
+    turns: T,
+}
+
+impl<T> AbsoluteGrayDecoder<T>
+where
+    T: Copy + Zero + SaturatingAdd + SaturatingMul + From<i16>,
+{
+    /// Creates a decoder for a gray-code encoder with a resolution of `bits` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not within `1..=14`.
+    pub fn new(bits: u8) -> Self {
+        assert!(
+            (1..=14).contains(&bits),
+            "Expected resolution of 1 to 14 bits."
+        );
+        Self {
+            bits,
+            coding: Coding::default(),
+            validator: Default::default(),
+            angle: None,
+            turns: Zero::zero(),
+        }
+    }
+
+    /// Sets the coding of the encoder's parallel output.
+    pub fn with_coding(mut self, coding: Coding) -> Self {
+        self.coding = coding;
+        self
+    }
+
+    /// Returns the coding of the encoder's parallel output.
+    pub fn coding(&self) -> Coding {
+        self.coding
+    }
+
+    /// Returns the encoder's resolution, in number of bits.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// Returns the encoder's resolution, in number of positions per turn.
+    pub fn resolution(&self) -> u16 {
+        1 << self.bits
+    }
+
+    /// Updates the decoder's state based on the given parallel `reading`,
+    /// returning the direction if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid input (i.e. a positional "jump") was detected.
+    ///
+    /// Bits of `reading` beyond the encoder's resolution are ignored.
+    pub fn update(&mut self, reading: u16) -> Result<Option<Change>, AbsoluteError> {
+        let mask = self.resolution() - 1;
+        let reading = reading & mask;
+
+        let (gray, angle) = match self.coding {
+            Coding::Gray => (reading, gray_to_binary(reading.into()) as u16),
+            Coding::Binary => (binary_to_gray(reading), reading),
+        };
+
+        let validation_result = self.validator.validate(gray);
+
+        // Invalid readings re-synchronize the decoder to the new position:
+        let last_angle = self.angle.replace(angle);
+
+        validation_result?;
+
+        let Some(last_angle) = last_angle else {
+            return Ok(None);
+        };
+
+        let change = match angle.wrapping_sub(last_angle) & mask {
+            0 => return Ok(None),
+            1 => Change::Positive,
+            delta if delta == mask => Change::Negative,
+            // A single-bit change between non-adjacent positions (e.g. from 0 to 3):
+            _ => {
+                return Err(AbsoluteError::Jump {
+                    from: binary_to_gray(last_angle),
+                    to: gray,
+                })
+            }
+        };
+
+        // Wraps between the last and the first position of a turn:
+        let delta: T = match (change, angle) {
+            (Change::Positive, 0) => 1,
+            (Change::Negative, angle) if angle == mask => -1,
+            _ => 0,
+        }
+        .into();
+        self.turns = self.turns.saturating_add(&delta);
+
+        Ok(Some(change))
+    }
+
+    /// Resets the decoder to its initial state and its turns back to `0`.
+    pub fn reset(&mut self) {
+        self.validator.reset();
+        self.angle = None;
+        self.turns = Zero::zero();
+    }
+
+    /// Returns the decoder's position within the current turn,
+    /// or `None` if no reading has been observed yet.
+    pub fn angle(&self) -> Option<u16> {
+        self.angle
+    }
+
+    /// Returns the decoder's number of turns relative to its initial turn.
+    pub fn turns(&self) -> T {
+        self.turns
+    }
+
+    /// Sets the decoder's number of turns.
+    pub fn set_turns(&mut self, turns: T) {
+        self.turns = turns;
+    }
+
+    /// Returns the decoder's (multi-turn) position, in number of positions.
+    pub fn position(&self) -> T {
+        let resolution: T = (self.resolution() as i16).into();
+        let angle: T = (self.angle.unwrap_or(0) as i16).into();
+        self.turns
+            .saturating_mul(&resolution)
+            .saturating_add(&angle)
+    }
+}
+
+pub(crate) fn gray_to_binary(gray: u32) -> u32 {
+    let mut binary = gray;
+    binary ^= binary >> 16;
+    binary ^= binary >> 8;
+    binary ^= binary >> 4;
+    binary ^= binary >> 2;
+    binary ^= binary >> 1;
+    binary
+}
+
+fn binary_to_gray(binary: u16) -> u16 {
+    binary ^ (binary >> 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gray_code_round_trip() {
+        for binary in 0..=u16::MAX {
+            assert_eq!(gray_to_binary(binary_to_gray(binary).into()), binary.into());
+        }
+    }
+
+    #[test]
+    fn forwards() {
+        let mut decoder: AbsoluteGrayDecoder = AbsoluteGrayDecoder::new(4);
+
+        assert_eq!(decoder.update(binary_to_gray(14)), Ok(None));
+        assert_eq!(decoder.angle(), Some(14));
+
+        for angle in [15, 0, 1, 2] {
+            assert_eq!(
+                decoder.update(binary_to_gray(angle)),
+                Ok(Some(Change::Positive))
+            );
+        }
+
+        assert_eq!(decoder.turns(), 1);
+        assert_eq!(decoder.position(), 18);
+    }
+
+    #[test]
+    fn backwards() {
+        let mut decoder: AbsoluteGrayDecoder = AbsoluteGrayDecoder::new(8);
+
+        assert_eq!(decoder.update(binary_to_gray(1)), Ok(None));
+        assert_eq!(decoder.update(binary_to_gray(1)), Ok(None));
+
+        for angle in [0, 255, 254] {
+            assert_eq!(
+                decoder.update(binary_to_gray(angle)),
+                Ok(Some(Change::Negative))
+            );
+        }
+
+        assert_eq!(decoder.turns(), -1);
+        assert_eq!(decoder.position(), -2);
+    }
+
+    #[test]
+    fn binary() {
+        let mut decoder: AbsoluteGrayDecoder =
+            AbsoluteGrayDecoder::new(4).with_coding(Coding::Binary);
+
+        assert_eq!(decoder.update(0b_0111), Ok(None));
+        assert_eq!(decoder.update(0b_1000), Ok(Some(Change::Positive)));
+        assert_eq!(
+            decoder.update(0b_1010),
+            Err(AbsoluteError::Jump {
+                from: binary_to_gray(0b_1000),
+                to: binary_to_gray(0b_1010),
+            })
+        );
+    }
+
+    #[test]
+    fn jump() {
+        let mut decoder: AbsoluteGrayDecoder = AbsoluteGrayDecoder::new(12);
+
+        assert_eq!(decoder.update(0b_0000), Ok(None));
+        assert_eq!(
+            decoder.update(0b_0011),
+            Err(AbsoluteError::Jump {
+                from: 0b_0000,
+                to: 0b_0011
+            })
+        );
+
+        assert_eq!(decoder.angle(), Some(2));
+        assert_eq!(decoder.update(0b_0010), Ok(Some(Change::Positive)));
+    }
+
+    #[test]
+    fn non_adjacent_jump() {
+        let mut decoder: AbsoluteGrayDecoder = AbsoluteGrayDecoder::new(4);
+
+        assert_eq!(decoder.update(0b_0000), Ok(None));
+        // A single bit flips, yet the position jumps from 0 to 3:
+        assert_eq!(
+            decoder.update(0b_0010),
+            Err(AbsoluteError::Jump {
+                from: 0b_0000,
+                to: 0b_0010
+            })
+        );
+
+        assert_eq!(decoder.angle(), Some(3));
+        assert_eq!(decoder.turns(), 0);
+        assert_eq!(decoder.update(0b_0011), Ok(Some(Change::Negative)));
+        assert_eq!(decoder.position(), 2);
+    }
+}
@@ -0,0 +1,313 @@
+//! BiSS-C absolute decoder.
+
+use num_traits::{SaturatingAdd, Zero};
+
+use crate::{decoder::multi_turn::MultiTurn, BissError, Change};
+
+/// The contents of a valid BiSS-C frame.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BissFrame {
+    /// The control data bit (CDS) sent by the slave.
+    pub cds: bool,
+    /// The (binary) position data.
+    pub data: u32,
+    /// `true` if the slave reported a warning (i.e. `nW` being low).
+    pub warning: bool,
+}
+
+/// A decoder for the frames of BiSS-C (point-to-point) absolute encoders.
+///
+/// A frame consists of the following bits, transmitted MSB first:
+///
+/// ```plain
+/// │ idle │ ack │ S │ CDS │ data (MSB … LSB) │ nE │ nW │ CRC │
+/// └──────┴─────┴───┴─────┴──────────────────┴────┴────┴─────┘
+/// ```
+///
+/// - `idle`: the idle (high) bits, until the slave answers the master's first clock cycles.
+/// - `ack`: the slave's acknowledge (low), of variable length (i.e. line delay and processing time).
+/// - `S`: the start bit (high).
+/// - `CDS`: the control data bit sent by the slave.
+/// - `data`: the encoder's position (in binary), MSB first.
+/// - `nE`: the (inverted) error bit, low indicating an error of the encoder.
+/// - `nW`: the (inverted) warning bit, low indicating a warning of the encoder.
+/// - `CRC`: the (inverted) 6-bit CRC over the data, `nE` and `nW` bits.
+///
+/// The position gets unwrapped into a multi-turn position,
+/// assuming that the encoder moves by less than half a turn per frame.
+#[derive(Debug)]
+pub struct BissDecoder<T = i32> {
+    bits: u8,
+    polynomial: u8,
+    frame: Option<BissFrame>,
+    multi_turn: MultiTurn<T>,
+}
+
+impl<T> BissDecoder<T>
+where
+    T: Copy + Zero + SaturatingAdd + From<i32>,
+{
+    /// The default CRC polynomial (i.e. `x^6 + x^1 + x^0`).
+    pub const DEFAULT_POLYNOMIAL: u8 = 0x43;
+
+    /// Creates a decoder for an encoder with a resolution of `bits` bits,
+    /// using the default CRC polynomial.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not within `1..=31`.
+    pub fn new(bits: u8) -> Self {
+        assert!(
+            (1..=31).contains(&bits),
+            "Expected resolution of 1 to 31 bits."
+        );
+        Self {
+            bits,
+            polynomial: Self::DEFAULT_POLYNOMIAL,
+            frame: None,
+            multi_turn: MultiTurn::new(1 << bits),
+        }
+    }
+
+    /// Sets the CRC polynomial, including its leading `x^6` term (e.g. `0x43` for `x^6 + x^1 + x^0`).
+    pub fn with_crc_polynomial(mut self, polynomial: u8) -> Self {
+        self.polynomial = polynomial;
+        self
+    }
+
+    /// Returns the CRC polynomial, including its leading `x^6` term.
+    pub fn crc_polynomial(&self) -> u8 {
+        self.polynomial
+    }
+
+    /// Returns the encoder's resolution, in number of bits.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// Returns the encoder's resolution, in number of positions per turn.
+    pub fn resolution(&self) -> u32 {
+        1 << self.bits
+    }
+
+    /// Returns the number of bits per frame from the start bit up to (and including) the CRC,
+    /// i.e. excluding the (variable-length) acknowledge.
+    pub fn frame_bits(&self) -> u8 {
+        1 + 1 + self.bits + 2 + 6
+    }
+
+    /// Updates the decoder's state based on the bits received from the slave
+    /// (MSB first, starting at or before the acknowledge), returning the direction
+    /// if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid frame was detected.
+    pub fn update(&mut self, bytes: &[u8]) -> Result<Option<Change>, BissError> {
+        let frame = self.parse(bytes)?;
+
+        self.frame = Some(frame);
+
+        Ok(self.multi_turn.update(frame.data))
+    }
+
+    fn parse(&self, bytes: &[u8]) -> Result<BissFrame, BissError> {
+        let mut bits = Bits::new(bytes);
+
+        // Skip the idle (high) bits, up to the acknowledge (low):
+        if !bits.by_ref().any(|bit| !bit) {
+            return Err(BissError::Ack);
+        }
+
+        // Skip the acknowledge (low) bits, up to the start bit (high):
+        if !bits.by_ref().any(|bit| bit) {
+            return Err(BissError::Start);
+        }
+
+        let cds = bits.read(1).ok_or(BissError::Truncated)? != 0;
+        let data = bits.read(self.bits).ok_or(BissError::Truncated)?;
+        let status = bits.read(2).ok_or(BissError::Truncated)?;
+        let crc = bits.read(6).ok_or(BissError::Truncated)?;
+
+        let covered = (data << 2) | status;
+        let expected_crc = !crc6(covered, self.bits + 2, self.polynomial) & 0x3F;
+
+        if crc != expected_crc {
+            return Err(BissError::Crc);
+        }
+
+        if status & 0b_10 == 0 {
+            return Err(BissError::Encoder);
+        }
+
+        Ok(BissFrame {
+            cds,
+            data: data as u32,
+            warning: status & 0b_01 == 0,
+        })
+    }
+
+    /// Resets the decoder to its initial state and its position back to `0`.
+    pub fn reset(&mut self) {
+        self.frame = None;
+        self.multi_turn.reset();
+    }
+
+    /// Returns the most recent valid frame, if any.
+    pub fn frame(&self) -> Option<BissFrame> {
+        self.frame
+    }
+
+    /// Returns the most recently decoded absolute angle (in the range of `0..resolution`),
+    /// or `None` if no valid frame has been decoded yet.
+    pub fn angle(&self) -> Option<u32> {
+        self.multi_turn.angle()
+    }
+
+    /// Returns the decoder's (multi-turn) position, in number of positions.
+    ///
+    /// The position gets initialized with the angle of the first valid frame.
+    pub fn position(&self) -> T {
+        self.multi_turn.position()
+    }
+
+    /// Sets the decoder's (multi-turn) position, in number of positions.
+    pub fn set_position(&mut self, position: T) {
+        self.multi_turn.set_position(position);
+    }
+}
+
+/// An iterator over the bits of a byte slice, MSB first.
+struct Bits<'a> {
+    bytes: &'a [u8],
+    index: usize,
+}
+
+impl<'a> Bits<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, index: 0 }
+    }
+
+    /// Returns the next `count` bits (MSB first), or `None` if there are not enough bits left.
+    fn read(&mut self, count: u8) -> Option<u64> {
+        (0..count).try_fold(0_u64, |value, _| {
+            let bit = self.next()?;
+            Some((value << 1) | u64::from(bit))
+        })
+    }
+}
+
+impl Iterator for Bits<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = self.bytes.get(self.index / 8)?;
+        let bit = (byte >> (7 - (self.index % 8))) & 1 != 0;
+        self.index += 1;
+        Some(bit)
+    }
+}
+
+/// Returns the (non-inverted) 6-bit CRC of the lowest `bits` bits of `value` (MSB first).
+fn crc6(value: u64, bits: u8, polynomial: u8) -> u64 {
+    let polynomial = u64::from(polynomial) & 0x3F;
+
+    (0..bits).rev().fold(0, |crc, index| {
+        let bit = (value >> index) & 1;
+        let feedback = ((crc >> 5) & 1) ^ bit;
+        let crc = (crc << 1) & 0x3F;
+        match feedback {
+            0 => crc,
+            _ => crc ^ polynomial,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the 6-bit remainder of the polynomial division of `value * x^6` by `polynomial`.
+    fn crc6_reference(value: u64, bits: u8, polynomial: u8) -> u64 {
+        let polynomial = u64::from(polynomial) | 0x40;
+        let mut remainder = value << 6;
+        for index in (6..(bits as u32 + 6)).rev() {
+            if remainder & (1 << index) != 0 {
+                remainder ^= polynomial << (index - 6);
+            }
+        }
+        remainder & 0x3F
+    }
+
+    /// Returns the bytes of a frame with the given number of idle and acknowledge bits.
+    fn frame(data: u64, bits: u8, status: u64, idle: u8, ack: u8) -> Vec<u8> {
+        let covered = (data << 2) | status;
+        let crc = !crc6_reference(covered, bits + 2, BissDecoder::<i32>::DEFAULT_POLYNOMIAL) & 0x3F;
+
+        let mut stream: Vec<bool> = vec![];
+        stream.extend((0..idle).map(|_| true));
+        stream.extend((0..ack).map(|_| false));
+        stream.push(true); // start
+        stream.push(false); // CDS
+        stream.extend((0..bits).rev().map(|index| (data >> index) & 1 != 0));
+        stream.extend((0..2).rev().map(|index| (status >> index) & 1 != 0));
+        stream.extend((0..6).rev().map(|index| (crc >> index) & 1 != 0));
+
+        stream
+            .chunks(8)
+            .map(|chunk| {
+                chunk.iter().enumerate().fold(0, |byte, (index, &bit)| {
+                    byte | (u8::from(bit) << (7 - index))
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn crc() {
+        for value in [0, 1, 0x1234, 0x3FFFF, 0xABCDE] {
+            for polynomial in [0x43, 0x61] {
+                assert_eq!(
+                    crc6(value, 20, polynomial),
+                    crc6_reference(value, 20, polynomial)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn valid() {
+        let mut decoder: BissDecoder = BissDecoder::new(18);
+
+        assert_eq!(decoder.update(&frame(1000, 18, 0b_11, 2, 3)), Ok(None));
+        assert_eq!(decoder.angle(), Some(1000));
+        assert_eq!(
+            decoder.update(&frame(1001, 18, 0b_10, 1, 5)),
+            Ok(Some(Change::Positive))
+        );
+        assert_eq!(
+            decoder.frame(),
+            Some(BissFrame {
+                cds: false,
+                data: 1001,
+                warning: true
+            })
+        );
+        assert_eq!(decoder.position(), 1001);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut decoder: BissDecoder = BissDecoder::new(18);
+
+        assert_eq!(decoder.update(&[0xFF, 0xFF]), Err(BissError::Ack));
+        assert_eq!(decoder.update(&[0xF0, 0x00]), Err(BissError::Start));
+        assert_eq!(decoder.update(&[0xF8, 0x10]), Err(BissError::Truncated));
+
+        let mut bytes = frame(1000, 18, 0b_11, 2, 3);
+        bytes[2] ^= 0b_0001_0000;
+        assert_eq!(decoder.update(&bytes), Err(BissError::Crc));
+
+        assert_eq!(
+            decoder.update(&frame(1000, 18, 0b_01, 2, 3)),
+            Err(BissError::Encoder)
+        );
+    }
+}
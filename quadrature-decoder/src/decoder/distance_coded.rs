@@ -0,0 +1,515 @@
+//! Distance-coded incremental decoder.
+
+use num_traits::{Bounded, One, SaturatingAdd, Zero};
+
+use crate::{
+    index_decoder::{counter_from, IndexDecoder},
+    state_transducer::Input,
+    Change, DistanceCodedError, Error, IncrementalDecoder, IndexEdge, IndexQualifier,
+    ReferenceError, StepMode,
+};
+
+/// A quadrature decoder for scales with distance-coded reference marks (z channel),
+/// establishing the absolute position after traversing any two adjacent reference marks,
+/// in either direction, without the need for a homing move to a single reference mark.
+///
+/// Fixed reference marks are spaced one nominal increment `N` apart, with a coded
+/// reference mark between each pair, whose distance to its preceding fixed reference mark
+/// grows by one basic spacing `G` per nominal increment:
+///
+/// ```plain
+/// Marks:  │         │              │         │               │
+///         0      N/2 + G           N     N + N/2 + 2G        2N
+/// Spacing: ◀ N/2+G ▶ ◀── N/2-G ───▶ ◀ N/2+2G ▶ ◀─── N/2-2G ───▶
+/// ```
+///
+/// Both `N` and `G` are given in number of changes (i.e. in the decoder's step-mode),
+/// e.g. `N = 4000` and `G = 4` for a scale with a nominal increment of 1000 signal periods
+/// and a basic spacing of 1 signal period, decoded in quad-step mode.
+///
+/// By default reference marks are detected at their directional edge (see `IndexEdge::Directional`),
+/// making their positions independent of the direction of travel.
+///
+/// Until the absolute position is established the decoder's counter is relative to its initial counter,
+/// afterwards it reports the absolute position (with position `0` being the scale's first reference mark).
+/// Reference marks traversed after establishing the absolute position get checked against it,
+/// reporting `ReferenceError::Mark` on mismatch.
+#[derive(Debug)]
+pub struct DistanceCodedDecoder<Mode, T = i32> {
+    decoder: IncrementalDecoder<Mode, T>,
+    indexer: IndexDecoder,
+    samples: u32,
+    nominal_increment: u32,
+    basic_spacing: u32,
+    tolerance: u32,
+    travel: i32,
+    mark: Option<i32>,
+    offset: Option<i32>,
+}
+
+impl<Mode, T> DistanceCodedDecoder<Mode, T>
+where
+    Mode: StepMode,
+    T: Zero,
+    IncrementalDecoder<Mode, T>: Default,
+{
+    /// Creates a decoder for a scale with the given `nominal_increment` (i.e. the distance
+    /// between fixed reference marks) and `basic_spacing` (i.e. the per-increment growth
+    /// of the coded distances), both in number of changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nominal_increment` is odd, if `basic_spacing` is `0`,
+    /// or if `basic_spacing` is not less than half the `nominal_increment`.
+    pub fn new(nominal_increment: u32, basic_spacing: u32) -> Self {
+        assert!(
+            nominal_increment % 2 == 0,
+            "Expected even nominal increment."
+        );
+        assert!(
+            basic_spacing > 0 && basic_spacing < nominal_increment / 2,
+            "Expected basic spacing within `1..nominal_increment / 2`."
+        );
+        Self {
+            decoder: Default::default(),
+            indexer: IndexDecoder::new(IndexQualifier::new().with_edge(IndexEdge::Directional)),
+            samples: 0,
+            nominal_increment,
+            basic_spacing,
+            tolerance: 0,
+            travel: 0,
+            mark: None,
+            offset: None,
+        }
+    }
+}
+
+impl<Mode, T> DistanceCodedDecoder<Mode, T>
+where
+    Mode: StepMode,
+    T: Copy + Zero + One + SaturatingAdd + Bounded + From<i8> + TryFrom<i32>,
+{
+    /// Sets the tolerance (in number of changes) for the positions of reference marks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tolerance` is not less than half the basic spacing.
+    pub fn with_tolerance(mut self, tolerance: u32) -> Self {
+        assert!(
+            tolerance.saturating_mul(2) < self.basic_spacing,
+            "Expected tolerance below half the basic spacing."
+        );
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the qualification criteria for accepting reference marks (z channel),
+    /// with pulse widths being measured in number of updates.
+    ///
+    /// As leading edges would make the marks' positions direction-dependent,
+    /// a qualifier's (default) `IndexEdge::Leading` edge gets replaced by `IndexEdge::Directional`.
+    pub fn with_index_qualifier(mut self, qualifier: IndexQualifier) -> Self {
+        let qualifier = match qualifier.edge() {
+            IndexEdge::Leading => qualifier.with_edge(IndexEdge::Directional),
+            _ => qualifier,
+        };
+        self.indexer = IndexDecoder::new(qualifier);
+        self
+    }
+
+    /// Returns the qualification criteria for accepting reference marks (z channel).
+    pub fn index_qualifier(&self) -> IndexQualifier {
+        self.indexer.qualifier()
+    }
+
+    /// Returns the nominal increment (i.e. the distance between fixed reference marks), in number of changes.
+    pub fn nominal_increment(&self) -> u32 {
+        self.nominal_increment
+    }
+
+    /// Returns the basic spacing (i.e. the per-increment growth of the coded distances), in number of changes.
+    pub fn basic_spacing(&self) -> u32 {
+        self.basic_spacing
+    }
+
+    /// Returns the tolerance (in number of changes) for the positions of reference marks.
+    pub fn tolerance(&self) -> u32 {
+        self.tolerance
+    }
+
+    /// Updates the decoder's state based on the given `a`, `b` and `z` pulse train (aka channel) readings,
+    /// returning the direction if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid input was detected.
+    ///
+    /// Upon detection of the second of two adjacent reference marks on the `z` pulse train
+    /// the counter gets set to the absolute position, with pulse widths being measured in number of updates.
+    pub fn update(
+        &mut self,
+        a: bool,
+        b: bool,
+        z: bool,
+    ) -> Result<Option<Change>, DistanceCodedError> {
+        let result = self.decoder.update(a, b);
+
+        let now = self.samples;
+        self.samples = self.samples.wrapping_add(1);
+
+        self.update_index(a, b, z, result, now)
+    }
+
+    /// Updates the decoder's state based on the given `a`, `b` and `z` pulse train (aka channel) readings,
+    /// sampled at the given `timestamp` (in arbitrary, but monotonic units, e.g. microseconds).
+    ///
+    /// Behaves exactly like `decoder.update(a, b, z)`, but with pulse widths being measured
+    /// in the units of the timestamps.
+    pub fn update_at(
+        &mut self,
+        a: bool,
+        b: bool,
+        z: bool,
+        timestamp: u32,
+    ) -> Result<Option<Change>, DistanceCodedError> {
+        let result = self.decoder.update_at(a, b, timestamp);
+
+        self.update_index(a, b, z, result, timestamp)
+    }
+
+    fn update_index(
+        &mut self,
+        a: bool,
+        b: bool,
+        z: bool,
+        result: Result<Option<Change>, Error>,
+        now: u32,
+    ) -> Result<Option<Change>, DistanceCodedError> {
+        let change = result.unwrap_or_default();
+
+        if let Some(change) = change {
+            self.travel = self.travel.saturating_add(i32::from(change as i8));
+        }
+
+        let reference = match self.indexer.update(z, Input::new(a, b), change, now) {
+            Some(event) => self.update_mark(self.travel.saturating_sub(event), event),
+            None => Ok(()),
+        };
+
+        let change = result.map_err(DistanceCodedError::Quadrature)?;
+        reference.map_err(DistanceCodedError::Reference)?;
+
+        Ok(change)
+    }
+
+    /// Handles a reference mark at relative position `mark`, traversed `event` changes ago.
+    fn update_mark(&mut self, mark: i32, event: i32) -> Result<(), ReferenceError> {
+        if let Some(offset) = self.offset {
+            let position = i64::from(mark) + i64::from(offset);
+            return match self.is_mark(position) {
+                true => Ok(()),
+                false => Err(ReferenceError::Mark {
+                    position: position as i32,
+                }),
+            };
+        }
+
+        let Some(previous) = self.mark.replace(mark) else {
+            return Ok(());
+        };
+
+        let distance = i64::from(mark) - i64::from(previous);
+
+        // The same reference mark, traversed again after a reversal:
+        if distance.unsigned_abs() <= u64::from(self.tolerance) {
+            return Ok(());
+        }
+
+        let Some((lower, upper)) = self.adjacent_marks(distance.unsigned_abs()) else {
+            return Err(ReferenceError::Spacing {
+                distance: distance.unsigned_abs() as u32,
+            });
+        };
+
+        // The absolute position of the current reference mark:
+        let position = match distance > 0 {
+            true => upper,
+            false => lower,
+        };
+
+        let offset = (position - i64::from(mark)) as i32;
+        self.offset = Some(offset);
+        self.decoder.set_counter(counter_from(
+            position.saturating_add(i64::from(event)) as i32
+        ));
+
+        Ok(())
+    }
+
+    /// Returns the absolute positions of the (lower, upper) pair of adjacent reference marks
+    /// with the given `distance`, or `None` if it does not match any coded distance.
+    fn adjacent_marks(&self, distance: u64) -> Option<(i64, i64)> {
+        let nominal = i64::from(self.nominal_increment);
+        let half = nominal / 2;
+        let spacing = i64::from(self.basic_spacing);
+
+        let delta = distance as i64 - half;
+        let steps = (delta.abs() + spacing / 2) / spacing;
+
+        if steps == 0 || steps * spacing >= half {
+            return None;
+        }
+
+        if (delta.abs() - steps * spacing).unsigned_abs() > u64::from(self.tolerance) {
+            return None;
+        }
+
+        let fixed = (steps - 1) * nominal;
+        let coded = fixed + half + steps * spacing;
+
+        match delta > 0 {
+            // A fixed reference mark, followed by a coded one:
+            true => Some((fixed, coded)),
+            // A coded reference mark, followed by a fixed one:
+            false => Some((coded, fixed + nominal)),
+        }
+    }
+
+    /// Returns `true` if there is a reference mark at the absolute `position`
+    /// (within tolerance), otherwise `false`.
+    fn is_mark(&self, position: i64) -> bool {
+        let nominal = i64::from(self.nominal_increment);
+        let tolerance = i64::from(self.tolerance);
+        let increment = position.div_euclid(nominal);
+        let remainder = position.rem_euclid(nominal);
+
+        let coded = nominal / 2 + (increment + 1) * i64::from(self.basic_spacing);
+
+        if increment < 0 && nominal - remainder > tolerance {
+            return false;
+        }
+
+        remainder <= tolerance
+            || nominal - remainder <= tolerance
+            || (remainder - coded).abs() <= tolerance
+    }
+
+    /// Resets the decoder to its initial state, discarding the absolute position,
+    /// and its counter back to `0`.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+        self.indexer.reset();
+        self.samples = 0;
+        self.travel = 0;
+        self.mark = None;
+        self.offset = None;
+    }
+
+    /// Returns `true` if the absolute position has been established, otherwise `false`.
+    pub fn is_referenced(&self) -> bool {
+        self.offset.is_some()
+    }
+
+    /// Returns the absolute position of the decoder's initial counter
+    /// (i.e. the offset between relative and absolute positions) in number of changes,
+    /// or `None` if the absolute position has not been established yet.
+    pub fn offset(&self) -> Option<i32> {
+        self.offset
+    }
+
+    /// Returns the decoder's counter in number of changes, relative to its initial counter
+    /// until the absolute position has been established, and absolute afterwards.
+    pub fn counter(&self) -> T {
+        self.decoder.counter()
+    }
+
+    /// Sets the decoder's counter.
+    pub fn set_counter(&mut self, counter: T) {
+        self.decoder.set_counter(counter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::QuadStep;
+
+    use super::*;
+
+    type Decoder = DistanceCodedDecoder<QuadStep>;
+
+    /// The nominal increment of the test scale.
+    const NOMINAL: u32 = 40;
+    /// The basic spacing of the test scale.
+    const SPACING: u32 = 2;
+
+    /// The absolute positions of the test scale's reference marks.
+    const MARKS: [i32; 6] = [0, 22, 40, 64, 80, 106];
+
+    /// The quadrature readings (`a`, `b`) in forwards order.
+    const QUADRATURE: [(bool, bool); 4] =
+        [(true, true), (false, true), (false, false), (true, false)];
+
+    /// Moves the decoder from absolute position `from` to `to` (one change per update),
+    /// with each of the reference `marks` spanning a full quadrature cycle.
+    fn travel(
+        decoder: &mut Decoder,
+        marks: &[i32],
+        from: i32,
+        to: i32,
+    ) -> Vec<Result<Option<Change>, DistanceCodedError>> {
+        let positions: Vec<i32> = match from.cmp(&to) {
+            core::cmp::Ordering::Less => (from + 1..=to).collect(),
+            core::cmp::Ordering::Equal => vec![from],
+            core::cmp::Ordering::Greater => (to..from).rev().collect(),
+        };
+
+        positions
+            .into_iter()
+            .map(|position| {
+                let (a, b) = QUADRATURE[position.rem_euclid(4) as usize];
+                let z = marks
+                    .iter()
+                    .any(|mark| (*mark..*mark + 4).contains(&position));
+                decoder.update(a, b, z)
+            })
+            .collect()
+    }
+
+    fn errors(results: &[Result<Option<Change>, DistanceCodedError>]) -> Vec<DistanceCodedError> {
+        results.iter().filter_map(|result| result.err()).collect()
+    }
+
+    #[test]
+    fn forwards() {
+        let mut decoder = Decoder::new(NOMINAL, SPACING);
+        travel(&mut decoder, &MARKS, 30, 30);
+
+        let results = travel(&mut decoder, &MARKS, 30, 60);
+        assert_eq!(errors(&results), vec![]);
+        assert!(!decoder.is_referenced());
+        assert_eq!(decoder.counter(), 30);
+
+        let results = travel(&mut decoder, &MARKS, 60, 70);
+        assert_eq!(errors(&results), vec![]);
+        assert!(decoder.is_referenced());
+        assert_eq!(decoder.offset(), Some(30));
+        assert_eq!(decoder.counter(), 70);
+    }
+
+    #[test]
+    fn backwards() {
+        let mut decoder = Decoder::new(NOMINAL, SPACING);
+        travel(&mut decoder, &MARKS, 100, 100);
+
+        let results = travel(&mut decoder, &MARKS, 100, 30);
+
+        assert_eq!(errors(&results), vec![]);
+        assert!(decoder.is_referenced());
+        assert_eq!(decoder.offset(), Some(100));
+        assert_eq!(decoder.counter(), 30);
+    }
+
+    #[test]
+    fn index_qualifier() {
+        let qualifier = IndexQualifier::new().with_gate(true, true);
+        let mut decoder = Decoder::new(NOMINAL, SPACING).with_index_qualifier(qualifier);
+        assert_eq!(decoder.index_qualifier().edge(), IndexEdge::Directional);
+        assert_eq!(decoder.index_qualifier().gate(), Some((true, true)));
+
+        travel(&mut decoder, &MARKS, 100, 100);
+        let results = travel(&mut decoder, &MARKS, 100, 30);
+
+        assert_eq!(errors(&results), vec![]);
+        assert_eq!(decoder.offset(), Some(100));
+        assert_eq!(decoder.counter(), 30);
+
+        let qualifier = IndexQualifier::new().with_edge(IndexEdge::Center);
+        let decoder = Decoder::new(NOMINAL, SPACING).with_index_qualifier(qualifier);
+        assert_eq!(decoder.index_qualifier().edge(), IndexEdge::Center);
+    }
+
+    #[test]
+    fn reversal_at_mark() {
+        let mut decoder = Decoder::new(NOMINAL, SPACING);
+        travel(&mut decoder, &MARKS, 30, 30);
+
+        // Crossing the same reference mark back and forth:
+        travel(&mut decoder, &MARKS, 30, 45);
+        travel(&mut decoder, &MARKS, 45, 35);
+        assert!(!decoder.is_referenced());
+
+        let results = travel(&mut decoder, &MARKS, 35, 70);
+        assert_eq!(errors(&results), vec![]);
+        assert!(decoder.is_referenced());
+        assert_eq!(decoder.counter(), 70);
+    }
+
+    #[test]
+    fn inconsistent_spacing() {
+        let mut decoder = Decoder::new(NOMINAL, SPACING);
+        travel(&mut decoder, &MARKS, 30, 30);
+
+        let results = travel(&mut decoder, &[35, 46], 30, 60);
+
+        assert_eq!(
+            errors(&results),
+            vec![DistanceCodedError::Reference(ReferenceError::Spacing {
+                distance: 11
+            })]
+        );
+        assert!(!decoder.is_referenced());
+    }
+
+    #[test]
+    fn inconsistent_mark() {
+        let mut decoder = Decoder::new(NOMINAL, SPACING);
+        travel(&mut decoder, &MARKS, 30, 30);
+        travel(&mut decoder, &MARKS, 30, 70);
+        assert!(decoder.is_referenced());
+
+        // A spurious reference mark:
+        let results = travel(&mut decoder, &[72], 70, 78);
+
+        assert_eq!(
+            errors(&results),
+            vec![DistanceCodedError::Reference(ReferenceError::Mark {
+                position: 72
+            })]
+        );
+        assert_eq!(decoder.counter(), 78);
+    }
+
+    #[test]
+    fn tolerance() {
+        // Reference marks at `0, 24, 40, 68, …`, with the second one off by a single change:
+        let marks = [40, 69];
+
+        let mut decoder = Decoder::new(NOMINAL, 4);
+        travel(&mut decoder, &marks, 30, 30);
+        let results = travel(&mut decoder, &marks, 30, 75);
+        assert_eq!(errors(&results).len(), 1);
+        assert!(!decoder.is_referenced());
+
+        let mut decoder = Decoder::new(NOMINAL, 4).with_tolerance(1);
+        travel(&mut decoder, &marks, 30, 30);
+        let results = travel(&mut decoder, &marks, 30, 75);
+        assert_eq!(errors(&results), vec![]);
+        assert!(decoder.is_referenced());
+        assert_eq!(decoder.counter(), 74);
+    }
+
+    #[test]
+    fn coded_distances() {
+        let decoder = Decoder::new(NOMINAL, SPACING);
+
+        let marks: Vec<i64> = (-10..120)
+            .filter(|&position| decoder.is_mark(position))
+            .collect();
+        let expected: Vec<i64> = MARKS.iter().map(|&mark| mark.into()).collect();
+        assert_eq!(marks, expected);
+
+        assert_eq!(decoder.adjacent_marks(22), Some((0, 22)));
+        assert_eq!(decoder.adjacent_marks(18), Some((22, 40)));
+        assert_eq!(decoder.adjacent_marks(24), Some((40, 64)));
+        assert_eq!(decoder.adjacent_marks(16), Some((64, 80)));
+        assert_eq!(decoder.adjacent_marks(20), None);
+        assert_eq!(decoder.adjacent_marks(21), None);
+    }
+}
@@ -0,0 +1,108 @@
+//! Three-phase hall sensor (UVW) decoder.
+
+use num_traits::{SaturatingAdd, Zero};
+
+use crate::{
+    state_transducer::{
+        hall::{HallInput, HallTransducer},
+        Output,
+    },
+    Change, HallError,
+};
+
+/// A decoder for the three-phase hall sensors (U, V, W) of a brushless (BLDC) motor,
+/// spaced 120° (electrically) apart.
+///
+/// Each electrical revolution passes through six valid hall states (aka commutation sectors),
+/// with every transition between two adjacent sectors counting as a single electrical step:
+///
+/// ```plain
+/// Sector:  0   1   2   3   4   5   0   1
+///     U:   1   1   0   0   0   1   1   1
+///     V:   0   1   1   1   0   0   0   1
+///     W:   0   0   0   1   1   1   0   0
+/// ```
+///
+/// The hall states `000` and `111`, as well as jumps between non-adjacent sectors,
+/// are reported as errors.
+#[derive(Debug)]
+pub struct HallDecoder<T = i32> {
+    transducer: HallTransducer,
+    counter: T,
+}
+
+impl<T> Default for HallDecoder<T>
+where
+    T: Zero,
+{
+    fn default() -> Self {
+        Self {
+            transducer: Default::default(),
+            counter: Zero::zero(),
+        }
+    }
+}
+
+impl<T> HallDecoder<T>
+where
+    T: Copy + Zero + SaturatingAdd + From<i8>,
+{
+    /// Updates the decoder's state based on the given `u`, `v` and `w` hall sensor readings,
+    /// returning the direction if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid input (i.e. an invalid hall state or a sector "jump") was detected.
+    ///
+    /// A change of `Change::Positive` corresponds to the sector sequence `0 → 1 → … → 5 → 0`.
+    pub fn update(&mut self, u: bool, v: bool, w: bool) -> Result<Option<Change>, HallError> {
+        let input = HallInput::new(u, v, w);
+        let last_state = self.transducer.state();
+
+        match self.transducer.step(input) {
+            Output::N => Ok(None),
+            Output::AB => {
+                let change = Change::Positive;
+                let delta: T = (change as i8).into();
+                self.counter = self.counter.saturating_add(&delta);
+                Ok(Some(change))
+            }
+            Output::BA => {
+                let change = Change::Negative;
+                let delta: T = (change as i8).into();
+                self.counter = self.counter.saturating_add(&delta);
+                Ok(Some(change))
+            }
+            Output::E => match input.bits() {
+                0b_000 => Err(HallError::E000),
+                0b_111 => Err(HallError::E111),
+                to => Err(HallError::Jump {
+                    from: last_state.bits().unwrap_or(to),
+                    to,
+                }),
+            },
+        }
+    }
+
+    /// Returns the decoder's current commutation sector (in the range of `0..=5`),
+    /// or `None` if no valid hall state has been observed yet.
+    pub fn sector(&self) -> Option<u8> {
+        self.transducer.state().sector()
+    }
+
+    /// Resets the decoder to its initial state and its counter back to `0`.
+    pub fn reset(&mut self) {
+        self.transducer.reset();
+        self.counter = Zero::zero();
+    }
+
+    /// Returns the decoder's counter relative to its initial counter in number of electrical steps.
+    ///
+    /// A change of `Change::Positive` increments the counter,
+    /// while a change of `Change::Negative` decrements it.
+    pub fn counter(&self) -> T {
+        self.counter
+    }
+
+    /// Sets the decoder's counter.
+    pub fn set_counter(&mut self, counter: T) {
+        self.counter = counter;
+    }
+}
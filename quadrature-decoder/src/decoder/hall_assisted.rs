@@ -0,0 +1,412 @@
+//! Hall-assisted incremental decoder.
+
+use num_traits::{One, SaturatingAdd, Zero};
+
+use crate::{
+    index_decoder::IndexDecoder, state_transducer::Input, Change, HallAssistedError, HallDecoder,
+    IncrementalDecoder, IndexQualifier, StepMode,
+};
+
+/// The alignment of a hall-assisted decoder's electrical angle.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub enum Alignment {
+    /// No valid hall state has been observed yet, i.e. the electrical angle is unknown.
+    #[default]
+    Unaligned,
+    /// The electrical angle has been initialized from the hall state
+    /// to the center of its commutation sector (i.e. within ±30° electrically).
+    Coarse,
+    /// The electrical angle has been aligned at a hall edge or an index pulse.
+    Fine,
+}
+
+/// A quadrature decoder for brushless (BLDC) motors, combining the motor's three-phase
+/// hall sensors (U, V, W) with an incremental encoder, allowing for commutation
+/// right from power-on without a homing move.
+///
+/// - Upon the first valid hall state the electrical angle gets initialized
+///   to the center of the hall state's commutation sector (i.e. `Alignment::Coarse`).
+/// - From then on the electrical angle gets tracked via the incremental encoder's changes.
+/// - Upon the first hall edge (i.e. commutation sector boundary) or index pulse
+///   the electrical angle gets re-aligned (i.e. `Alignment::Fine`),
+///   with further index pulses re-aligning it as well.
+///
+/// ```plain
+/// Sector:     │   0   │   1   │   2   │   3   │   4   │   5   │
+/// Angle:      ◀───────────────── counts per revolution ─────────▶
+///             ▲ hall offset
+/// ```
+///
+/// The hall sensors and the incremental encoder are expected to agree on their direction,
+/// i.e. `Change::Positive` corresponding to the sector sequence `0 → 1 → … → 5 → 0`.
+#[derive(Debug)]
+pub struct HallAssistedDecoder<Mode, T = i32> {
+    decoder: IncrementalDecoder<Mode, T>,
+    hall: HallDecoder<T>,
+    indexer: IndexDecoder,
+    samples: u32,
+    counts: u32,
+    hall_offset: u32,
+    index_angle: Option<u32>,
+    angle: Option<u32>,
+    alignment: Alignment,
+}
+
+impl<Mode, T> HallAssistedDecoder<Mode, T>
+where
+    Mode: StepMode,
+    T: Zero,
+    IncrementalDecoder<Mode, T>: Default,
+{
+    /// Creates a decoder for an incremental encoder with `counts` changes per electrical revolution
+    /// (i.e. per mechanical revolution, divided by the motor's number of pole pairs).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counts` is less than `6`.
+    pub fn new(counts: u32) -> Self {
+        assert!(counts >= 6, "Expected at least 6 counts per revolution.");
+        Self {
+            decoder: Default::default(),
+            hall: Default::default(),
+            indexer: Default::default(),
+            samples: 0,
+            counts,
+            hall_offset: 0,
+            index_angle: None,
+            angle: None,
+            alignment: Alignment::default(),
+        }
+    }
+}
+
+impl<Mode, T> HallAssistedDecoder<Mode, T>
+where
+    Mode: StepMode,
+    T: Copy + Zero + One + SaturatingAdd + From<i8>,
+{
+    /// Sets the electrical angle (in number of counts) at which commutation sector `0` begins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hall_offset` is not within `0..counts`.
+    pub fn with_hall_offset(mut self, hall_offset: u32) -> Self {
+        assert!(
+            hall_offset < self.counts,
+            "Expected offset within `0..counts`."
+        );
+        self.hall_offset = hall_offset;
+        self
+    }
+
+    /// Sets the electrical angle (in number of counts) at which the index pulse's rising edge occurs,
+    /// enabling re-alignment via `decoder.update_indexed(a, b, z, u, v, w)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index_angle` is not within `0..counts`.
+    pub fn with_index_angle(mut self, index_angle: u32) -> Self {
+        assert!(
+            index_angle < self.counts,
+            "Expected angle within `0..counts`."
+        );
+        self.index_angle = Some(index_angle);
+        self
+    }
+
+    /// Sets the qualification criteria for accepting index pulses (z channel),
+    /// with pulse widths being measured in number of updates.
+    pub fn with_index_qualifier(mut self, qualifier: IndexQualifier) -> Self {
+        self.indexer = IndexDecoder::new(qualifier);
+        self
+    }
+
+    /// Returns the number of counts per electrical revolution.
+    pub fn counts(&self) -> u32 {
+        self.counts
+    }
+
+    /// Returns the electrical angle (in number of counts) at which commutation sector `0` begins.
+    pub fn hall_offset(&self) -> u32 {
+        self.hall_offset
+    }
+
+    /// Returns the electrical angle (in number of counts) of the index pulse, if any.
+    pub fn index_angle(&self) -> Option<u32> {
+        self.index_angle
+    }
+
+    /// Updates the decoder's state based on the given `a` and `b` pulse train (aka channel) readings,
+    /// as well as the `u`, `v` and `w` hall sensor readings, returning the direction
+    /// if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid input was detected.
+    ///
+    /// Upon invalid hall sensor readings the `a` and `b` readings get discarded as well
+    /// (i.e. neither counted, nor tracked), to be picked up by the next valid update.
+    pub fn update(
+        &mut self,
+        a: bool,
+        b: bool,
+        u: bool,
+        v: bool,
+        w: bool,
+    ) -> Result<Option<Change>, HallAssistedError> {
+        self.decode(a, b, u, v, w)
+    }
+
+    /// Updates the decoder's state based on the given `a`, `b` and `z` pulse train (aka channel) readings,
+    /// as well as the `u`, `v` and `w` hall sensor readings, returning the direction
+    /// if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid input was detected.
+    ///
+    /// Upon detection of a (qualified) index event on the `z` pulse train the electrical angle
+    /// gets aligned to the index angle (if any).
+    ///
+    /// Upon invalid hall sensor readings the `a` and `b` readings get discarded as well
+    /// (i.e. neither counted, nor tracked), to be picked up by the next valid update.
+    pub fn update_indexed(
+        &mut self,
+        a: bool,
+        b: bool,
+        z: bool,
+        u: bool,
+        v: bool,
+        w: bool,
+    ) -> Result<Option<Change>, HallAssistedError> {
+        let result = self.decode(a, b, u, v, w);
+
+        let now = self.samples;
+        self.samples = self.samples.wrapping_add(1);
+
+        let change = result.unwrap_or(None);
+        let offset = self.indexer.update(z, Input::new(a, b), change, now);
+
+        if let (Some(offset), Some(index_angle)) = (offset, self.index_angle) {
+            let counts = self.counts as i64;
+            let angle = (i64::from(index_angle) + i64::from(offset)).rem_euclid(counts);
+            self.angle = Some(angle as u32);
+            self.alignment = Alignment::Fine;
+        }
+
+        result
+    }
+
+    fn decode(
+        &mut self,
+        a: bool,
+        b: bool,
+        u: bool,
+        v: bool,
+        w: bool,
+    ) -> Result<Option<Change>, HallAssistedError> {
+        // The hall sensors get decoded first, so that invalid readings discard the update as a whole:
+        let hall_change = self.hall.update(u, v, w).map_err(HallAssistedError::Hall)?;
+
+        let result = self.decoder.update(a, b);
+
+        if let Ok(Some(change)) = result {
+            self.step(change);
+        }
+
+        self.align(hall_change);
+
+        result.map_err(HallAssistedError::Quadrature)
+    }
+
+    fn step(&mut self, change: Change) {
+        let counts = self.counts;
+        self.angle = self.angle.map(|angle| match change {
+            Change::Positive => (angle + 1) % counts,
+            Change::Negative => (angle + counts - 1) % counts,
+        });
+    }
+
+    fn align(&mut self, change: Option<Change>) {
+        let Some(sector) = self.hall.sector() else {
+            return;
+        };
+
+        match (self.alignment, change) {
+            (Alignment::Unaligned, _) => {
+                // The center of the sector:
+                self.angle = Some(self.sector_angle(2 * u32::from(sector) + 1, 12));
+                self.alignment = Alignment::Coarse;
+            }
+            (Alignment::Coarse, Some(Change::Positive)) => {
+                // The sector's lower boundary, just crossed:
+                self.angle = Some(self.sector_angle(u32::from(sector), 6));
+                self.alignment = Alignment::Fine;
+            }
+            (Alignment::Coarse, Some(Change::Negative)) => {
+                // The sector's upper boundary, just crossed:
+                let boundary = self.sector_angle(u32::from(sector) + 1, 6);
+                self.angle = Some((boundary + self.counts - 1) % self.counts);
+                self.alignment = Alignment::Fine;
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the electrical angle at `numerator / denominator` of a revolution,
+    /// relative to the hall offset.
+    fn sector_angle(&self, numerator: u32, denominator: u32) -> u32 {
+        let counts = u64::from(self.counts);
+        let angle = u64::from(numerator) * counts / u64::from(denominator);
+        ((u64::from(self.hall_offset) + angle) % counts) as u32
+    }
+
+    /// Resets the decoder to its initial (i.e. unaligned) state and its counter back to `0`.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+        self.hall.reset();
+        self.indexer.reset();
+        self.samples = 0;
+        self.angle = None;
+        self.alignment = Alignment::default();
+    }
+
+    /// Returns the alignment of the decoder's electrical angle.
+    pub fn alignment(&self) -> Alignment {
+        self.alignment
+    }
+
+    /// Returns the decoder's electrical angle (in the range of `0..counts`),
+    /// or `None` if no valid hall state has been observed yet.
+    pub fn electrical_angle(&self) -> Option<u32> {
+        self.angle
+    }
+
+    /// Returns the hall sensors' current commutation sector (in the range of `0..=5`),
+    /// or `None` if no valid hall state has been observed yet.
+    pub fn sector(&self) -> Option<u8> {
+        self.hall.sector()
+    }
+
+    /// Returns the decoder's counter relative to its initial counter in number of changes.
+    pub fn counter(&self) -> T {
+        self.decoder.counter()
+    }
+
+    /// Sets the decoder's counter.
+    pub fn set_counter(&mut self, counter: T) {
+        self.decoder.set_counter(counter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HallError, QuadStep};
+
+    use super::*;
+
+    type Decoder = HallAssistedDecoder<QuadStep>;
+
+    /// The hall readings (`u`, `v`, `w`) of each sector.
+    const HALL: [(bool, bool, bool); 6] = [
+        (true, false, false),
+        (true, true, false),
+        (false, true, false),
+        (false, true, true),
+        (false, false, true),
+        (true, false, true),
+    ];
+
+    /// The quadrature readings (`a`, `b`) in forwards order.
+    const QUADRATURE: [(bool, bool); 4] =
+        [(true, true), (false, true), (false, false), (true, false)];
+
+    fn update(
+        decoder: &mut Decoder,
+        step: usize,
+        sector: usize,
+    ) -> Result<Option<Change>, HallAssistedError> {
+        let (a, b) = QUADRATURE[step % 4];
+        let (u, v, w) = HALL[sector % 6];
+        decoder.update(a, b, u, v, w)
+    }
+
+    #[test]
+    fn coarse_then_fine() {
+        let mut decoder = Decoder::new(120).with_hall_offset(10);
+
+        assert_eq!(decoder.alignment(), Alignment::Unaligned);
+        assert_eq!(decoder.electrical_angle(), None);
+
+        // Sector 1 spans `30..50`, with its center at `40`:
+        assert_eq!(update(&mut decoder, 0, 1), Ok(None));
+        assert_eq!(decoder.alignment(), Alignment::Coarse);
+        assert_eq!(decoder.electrical_angle(), Some(40));
+
+        // Actually located at `45`, moving forwards by 4 changes:
+        for step in 1..=4 {
+            assert_eq!(update(&mut decoder, step, 1), Ok(Some(Change::Positive)));
+        }
+        assert_eq!(decoder.electrical_angle(), Some(44));
+
+        // Crossing into sector 2 at `50`:
+        assert_eq!(update(&mut decoder, 5, 2), Ok(Some(Change::Positive)));
+        assert_eq!(decoder.alignment(), Alignment::Fine);
+        assert_eq!(decoder.electrical_angle(), Some(50));
+        assert_eq!(decoder.counter(), 5);
+    }
+
+    #[test]
+    fn backwards_wrapping() {
+        let mut decoder = Decoder::new(120);
+
+        assert_eq!(update(&mut decoder, 0, 0), Ok(None));
+        assert_eq!(decoder.electrical_angle(), Some(10));
+
+        // Crossing into sector 5 at `0`:
+        assert_eq!(update(&mut decoder, 3, 5), Ok(Some(Change::Negative)));
+        assert_eq!(decoder.alignment(), Alignment::Fine);
+        assert_eq!(decoder.electrical_angle(), Some(119));
+
+        // Further hall edges do not re-align:
+        assert_eq!(update(&mut decoder, 2, 4), Ok(Some(Change::Negative)));
+        assert_eq!(decoder.electrical_angle(), Some(118));
+    }
+
+    #[test]
+    fn index() {
+        let mut decoder = Decoder::new(120).with_index_angle(100);
+
+        let (u, v, w) = HALL[3];
+        assert_eq!(decoder.update_indexed(true, true, false, u, v, w), Ok(None));
+        assert_eq!(decoder.electrical_angle(), Some(70));
+
+        assert_eq!(
+            decoder.update_indexed(false, true, true, u, v, w),
+            Ok(Some(Change::Positive))
+        );
+        assert_eq!(decoder.alignment(), Alignment::Fine);
+        assert_eq!(decoder.electrical_angle(), Some(100));
+    }
+
+    #[test]
+    fn invalid_hall_state() {
+        let mut decoder = Decoder::new(120);
+
+        assert_eq!(
+            decoder.update(true, true, false, false, false),
+            Err(HallAssistedError::Hall(HallError::E000))
+        );
+        assert_eq!(decoder.alignment(), Alignment::Unaligned);
+
+        // The quadrature readings get discarded along with the invalid hall readings:
+        assert_eq!(
+            decoder.update(false, true, false, false, false),
+            Err(HallAssistedError::Hall(HallError::E000))
+        );
+        assert_eq!(decoder.counter(), 0);
+
+        // … to be picked up by the next valid update:
+        let (u, v, w) = HALL[0];
+        assert_eq!(
+            decoder.update(false, true, u, v, w),
+            Ok(Some(Change::Positive))
+        );
+        assert_eq!(decoder.counter(), 1);
+        assert_eq!(decoder.electrical_angle(), Some(10));
+    }
+}
@@ -5,9 +5,11 @@ use core::marker::PhantomData;
 use num_traits::{One, SaturatingAdd, Zero};
 
 use crate::{
+    hysteresis::ReversalHysteresis,
     state_transducer::{Input, Output},
-    validator::InputValidator,
-    Change, Error, FullStep, HalfStep, QuadStep, StateTransducer, StepMode,
+    validator::{InputValidator, Protocol},
+    Change, Error, FullStep, HalfStep, QuadStep, SignalMonitor, StateTransducer, StepDirection,
+    StepMode, Tachometer, UpDown,
 };
 
 /// A robust quadrature decoder with support for multiple step-modes,
@@ -34,7 +36,11 @@ use crate::{
 pub struct IncrementalDecoder<Mode, T = i32> {
     transducer: StateTransducer<'static, 8, 4>,
     validator: InputValidator,
+    monitor: Option<SignalMonitor>,
+    hysteresis: ReversalHysteresis,
     counter: T,
+    raw_counter: T,
+    is_enabled: bool,
     _phantom: PhantomData<Mode>,
 }
 
@@ -43,9 +49,10 @@ where
     T: Zero,
 {
     fn default() -> Self {
-        Self::new(StateTransducer::new(
-            &crate::state_transducer::full_step::TRANSITIONS,
-        ))
+        Self::new(
+            StateTransducer::new(&crate::state_transducer::full_step::TRANSITIONS),
+            InputValidator::new(Protocol::Quadrature),
+        )
     }
 }
 
@@ -54,9 +61,10 @@ where
     T: Zero,
 {
     fn default() -> Self {
-        Self::new(StateTransducer::new(
-            &crate::state_transducer::half_step::TRANSITIONS,
-        ))
+        Self::new(
+            StateTransducer::new(&crate::state_transducer::half_step::TRANSITIONS),
+            InputValidator::new(Protocol::Quadrature),
+        )
     }
 }
 
@@ -65,9 +73,47 @@ where
     T: Zero,
 {
     fn default() -> Self {
-        Self::new(StateTransducer::new(
-            &crate::state_transducer::quad_step::TRANSITIONS,
-        ))
+        Self::new(
+            StateTransducer::new(&crate::state_transducer::quad_step::TRANSITIONS),
+            InputValidator::new(Protocol::Quadrature),
+        )
+    }
+}
+
+impl<T> Default for IncrementalDecoder<StepDirection, T>
+where
+    T: Zero,
+{
+    fn default() -> Self {
+        Self::new(
+            StateTransducer::new(&crate::state_transducer::step_direction::TRANSITIONS),
+            InputValidator::new(Protocol::StepDirection),
+        )
+    }
+}
+
+impl<T> Default for IncrementalDecoder<UpDown, T>
+where
+    T: Zero,
+{
+    fn default() -> Self {
+        Self::new(
+            StateTransducer::new(&crate::state_transducer::up_down::TRANSITIONS),
+            InputValidator::new(Protocol::UpDown),
+        )
+    }
+}
+
+impl<T> Default for IncrementalDecoder<Tachometer, T>
+where
+    T: Zero,
+{
+    fn default() -> Self {
+        // Any input sequence is valid, just like for step/direction:
+        Self::new(
+            StateTransducer::new(&crate::state_transducer::tachometer::TRANSITIONS),
+            InputValidator::new(Protocol::StepDirection),
+        )
     }
 }
 
@@ -76,14 +122,70 @@ where
     Mode: StepMode,
     T: Zero,
 {
-    pub(crate) fn new(transducer: StateTransducer<'static, 8, 4>) -> Self {
+    pub(crate) fn new(
+        transducer: StateTransducer<'static, 8, 4>,
+        validator: InputValidator,
+    ) -> Self {
         Self {
             transducer,
-            validator: Default::default(),
+            validator,
+            monitor: None,
+            hysteresis: Default::default(),
             counter: Zero::zero(),
+            raw_counter: Zero::zero(),
+            is_enabled: true,
             _phantom: PhantomData,
         }
     }
+
+    /// Attaches a signal monitor to the decoder, making it track soft anomalies
+    /// (such as glitches, reversal chatter and edge-timing jitter) of its inputs.
+    pub fn monitored(mut self, monitor: SignalMonitor) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Returns the decoder's signal monitor, if any.
+    pub fn monitor(&self) -> Option<&SignalMonitor> {
+        self.monitor.as_ref()
+    }
+
+    /// Returns the decoder's signal monitor mutably, if any.
+    pub fn monitor_mut(&mut self) -> Option<&mut SignalMonitor> {
+        self.monitor.as_mut()
+    }
+
+    /// Sets the decoder's direction-reversal hysteresis, in number of changes.
+    ///
+    /// After a change of direction the decoder only reports changes (and updates its counter)
+    /// once the input has travelled more than `hysteresis` changes in the new direction,
+    /// suppressing the chatter of a shaft resting exactly on a transition.
+    /// In quad-step mode a single change corresponds to a quarter cycle.
+    ///
+    /// The unfiltered counter remains available via `decoder.raw_counter()`.
+    pub fn with_hysteresis(mut self, hysteresis: u8) -> Self {
+        self.hysteresis = ReversalHysteresis::new(hysteresis);
+        self
+    }
+
+    /// Returns the decoder's direction-reversal hysteresis, in number of changes.
+    pub fn hysteresis(&self) -> u8 {
+        self.hysteresis.threshold()
+    }
+
+    /// Enables or disables (aka gates) the decoder's counting, e.g. based on a gate input.
+    ///
+    /// While disabled the decoder keeps tracking (and validating) its inputs,
+    /// but neither reports changes, nor updates its counters,
+    /// allowing for measuring distances only during specific phases of a process.
+    pub fn set_enabled(&mut self, is_enabled: bool) {
+        self.is_enabled = is_enabled;
+    }
+
+    /// Returns `true` if the decoder's counting is enabled (which is the default), otherwise `false`.
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
 }
 
 impl<Mode, T> IncrementalDecoder<Mode, T>
@@ -100,8 +202,60 @@ where
     /// you would either call `decoder.update(a, b)` directly, or via `decoder.update(a, b).unwrap_or_default()`
     /// to fall back to `None` in case of `Err(_)`.
     pub fn update(&mut self, a: bool, b: bool) -> Result<Option<Change>, Error> {
-        let input = Input::new(a, b);
+        self.update_input(Input::new(a, b), None)
+    }
+
+    /// Updates the decoder's state based on the given `a` and `b` pulse train (aka channel) readings,
+    /// sampled at the given `timestamp` (in arbitrary, but monotonic units, e.g. microseconds).
+    ///
+    /// Behaves exactly like `decoder.update(a, b)`, but additionally allows an attached
+    /// signal monitor to keep track of edge-timing jitter.
+    pub fn update_at(&mut self, a: bool, b: bool, timestamp: u32) -> Result<Option<Change>, Error> {
+        self.update_input(Input::new(a, b), Some(timestamp))
+    }
+
+    fn update_input(
+        &mut self,
+        input: Input,
+        timestamp: Option<u32>,
+    ) -> Result<Option<Change>, Error> {
+        self.update_decoded(input, timestamp)
+            .map(|(change, _)| change)
+    }
+
+    /// Updates the decoder's state based on the given `input`, returning both the reported change
+    /// and the decoded change (i.e. prior to gating and hysteresis), if any.
+    pub(crate) fn update_decoded(
+        &mut self,
+        input: Input,
+        timestamp: Option<u32>,
+    ) -> Result<(Option<Change>, Option<Change>), Error> {
+        let result = self.decode(input);
+
+        if let Some(monitor) = self.monitor.as_mut() {
+            monitor.observe(input, result, timestamp);
+        }
+
+        let decoded = result?;
+        let change = match decoded {
+            Some(change) if self.is_enabled => change,
+            _ => return Ok((None, decoded)),
+        };
+
+        let delta: T = (change as i8).into();
+        self.raw_counter = self.raw_counter.saturating_add(&delta);
+
+        let change = self.hysteresis.filter(change);
+
+        if let Some(change) = change {
+            let delta: T = (change as i8).into();
+            self.counter = self.counter.saturating_add(&delta);
+        }
+
+        Ok((change, decoded))
+    }
 
+    fn decode(&mut self, input: Input) -> Result<Option<Change>, Error> {
         let validation_result = self.validator.validate(input);
         let transducer_output = self.transducer.step(input);
 
@@ -111,18 +265,8 @@ where
                 Err(error)
             }
             (Ok(_), Output::N) => Ok(None),
-            (Ok(_), Output::AB) => {
-                let change = Change::Positive;
-                let delta: T = (change as i8).into();
-                self.counter = self.counter.saturating_add(&delta);
-                Ok(Some(change))
-            }
-            (Ok(_), Output::BA) => {
-                let change = Change::Negative;
-                let delta: T = (change as i8).into();
-                self.counter = self.counter.saturating_add(&delta);
-                Ok(Some(change))
-            }
+            (Ok(_), Output::AB) => Ok(Some(Change::Positive)),
+            (Ok(_), Output::BA) => Ok(Some(Change::Negative)),
             (_, Output::E) => {
                 // Transducers are expected to not return error outputs since their states tend to
                 // be insufficient for reliable detection without false positives/negatives.
@@ -132,10 +276,17 @@ where
     }
 
     /// Resets the decoder to its initial state and its counter counter back to `0`.
+    ///
+    /// If a signal monitor is attached, its metrics get reset as well.
     pub fn reset(&mut self) {
         self.transducer.reset();
         self.validator.reset();
+        if let Some(monitor) = self.monitor.as_mut() {
+            monitor.reset();
+        }
+        self.hysteresis.reset();
         self.counter = Zero::zero();
+        self.raw_counter = Zero::zero();
     }
 
     /// Returns the decoder's counter counter relative to its initial counter in number of cycles.
@@ -146,8 +297,35 @@ where
         self.counter
     }
 
-    /// Sets the decoder's counter.
+    /// Returns the decoder's counter, unaffected by the decoder's direction-reversal hysteresis.
+    ///
+    /// Without hysteresis this is identical to `decoder.counter()`.
+    pub fn raw_counter(&self) -> T {
+        self.raw_counter
+    }
+
+    /// Sets the decoder's counter (as well as its raw counter).
     pub fn set_counter(&mut self, counter: T) {
+        self.hysteresis.reset();
         self.counter = counter;
+        self.raw_counter = counter;
+    }
+}
+
+impl<T> IncrementalDecoder<Tachometer, T>
+where
+    T: Copy + Zero + One + SaturatingAdd + From<i8>,
+{
+    /// Updates the decoder's state based on the given `pulse` train (aka channel) reading,
+    /// with the direction being provided externally (e.g. a commanded direction,
+    /// or a constant one for unidirectional movement), rather than by a direction pin.
+    ///
+    /// Behaves exactly like `decoder.update(pulse, direction == Change::Positive)`.
+    pub fn update_pulse(
+        &mut self,
+        pulse: bool,
+        direction: Change,
+    ) -> Result<Option<Change>, Error> {
+        self.update(pulse, direction == Change::Positive)
     }
 }
@@ -1,8 +1,12 @@
 //! Quadrature-based decoder.
 
-use num_traits::{One, SaturatingAdd, Zero};
+use num_traits::{Bounded, One, SaturatingAdd, Zero};
 
-use crate::{index_decoder::IndexDecoder, Change, Error, IncrementalDecoder, StepMode};
+use crate::{
+    index_decoder::{counter_from, IndexDecoder},
+    state_transducer::Input,
+    Change, Error, IncrementalDecoder, IndexQualifier, StepMode,
+};
 
 /// A robust indexed quadrature decoder with support for multiple step-modes,
 /// based on which channel (A vs. B) is leading the other.
@@ -36,6 +40,7 @@ use crate::{index_decoder::IndexDecoder, Change, Error, IncrementalDecoder, Step
 pub struct IndexedIncrementalDecoder<Mode, T = i32> {
     decoder: IncrementalDecoder<Mode, T>,
     indexer: IndexDecoder,
+    samples: u32,
 }
 
 impl<Mode, T> Default for IndexedIncrementalDecoder<Mode, T>
@@ -56,39 +61,94 @@ where
         Self {
             decoder,
             indexer: Default::default(),
+            samples: 0,
         }
     }
+
+    /// Sets the qualification criteria for accepting index pulses (z channel).
+    pub fn with_index_qualifier(mut self, qualifier: IndexQualifier) -> Self {
+        self.indexer = IndexDecoder::new(qualifier);
+        self
+    }
+
+    /// Returns the qualification criteria for accepting index pulses (z channel).
+    pub fn index_qualifier(&self) -> IndexQualifier {
+        self.indexer.qualifier()
+    }
 }
 
 impl<Mode, T> IndexedIncrementalDecoder<Mode, T>
 where
     Mode: StepMode,
-    T: Copy + Zero + One + SaturatingAdd + From<i8>,
+    T: Copy + Zero + One + SaturatingAdd + Bounded + From<i8> + TryFrom<i32>,
 {
     /// Updates the decoder's state based on the given `a` and `b` pulse train (aka channel) readings,
     /// returning the direction if a change was detected, `None` if no change was detected,
     /// or `Err(_)` if an invalid input (i.e. a counteral "jump") was detected.
     ///
-    /// Upon detection of a raising edge on the `z` pulse train the counter gets reset back to `0`.
+    /// Upon detection of a (qualified) index event on the `z` pulse train the counter gets reset back to `0`,
+    /// with pulse widths being measured in number of updates.
     ///
     /// Depending on whether it matters why the decoder did not detect a change
     /// (e.g. due to actual lack of change or an erroneous read)
     /// you would either call `decoder.update(a, b)` directly, or via `decoder.update(a, b).unwrap_or_default()`
     /// to fall back to `None` in case of `Err(_)`.
     pub fn update(&mut self, a: bool, b: bool, z: bool) -> Result<Option<Change>, Error> {
-        let result = self.decoder.update(a, b);
+        let now = self.samples;
+        self.samples = self.samples.wrapping_add(1);
+
+        self.update_index(a, b, z, None, now)
+    }
+
+    /// Updates the decoder's state based on the given `a`, `b` and `z` pulse train (aka channel) readings,
+    /// sampled at the given `timestamp` (in arbitrary, but monotonic units, e.g. microseconds).
+    ///
+    /// Behaves exactly like `decoder.update(a, b, z)`, but with pulse widths being measured
+    /// in the units of the timestamps, additionally allowing an attached signal monitor
+    /// to keep track of edge-timing jitter.
+    pub fn update_at(
+        &mut self,
+        a: bool,
+        b: bool,
+        z: bool,
+        timestamp: u32,
+    ) -> Result<Option<Change>, Error> {
+        self.update_index(a, b, z, Some(timestamp), timestamp)
+    }
+
+    fn update_index(
+        &mut self,
+        a: bool,
+        b: bool,
+        z: bool,
+        timestamp: Option<u32>,
+        now: u32,
+    ) -> Result<Option<Change>, Error> {
+        let input = Input::new(a, b);
+        let result = self.decoder.update_decoded(input, timestamp);
+
+        // While disabled the index decoder keeps tracking the (otherwise discarded) movements,
+        // so that its distances remain valid once re-enabled:
+        let (change, decoded) = result.unwrap_or_default();
+        let tracked = match self.decoder.is_enabled() {
+            true => change,
+            false => decoded,
+        };
+
+        let event = self.indexer.update(z, input, tracked, now);
 
-        if self.indexer.update(z) {
-            self.decoder.set_counter(Zero::zero());
+        if let Some(counter) = event.filter(|_| self.decoder.is_enabled()) {
+            self.decoder.set_counter(counter_from(counter));
         }
 
-        result
+        result.map(|(change, _)| change)
     }
 
     /// Resets the decoder to its initial state and its counter counter back to `0`.
     pub fn reset(&mut self) {
         self.decoder.reset();
         self.indexer.reset();
+        self.samples = 0;
     }
 
     /// Returns the decoder's counter counter relative to its initial counter in number of cycles.
@@ -103,11 +163,26 @@ where
     pub fn set_counter(&mut self, counter: T) {
         self.decoder.set_counter(counter);
     }
+
+    /// Enables or disables (aka gates) the decoder's counting, e.g. based on a gate input.
+    ///
+    /// While disabled the decoder keeps tracking (and validating) its inputs,
+    /// but neither reports changes, nor updates its counter (not even upon index events).
+    /// Index pulses keep getting qualified against the movements while disabled, though
+    /// (e.g. for their minimum distance), so that they remain valid once re-enabled.
+    pub fn set_enabled(&mut self, is_enabled: bool) {
+        self.decoder.set_enabled(is_enabled);
+    }
+
+    /// Returns `true` if the decoder's counting is enabled (which is the default), otherwise `false`.
+    pub fn is_enabled(&self) -> bool {
+        self.decoder.is_enabled()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::HalfStep;
+    use crate::{HalfStep, QuadStep};
 
     use super::*;
 
@@ -145,4 +220,80 @@ mod tests {
             assert_eq!(decoder.counter(), expected_counter);
         }
     }
+
+    #[test]
+    fn gated_index() {
+        let a: Vec<bool> = vec![true, false, false, true, true, false, false, true];
+        let b: Vec<bool> = vec![true, true, false, false, true, true, false, false];
+        let z: Vec<bool> = vec![false, true, true, true, true, false, true, false];
+
+        // The index pulse only counts once coinciding with `a` and `b` being high,
+        // with the second (too narrow) index pulse getting rejected:
+        let counters: Vec<i32> = vec![0, 1, 2, 3, 0, 1, 2, 3];
+
+        let qualifier = IndexQualifier::new()
+            .with_gate(true, true)
+            .with_min_width(2);
+        let mut decoder: IndexedIncrementalDecoder<QuadStep> =
+            IndexedIncrementalDecoder::default().with_index_qualifier(qualifier);
+
+        for (((a, b), z), expected_counter) in a.into_iter().zip(b).zip(z).zip(counters) {
+            decoder.update(a, b, z).unwrap();
+            assert_eq!(decoder.counter(), expected_counter);
+        }
+    }
+
+    #[test]
+    fn disabled_index() {
+        let a: Vec<bool> = vec![false, false, true, true, false, false, true, true];
+        let b: Vec<bool> = vec![true, false, false, true, true, false, false, true];
+        let z: Vec<bool> = vec![false, false, false, false, true, false, false, false];
+
+        let mut decoder: IndexedIncrementalDecoder<HalfStep> = Default::default();
+        decoder.set_counter(5);
+        decoder.set_enabled(false);
+
+        // Neither movements, nor index events affect the counter:
+        for ((a, b), z) in a.into_iter().zip(b).zip(z) {
+            assert_eq!(decoder.update(a, b, z), Ok(None));
+            assert_eq!(decoder.counter(), 5);
+        }
+
+        decoder.set_enabled(true);
+
+        assert_eq!(decoder.update(false, true, false), Ok(None));
+        assert_eq!(
+            decoder.update(false, false, false),
+            Ok(Some(Change::Positive))
+        );
+        assert_eq!(decoder.counter(), 6);
+    }
+
+    #[test]
+    fn disabled_travel() {
+        /// The quadrature readings (`a`, `b`) in forwards order.
+        const QUADRATURE: [(bool, bool); 4] =
+            [(true, true), (false, true), (false, false), (true, false)];
+
+        let qualifier = IndexQualifier::new().with_min_distance(8);
+        let mut decoder: IndexedIncrementalDecoder<QuadStep> =
+            IndexedIncrementalDecoder::default().with_index_qualifier(qualifier);
+
+        assert_eq!(decoder.update(true, true, true), Ok(None));
+        assert_eq!(decoder.counter(), 0);
+        decoder.set_counter(100);
+
+        // Travelling beyond the minimum distance while disabled:
+        decoder.set_enabled(false);
+        for step in 1..=10 {
+            let (a, b) = QUADRATURE[step % 4];
+            assert_eq!(decoder.update(a, b, false), Ok(None));
+        }
+        decoder.set_enabled(true);
+
+        // The index event is far enough from the previous one:
+        let (a, b) = QUADRATURE[11 % 4];
+        assert_eq!(decoder.update(a, b, true), Ok(Some(Change::Positive)));
+        assert_eq!(decoder.counter(), 0);
+    }
 }
@@ -0,0 +1,78 @@
+//! Multi-turn unwrapping of absolute angles.
+
+use num_traits::{SaturatingAdd, Zero};
+
+use crate::Change;
+
+/// A tracker for unwrapping a sequence of absolute (single-turn) angles into a multi-turn position,
+/// assuming that consecutive angles are less than half a turn apart.
+#[derive(Debug)]
+pub(crate) struct MultiTurn<T> {
+    resolution: u32,
+    angle: Option<u32>,
+    position: T,
+}
+
+impl<T> MultiTurn<T>
+where
+    T: Copy + Zero + SaturatingAdd + From<i32>,
+{
+    /// Creates a tracker for angles in the range of `0..resolution`.
+    pub(crate) fn new(resolution: u32) -> Self {
+        debug_assert!(
+            resolution <= (1 << 31),
+            "Expected resolution of at most 2^31."
+        );
+        Self {
+            resolution,
+            angle: None,
+            position: Zero::zero(),
+        }
+    }
+
+    /// Updates the tracker with the given `angle`, returning the direction of the change, if any.
+    ///
+    /// The position gets initialized with the first angle.
+    pub(crate) fn update(&mut self, angle: u32) -> Option<Change> {
+        let resolution = i64::from(self.resolution);
+
+        let Some(last_angle) = self.angle.replace(angle) else {
+            self.position = (angle as i32).into();
+            return None;
+        };
+
+        // The shortest signed distance between the two angles:
+        let mut delta = (i64::from(angle) - i64::from(last_angle)).rem_euclid(resolution);
+        if delta > resolution / 2 {
+            delta -= resolution;
+        }
+
+        let change = match delta.cmp(&0) {
+            core::cmp::Ordering::Less => Some(Change::Negative),
+            core::cmp::Ordering::Equal => None,
+            core::cmp::Ordering::Greater => Some(Change::Positive),
+        };
+
+        let delta: T = (delta as i32).into();
+        self.position = self.position.saturating_add(&delta);
+
+        change
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.angle = None;
+        self.position = Zero::zero();
+    }
+
+    pub(crate) fn angle(&self) -> Option<u32> {
+        self.angle
+    }
+
+    pub(crate) fn position(&self) -> T {
+        self.position
+    }
+
+    pub(crate) fn set_position(&mut self, position: T) {
+        self.position = position;
+    }
+}
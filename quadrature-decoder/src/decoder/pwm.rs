@@ -0,0 +1,333 @@
+//! PWM-based absolute decoder.
+
+use num_traits::{SaturatingAdd, Zero};
+
+use crate::{decoder::multi_turn::MultiTurn, Change, PwmError};
+
+/// The frame format of a PWM-output absolute encoder, in number of (sensor) clock periods.
+///
+/// ```plain
+///     ┌──────┬───────┬───────┐                   ┌── high
+///     │ init │ error │ angle │                   │
+///   ──┘      ┆       ┆       └─────────┬─────────┘   low
+///     ◀───────────────── frame ─────────────────▶
+///                    ◀───── data ─────▶◀─ exit ─▶
+/// ```
+///
+/// The high pulse spans the init and error sections, followed by one clock period
+/// per position of the encoded angle, with the remainder of the data section
+/// and the exit section being low.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PwmFormat {
+    /// The number of clock periods of the (high) init section.
+    pub init: u16,
+    /// The number of clock periods of the error section
+    /// (high, unless the sensor reports an error).
+    pub error: u16,
+    /// The number of positions per turn encoded in the data section.
+    pub positions: u16,
+    /// The number of clock periods of the (low) exit section.
+    pub exit: u16,
+}
+
+impl PwmFormat {
+    /// The PWM frame format of the AS5600 magnetic angle sensor.
+    pub const AS5600: Self = Self {
+        init: 128,
+        error: 0,
+        positions: 4096,
+        exit: 128,
+    };
+
+    /// The PWM frame format of the AS5048 magnetic angle sensor.
+    pub const AS5048: Self = Self {
+        init: 12,
+        error: 4,
+        positions: 4096,
+        exit: 8,
+    };
+
+    /// Returns the number of clock periods per frame.
+    pub const fn clocks(&self) -> u32 {
+        self.init as u32 + self.error as u32 + (self.positions as u32 - 1) + self.exit as u32
+    }
+}
+
+/// A decoder for PWM-output absolute rotary encoders (e.g. AS5600 and AS5048 magnetic angle sensors),
+/// whose duty cycle encodes their absolute angle.
+///
+/// The decoder is fed with the timestamps of the PWM signal's rising and falling edges
+/// (e.g. as obtained via a timer's input capture), with the angle being independent
+/// of the sensor's actual (and often rather imprecise) clock frequency.
+///
+/// The absolute angle gets unwrapped into a multi-turn position,
+/// assuming that the encoder moves by less than half a turn per frame.
+#[derive(Debug)]
+pub struct PwmDecoder<T = i32> {
+    format: PwmFormat,
+    tolerance: u16,
+    level: Option<bool>,
+    rising: Option<u32>,
+    falling: Option<u32>,
+    period: Option<u32>,
+    multi_turn: MultiTurn<T>,
+}
+
+impl<T> PwmDecoder<T>
+where
+    T: Copy + Zero + SaturatingAdd + From<i32>,
+{
+    /// The default tolerance, in number of clock periods.
+    pub const DEFAULT_TOLERANCE: u16 = 4;
+
+    /// Creates a decoder for the given frame format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format has fewer than 2 positions (i.e. does not encode an angle).
+    pub fn new(format: PwmFormat) -> Self {
+        assert!(format.positions >= 2, "Expected at least 2 positions.");
+        Self {
+            format,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            level: None,
+            rising: None,
+            falling: None,
+            period: None,
+            multi_turn: MultiTurn::new(u32::from(format.positions)),
+        }
+    }
+
+    /// Sets the decoder's tolerance for frame validation, in number of clock periods.
+    pub fn with_tolerance(mut self, tolerance: u16) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Returns the decoder's tolerance for frame validation, in number of clock periods.
+    pub fn tolerance(&self) -> u16 {
+        self.tolerance
+    }
+
+    /// Returns the decoder's frame format.
+    pub fn format(&self) -> PwmFormat {
+        self.format
+    }
+
+    /// Updates the decoder's state based on an edge of the PWM signal,
+    /// with `level` being the signal's level after the edge (i.e. `true` for a rising edge)
+    /// and `timestamp` being the edge's time (in arbitrary, but monotonic units, e.g. microseconds).
+    ///
+    /// Returns the direction if a frame got completed (i.e. on a rising edge) and
+    /// a change was detected, `None` if no change was detected, or `Err(_)`
+    /// if an invalid frame (or a missed edge) was detected.
+    pub fn update(&mut self, level: bool, timestamp: u32) -> Result<Option<Change>, PwmError> {
+        let last_level = self.level.replace(level);
+
+        if last_level == Some(level) {
+            // Discard the current frame:
+            self.rising = level.then_some(timestamp);
+            self.falling = None;
+            return Err(PwmError::Edge);
+        }
+
+        if !level {
+            self.falling = Some(timestamp);
+            return Ok(None);
+        }
+
+        let rising = self.rising.replace(timestamp);
+        let falling = self.falling.take();
+
+        let (Some(rising), Some(falling)) = (rising, falling) else {
+            return Ok(None);
+        };
+
+        let period = timestamp.wrapping_sub(rising);
+        let high = falling.wrapping_sub(rising);
+
+        let angle = self.decode_frame(period, high)?;
+
+        Ok(self.multi_turn.update(u32::from(angle)))
+    }
+
+    fn decode_frame(&mut self, period: u32, high: u32) -> Result<u16, PwmError> {
+        let format = &self.format;
+        let tolerance = u64::from(self.tolerance);
+        let clocks = u64::from(format.clocks());
+
+        let last_period = self.period.replace(period);
+
+        if period == 0 || high > period {
+            return Err(PwmError::Period);
+        }
+
+        // The deviation from the last frame's period, in number of clock periods:
+        if let Some(last_period) = last_period {
+            let deviation = u64::from(period.abs_diff(last_period)) * clocks / u64::from(period);
+            if deviation > tolerance {
+                return Err(PwmError::Period);
+            }
+        }
+
+        let period = u64::from(period);
+        let high_clocks = (u64::from(high) * clocks + period / 2) / period;
+
+        let init = u64::from(format.init);
+        let error = u64::from(format.error);
+        let positions = u64::from(format.positions);
+
+        if high_clocks + tolerance < init {
+            return Err(PwmError::Init);
+        }
+
+        // The sensor reports errors by pulling the error section low:
+        if format.error > 0 && high_clocks < init + error / 2 {
+            return Err(PwmError::Sensor);
+        }
+
+        let max_high_clocks = init + error + (positions - 1);
+
+        if high_clocks > max_high_clocks + tolerance {
+            return Err(PwmError::Exit);
+        }
+
+        let angle = high_clocks.saturating_sub(init + error).min(positions - 1);
+
+        Ok(angle as u16)
+    }
+
+    /// Resets the decoder to its initial state and its position back to `0`.
+    pub fn reset(&mut self) {
+        self.level = None;
+        self.rising = None;
+        self.falling = None;
+        self.period = None;
+        self.multi_turn.reset();
+    }
+
+    /// Returns the most recently decoded absolute angle (in the range of `0..positions`),
+    /// or `None` if no valid frame has been decoded yet.
+    pub fn angle(&self) -> Option<u16> {
+        self.multi_turn.angle().map(|angle| angle as u16)
+    }
+
+    /// Returns the decoder's (multi-turn) position, in number of positions.
+    ///
+    /// The position gets initialized with the angle of the first valid frame.
+    pub fn position(&self) -> T {
+        self.multi_turn.position()
+    }
+
+    /// Sets the decoder's (multi-turn) position, in number of positions.
+    pub fn set_position(&mut self, position: T) {
+        self.multi_turn.set_position(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the timestamps of the rising and falling edges of a frame.
+    fn frame(format: &PwmFormat, start: u32, angle: u16, clock: u32) -> (u32, u32) {
+        let high = u32::from(format.init) + u32::from(format.error) + u32::from(angle);
+        (start, start + high * clock)
+    }
+
+    fn feed(
+        decoder: &mut PwmDecoder,
+        angles: &[u16],
+        clock: u32,
+    ) -> Vec<Result<Option<Change>, PwmError>> {
+        let format = decoder.format();
+        let period = format.clocks() * clock;
+        let mut results = vec![];
+
+        for (index, &angle) in angles.iter().enumerate() {
+            let (rising, falling) = frame(&format, index as u32 * period, angle, clock);
+            results.push(decoder.update(true, rising));
+            decoder.update(false, falling).unwrap();
+        }
+
+        results
+    }
+
+    #[test]
+    fn angle() {
+        for format in [PwmFormat::AS5600, PwmFormat::AS5048] {
+            let mut decoder: PwmDecoder = PwmDecoder::new(format);
+
+            // Each frame only gets decoded on the following frame's rising edge:
+            let results = feed(&mut decoder, &[4095, 4095, 0, 1234, 2000, 0], 3);
+
+            assert_eq!(results[0], Ok(None));
+            assert_eq!(results[1], Ok(None));
+            assert_eq!(results[2], Ok(None));
+            assert_eq!(results[3], Ok(Some(Change::Positive)));
+            assert_eq!(results[4], Ok(Some(Change::Positive)));
+            assert_eq!(results[5], Ok(Some(Change::Positive)));
+            assert_eq!(decoder.angle(), Some(2000));
+            assert_eq!(decoder.position(), 4096 + 2000);
+        }
+    }
+
+    #[test]
+    fn unwrapping() {
+        let mut decoder: PwmDecoder = PwmDecoder::new(PwmFormat::AS5600);
+
+        let results = feed(&mut decoder, &[4000, 100, 2000, 4000, 100, 4000, 0], 2);
+
+        assert_eq!(results[5], Ok(Some(Change::Positive)));
+        assert_eq!(results[6], Ok(Some(Change::Negative)));
+        assert_eq!(decoder.position(), 4096 + 4000);
+    }
+
+    #[test]
+    fn high_resolution() {
+        let format = PwmFormat {
+            init: 16,
+            error: 0,
+            positions: 40_000,
+            exit: 16,
+        };
+        let mut decoder: PwmDecoder = PwmDecoder::new(format);
+
+        let results = feed(&mut decoder, &[39_000, 39_000, 1000, 1000], 1);
+
+        assert_eq!(results[3], Ok(Some(Change::Positive)));
+        assert_eq!(decoder.position(), 40_000 + 1000);
+    }
+
+    #[test]
+    fn missed_edge() {
+        let mut decoder: PwmDecoder = PwmDecoder::new(PwmFormat::AS5600);
+
+        assert_eq!(decoder.update(true, 0), Ok(None));
+        assert_eq!(decoder.update(true, 100), Err(PwmError::Edge));
+    }
+
+    #[test]
+    fn invalid_frames() {
+        let format = PwmFormat::AS5048;
+        let mut decoder: PwmDecoder = PwmDecoder::new(format);
+        let period = format.clocks();
+
+        // High pulse shorter than the init section:
+        decoder.update(true, 0).unwrap();
+        decoder.update(false, 5).unwrap();
+        assert_eq!(decoder.update(true, period), Err(PwmError::Init));
+
+        // High pulse ending within the error section:
+        decoder.update(false, period + 13).unwrap();
+        assert_eq!(decoder.update(true, 2 * period), Err(PwmError::Sensor));
+
+        // High pulse extending into the exit section:
+        decoder.update(false, 3 * period - 1).unwrap();
+        assert_eq!(decoder.update(true, 3 * period), Err(PwmError::Exit));
+
+        // Frame period deviating from the last one's:
+        decoder.update(false, 3 * period + 100).unwrap();
+        assert_eq!(decoder.update(true, 5 * period), Err(PwmError::Period));
+    }
+}
@@ -0,0 +1,283 @@
+//! SSI (synchronous serial interface) absolute decoder.
+
+use num_traits::{SaturatingAdd, Zero};
+
+use crate::{
+    decoder::{absolute::gray_to_binary, multi_turn::MultiTurn},
+    Change, Coding, SsiError,
+};
+
+/// The parity of an SSI frame.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Parity {
+    /// No parity bit.
+    #[default]
+    None,
+    /// Even parity bit, i.e. the number of set bits (including the parity bit) is even.
+    Even,
+    /// Odd parity bit, i.e. the number of set bits (including the parity bit) is odd.
+    Odd,
+}
+
+/// A decoder for the frames of SSI (synchronous serial interface) absolute encoders.
+///
+/// A frame consists of the following bits, transmitted MSB first:
+///
+/// ```plain
+///       ┌─┐ ┌─┐ ┌─┐ ┌─┐ ┌─┐ ┌─┐ ┌─┐ ┌─┐     ┌─────── high
+/// Clock │ │ │ │ │ │ │ │ │ │ │ │ │ │ │ │     │
+///     ──┘ └─┘ └─┘ └─┘ └─┘ └─┘ └─┘ └─┘ └─────┘        low
+///       │ data (MSB … LSB)│ error │ p │ m │
+///       └─────────────────┴───────┴───┴───┘
+/// ```
+///
+/// - `data`: the encoder's position (in gray-code or binary), MSB first.
+/// - `error`: optional error bits, with any set bit indicating an error of the encoder.
+/// - `p`: an optional parity bit, covering the data and error bits.
+/// - `m`: a trailing monoflop bit, which is expected to be low (i.e. monoflop active).
+///
+/// The position gets unwrapped into a multi-turn position,
+/// assuming that the encoder moves by less than half a turn per frame.
+#[derive(Debug)]
+pub struct SsiDecoder<T = i32> {
+    bits: u8,
+    coding: Coding,
+    error_bits: u8,
+    parity: Parity,
+    multi_turn: MultiTurn<T>,
+}
+
+impl<T> SsiDecoder<T>
+where
+    T: Copy + Zero + SaturatingAdd + From<i32>,
+{
+    /// Creates a decoder for a gray-code encoder with a resolution of `bits` bits,
+    /// without error bits and without parity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not within `1..=31`.
+    pub fn new(bits: u8) -> Self {
+        assert!(
+            (1..=31).contains(&bits),
+            "Expected resolution of 1 to 31 bits."
+        );
+        Self {
+            bits,
+            coding: Coding::default(),
+            error_bits: 0,
+            parity: Parity::default(),
+            multi_turn: MultiTurn::new(1 << bits),
+        }
+    }
+
+    /// Sets the coding of the frame's data bits.
+    pub fn with_coding(mut self, coding: Coding) -> Self {
+        self.coding = coding;
+        self
+    }
+
+    /// Sets the number of error bits following the frame's data bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `error_bits` is larger than `8`.
+    pub fn with_error_bits(mut self, error_bits: u8) -> Self {
+        assert!(error_bits <= 8, "Expected at most 8 error bits.");
+        self.error_bits = error_bits;
+        self
+    }
+
+    /// Sets the parity of the frame.
+    pub fn with_parity(mut self, parity: Parity) -> Self {
+        self.parity = parity;
+        self
+    }
+
+    /// Returns the coding of the frame's data bits.
+    pub fn coding(&self) -> Coding {
+        self.coding
+    }
+
+    /// Returns the encoder's resolution, in number of bits.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// Returns the number of error bits following the frame's data bits.
+    pub fn error_bits(&self) -> u8 {
+        self.error_bits
+    }
+
+    /// Returns the parity of the frame.
+    pub fn parity(&self) -> Parity {
+        self.parity
+    }
+
+    /// Returns the encoder's resolution, in number of positions per turn.
+    pub fn resolution(&self) -> u32 {
+        1 << self.bits
+    }
+
+    /// Returns the total number of bits per frame (i.e. clock cycles),
+    /// including the trailing monoflop bit.
+    pub fn frame_bits(&self) -> u8 {
+        let parity_bits = match self.parity {
+            Parity::None => 0,
+            Parity::Even | Parity::Odd => 1,
+        };
+        self.bits + self.error_bits + parity_bits + 1
+    }
+
+    /// Updates the decoder's state based on the given `frame`, whose lowest `decoder.frame_bits()`
+    /// bits contain the received bits (with the first received bit being the most significant one),
+    /// returning the direction if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid frame was detected.
+    pub fn update(&mut self, frame: u64) -> Result<Option<Change>, SsiError> {
+        // The trailing monoflop bit:
+        if frame & 1 != 0 {
+            return Err(SsiError::Monoflop);
+        }
+
+        let mut frame = frame >> 1;
+
+        if self.parity != Parity::None {
+            let covered_bits = u32::from(self.bits + self.error_bits) + 1;
+            let ones = (frame & ((1 << covered_bits) - 1)).count_ones();
+            let is_valid = match self.parity {
+                Parity::Even => ones % 2 == 0,
+                _ => ones % 2 == 1,
+            };
+            if !is_valid {
+                return Err(SsiError::Parity);
+            }
+            frame >>= 1;
+        }
+
+        let errors = frame & ((1 << self.error_bits) - 1);
+        if errors != 0 {
+            return Err(SsiError::Encoder);
+        }
+        frame >>= self.error_bits;
+
+        let data = (frame & u64::from(self.resolution() - 1)) as u32;
+        let angle = match self.coding {
+            Coding::Gray => gray_to_binary(data),
+            Coding::Binary => data,
+        };
+
+        Ok(self.multi_turn.update(angle))
+    }
+
+    /// Resets the decoder to its initial state and its position back to `0`.
+    pub fn reset(&mut self) {
+        self.multi_turn.reset();
+    }
+
+    /// Returns the most recently decoded absolute angle (in the range of `0..resolution`),
+    /// or `None` if no valid frame has been decoded yet.
+    pub fn angle(&self) -> Option<u32> {
+        self.multi_turn.angle()
+    }
+
+    /// Returns the decoder's (multi-turn) position, in number of positions.
+    ///
+    /// The position gets initialized with the angle of the first valid frame.
+    pub fn position(&self) -> T {
+        self.multi_turn.position()
+    }
+
+    /// Sets the decoder's (multi-turn) position, in number of positions.
+    pub fn set_position(&mut self, position: T) {
+        self.multi_turn.set_position(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(data: u64, errors: u64, parity: Option<u64>, decoder: &SsiDecoder) -> u64 {
+        let mut frame = data;
+        frame = (frame << decoder.error_bits()) | errors;
+        if let Some(parity) = parity {
+            frame = (frame << 1) | parity;
+        }
+        frame << 1
+    }
+
+    #[test]
+    fn gray() {
+        let mut decoder: SsiDecoder = SsiDecoder::new(13);
+
+        assert_eq!(decoder.frame_bits(), 14);
+
+        // Gray-code `1_0000_0000_0000` corresponds to binary `1_1111_1111_1111`:
+        assert_eq!(decoder.update(frame(4096, 0, None, &decoder)), Ok(None));
+        assert_eq!(decoder.angle(), Some(8191));
+        assert_eq!(
+            decoder.update(frame(0, 0, None, &decoder)),
+            Ok(Some(Change::Positive))
+        );
+        assert_eq!(decoder.position(), 8192);
+    }
+
+    #[test]
+    fn binary_unwrapping() {
+        let mut decoder: SsiDecoder = SsiDecoder::new(10).with_coding(Coding::Binary);
+
+        for data in [1000, 1023, 10, 500] {
+            decoder.update(frame(data, 0, None, &decoder)).unwrap();
+        }
+        assert_eq!(decoder.position(), 1024 + 500);
+
+        assert_eq!(
+            decoder.update(frame(1000, 0, None, &decoder)),
+            Ok(Some(Change::Positive))
+        );
+        assert_eq!(
+            decoder.update(frame(600, 0, None, &decoder)),
+            Ok(Some(Change::Negative))
+        );
+        assert_eq!(decoder.position(), 1024 + 600);
+    }
+
+    #[test]
+    fn error_and_parity_bits() {
+        let mut decoder: SsiDecoder = SsiDecoder::new(12)
+            .with_coding(Coding::Binary)
+            .with_error_bits(1)
+            .with_parity(Parity::Even);
+
+        assert_eq!(decoder.frame_bits(), 15);
+        assert_eq!(decoder.update(frame(0b_11, 0, Some(0), &decoder)), Ok(None));
+        assert_eq!(decoder.angle(), Some(0b_11));
+        assert_eq!(
+            decoder.update(frame(0b_111, 0, Some(0), &decoder)),
+            Err(SsiError::Parity)
+        );
+        assert_eq!(
+            decoder.update(frame(0b_11, 1, Some(1), &decoder)),
+            Err(SsiError::Encoder)
+        );
+
+        let mut decoder: SsiDecoder = SsiDecoder::new(12)
+            .with_coding(Coding::Binary)
+            .with_parity(Parity::Odd);
+
+        assert_eq!(
+            decoder.update(frame(0b_111, 0, Some(0), &decoder)),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn monoflop() {
+        let mut decoder: SsiDecoder = SsiDecoder::new(12);
+
+        assert_eq!(
+            decoder.update(frame(0, 0, None, &decoder) | 1),
+            Err(SsiError::Monoflop)
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Differential-drive (aka wheel) odometry.
+//!
+//! Uses fixed-point arithmetic, with a floating-point variant being available with the `float` feature enabled.
+
+mod fixed;
+#[cfg(feature = "float")]
+mod float;
+
+pub use self::fixed::{DifferentialOdometry, Pose};
+#[cfg(feature = "float")]
+pub use self::float::{DifferentialOdometryF32, PoseF32};
@@ -0,0 +1,308 @@
+//! Fixed-point differential-drive odometry.
+
+/// The value of `2π`, in Q32 fixed-point.
+const TAU: i128 = 26986075409;
+
+/// The number of fractional bits of the internally tracked coordinates.
+const FRACTION_BITS: u32 = 16;
+
+/// The pose (i.e. position and heading) of a differential-drive robot.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Pose {
+    /// The x coordinate, in the units of the wheel radius and track width (e.g. micrometers).
+    pub x: i64,
+    /// The y coordinate, in the units of the wheel radius and track width (e.g. micrometers).
+    pub y: i64,
+    /// The heading (counter-clockwise, relative to the x axis),
+    /// as a binary angle in units of `1 / 2^32` turns (i.e. `1 << 30` corresponding to 90°).
+    pub heading: i32,
+}
+
+/// Odometry of a differential-drive robot (i.e. one with a left and a right wheel on a common axis),
+/// integrating its pose (i.e. `x`, `y` and heading) from the movements of its wheels' encoders.
+///
+/// ```plain
+///   y ▲       ┌─┐ left
+///     │    ───┼─┼───▶ heading
+///     │       └─┘ right
+///     │    ◀──▶ track width
+///     └────────────────▶ x
+/// ```
+///
+/// Lengths are in arbitrary, but consistent integer units (e.g. micrometers),
+/// with the heading being derived from the total difference in wheel counts
+/// (rather than being accumulated), so that it does not drift due to rounding.
+#[derive(Clone, Debug)]
+pub struct DifferentialOdometry {
+    wheel_radius: u32,
+    track_width: u32,
+    cpr: u32,
+    /// The heading at the time the pose was last set.
+    heading: i32,
+    /// The total difference of right and left wheel counts since the pose was last set.
+    difference: i64,
+    /// The coordinates, in fixed-point with `FRACTION_BITS` fractional bits.
+    x: i64,
+    y: i64,
+    /// The wheels' positions of the last update, if any.
+    positions: Option<(i32, i32)>,
+}
+
+impl DifferentialOdometry {
+    /// Creates odometry for wheels of the given `wheel_radius`, spaced `track_width` apart
+    /// (both in the same units, e.g. micrometers), with encoders of `cpr` counts per (wheel) revolution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `wheel_radius`, `track_width` or `cpr` are `0`.
+    pub fn new(wheel_radius: u32, track_width: u32, cpr: u32) -> Self {
+        assert!(
+            wheel_radius > 0 && track_width > 0 && cpr > 0,
+            "Expected non-zero wheel radius, track width and counts per revolution."
+        );
+        Self {
+            wheel_radius,
+            track_width,
+            cpr,
+            heading: 0,
+            difference: 0,
+            x: 0,
+            y: 0,
+            positions: None,
+        }
+    }
+
+    /// Returns the wheel radius.
+    pub fn wheel_radius(&self) -> u32 {
+        self.wheel_radius
+    }
+
+    /// Returns the track width (i.e. the distance between the wheels).
+    pub fn track_width(&self) -> u32 {
+        self.track_width
+    }
+
+    /// Returns the encoders' number of counts per (wheel) revolution.
+    pub fn cpr(&self) -> u32 {
+        self.cpr
+    }
+
+    /// Returns the current pose.
+    pub fn pose(&self) -> Pose {
+        let round = |value: i64| (value + (1 << (FRACTION_BITS - 1))) >> FRACTION_BITS;
+        Pose {
+            x: round(self.x),
+            y: round(self.y),
+            heading: self.heading(),
+        }
+    }
+
+    /// Sets the current pose (e.g. from an external reference).
+    pub fn set_pose(&mut self, pose: Pose) {
+        self.heading = pose.heading;
+        self.difference = 0;
+        self.x = pose.x << FRACTION_BITS;
+        self.y = pose.y << FRACTION_BITS;
+    }
+
+    /// Resets the pose back to the origin (retaining the wheels' positions of the last update, if any).
+    pub fn reset(&mut self) {
+        self.set_pose(Pose::default());
+    }
+
+    /// Updates the pose based on the wheels' (absolute) positions (e.g. `encoder.position()`),
+    /// in number of counts, returning the updated pose.
+    ///
+    /// The first update only establishes the wheels' initial positions.
+    pub fn update(&mut self, left: i32, right: i32) -> Pose {
+        if let Some((last_left, last_right)) = self.positions.replace((left, right)) {
+            self.update_delta(left.wrapping_sub(last_left), right.wrapping_sub(last_right));
+        }
+
+        self.pose()
+    }
+
+    /// Updates the pose based on the wheels' (relative) movements (e.g. `±1` per detected movement),
+    /// in number of counts.
+    pub fn update_delta(&mut self, left: i32, right: i32) {
+        let start = self.heading();
+        self.difference += i64::from(right) - i64::from(left);
+        let end = self.heading();
+
+        // Moving along the mid-point heading (i.e. along the chord of the arc):
+        let heading = start.wrapping_add(end.wrapping_sub(start) / 2);
+        let (cos, sin) = cos_sin(heading);
+
+        // The distance travelled by the center of the axis, i.e. `(left + right) / 2 * 2π * r / cpr`:
+        let sum = i128::from(left) + i128::from(right);
+        let distance = sum * i128::from(self.wheel_radius) * TAU
+            / (i128::from(self.cpr) << (32 + 1 - FRACTION_BITS));
+
+        let project = |factor: i32| ((distance * i128::from(factor) + (1 << 29)) >> 30) as i64;
+        self.x += project(cos);
+        self.y += project(sin);
+    }
+
+    /// Returns the heading, derived from the total difference in wheel counts,
+    /// i.e. `(right - left) * r / (cpr * track width)` turns.
+    fn heading(&self) -> i32 {
+        let turns = ((i128::from(self.difference) * i128::from(self.wheel_radius)) << 32)
+            / (i128::from(self.cpr) * i128::from(self.track_width));
+        // Truncation wraps the heading into a single turn:
+        self.heading.wrapping_add(turns as i32)
+    }
+}
+
+/// Returns the cosine and sine of the given `angle` (in units of `1 / 2^32` turns)
+/// in Q30 fixed-point (i.e. `1 << 30` corresponding to `1.0`),
+/// computed via the CORDIC algorithm (in rotation mode).
+fn cos_sin(angle: i32) -> (i32, i32) {
+    /// The values of `atan(2^-i)`, in units of `1 / 2^32` turns.
+    const ATANS: [u32; 24] = [
+        536870912, 316933406, 167458907, 85004756, 42667331, 21354465, 10679838, 5340245, 2670163,
+        1335087, 667544, 333772, 166886, 83443, 41722, 20861, 10430, 5215, 2608, 1304, 652, 326,
+        163, 81,
+    ];
+    /// The reciprocal of the CORDIC gain, in Q30 fixed-point.
+    const GAIN: i64 = 652032874;
+    const QUARTER: i32 = 1 << 30;
+
+    // Rotate by 180° into the right half-plane, within which CORDIC converges:
+    let (mut angle, sign) = if !(-QUARTER..=QUARTER).contains(&angle) {
+        (angle.wrapping_add(i32::MIN), -1)
+    } else {
+        (angle, 1)
+    };
+
+    let (mut x, mut y) = (GAIN, 0_i64);
+
+    for (i, atan) in ATANS.into_iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if angle >= 0 {
+            (x, y) = (x - dx, y + dy);
+            angle = angle.wrapping_sub(atan as i32);
+        } else {
+            (x, y) = (x + dx, y - dy);
+            angle = angle.wrapping_add(atan as i32);
+        }
+    }
+
+    ((sign * x) as i32, (sign * y) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUARTER: i32 = 1 << 30;
+
+    /// Wheels of 50mm radius, 300mm apart, with 1000 counts per revolution (in micrometers).
+    fn odometry() -> DifferentialOdometry {
+        DifferentialOdometry::new(50_000, 300_000, 1000)
+    }
+
+    #[test]
+    fn cos_sin_accuracy() {
+        for degrees in (-180..180).step_by(5) {
+            let radians = (degrees as f64).to_radians();
+            let angle = (degrees as f64 / 360.0 * 4294967296.0) as i64 as i32;
+            let (cos, sin) = cos_sin(angle);
+            let unity = (1 << 30) as f64;
+            assert!(
+                (cos as f64 / unity - radians.cos()).abs() < 1e-6,
+                "{degrees}°"
+            );
+            assert!(
+                (sin as f64 / unity - radians.sin()).abs() < 1e-6,
+                "{degrees}°"
+            );
+        }
+    }
+
+    #[test]
+    fn straight() {
+        let mut odometry = odometry();
+
+        for _ in 0..1000 {
+            odometry.update_delta(1, 1);
+        }
+
+        // A single wheel revolution, i.e. `2π * 50mm`:
+        assert_eq!(
+            odometry.pose(),
+            Pose {
+                x: 314_159,
+                y: 0,
+                heading: 0
+            }
+        );
+    }
+
+    #[test]
+    fn turn_in_place() {
+        let mut odometry = odometry();
+
+        // A quarter turn, i.e. `(right - left) = cpr * track width / (4 * r)`:
+        odometry.update_delta(-750, 750);
+        assert_eq!(odometry.pose().heading, QUARTER);
+
+        for _ in 0..1000 {
+            odometry.update_delta(1, 1);
+        }
+
+        let pose = odometry.pose();
+        assert!(pose.x.abs() <= 1, "{pose:?}");
+        assert_eq!(pose.y, 314_159);
+    }
+
+    #[test]
+    fn circle() {
+        let mut odometry = odometry();
+
+        // Each step turns by `1 / 6000` turns, completing a full circle:
+        for _ in 0..6000 {
+            odometry.update_delta(1, 2);
+        }
+
+        let pose = odometry.pose();
+        assert_eq!(pose.heading, 0);
+        assert!(pose.x.abs() <= 10 && pose.y.abs() <= 10, "{pose:?}");
+    }
+
+    #[test]
+    fn positions() {
+        let mut odometry = odometry();
+
+        // The first update only establishes the initial positions:
+        assert_eq!(odometry.update(100, -100), Pose::default());
+
+        let pose = odometry.update(-650, 650);
+        assert_eq!(pose.heading, QUARTER);
+        assert_eq!((pose.x, pose.y), (0, 0));
+    }
+
+    #[test]
+    fn set_pose_and_reset() {
+        let mut odometry = odometry();
+
+        let pose = Pose {
+            x: 1_000,
+            y: -2_000,
+            heading: QUARTER,
+        };
+        odometry.set_pose(pose);
+        assert_eq!(odometry.pose(), pose);
+
+        for _ in 0..1000 {
+            odometry.update_delta(-1, -1);
+        }
+
+        let pose = odometry.pose();
+        assert!((pose.x - 1_000).abs() <= 1, "{pose:?}");
+        assert_eq!(pose.y, -2_000 - 314_159);
+        assert_eq!(pose.heading, QUARTER);
+
+        odometry.reset();
+        assert_eq!(odometry.pose(), Pose::default());
+    }
+}
@@ -0,0 +1,283 @@
+//! Floating-point differential-drive odometry.
+
+use core::f32::consts::{PI, TAU};
+
+use num_traits::Float;
+
+/// The floating-point pose (i.e. position and heading) of a differential-drive robot.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PoseF32 {
+    /// The x coordinate, in the units of the wheel radius and track width (e.g. meters).
+    pub x: f32,
+    /// The y coordinate, in the units of the wheel radius and track width (e.g. meters).
+    pub y: f32,
+    /// The heading (counter-clockwise, relative to the x axis), in radians (within `-π..π`).
+    pub heading: f32,
+}
+
+/// Floating-point odometry of a differential-drive robot (i.e. one with a left and a right wheel on a common axis),
+/// integrating its pose (i.e. `x`, `y` and heading) from the movements of its wheels' encoders.
+///
+/// Behaves just like `DifferentialOdometry`, but with lengths and headings in `f32`.
+///
+/// ```plain
+///   y ▲       ┌─┐ left
+///     │    ───┼─┼───▶ heading
+///     │       └─┘ right
+///     │    ◀──▶ track width
+///     └────────────────▶ x
+/// ```
+///
+/// Lengths are in arbitrary, but consistent units (e.g. meters),
+/// with the heading being derived from the total difference in wheel counts
+/// (rather than being accumulated), and reduced to within a single turn in double precision,
+/// so that it neither drifts due to rounding, nor loses resolution over many turns.
+#[derive(Clone, Debug)]
+pub struct DifferentialOdometryF32 {
+    wheel_radius: f32,
+    track_width: f32,
+    cpr: u32,
+    /// The (normalized) heading at the time the pose was last set.
+    heading: f32,
+    /// The total difference of right and left wheel counts since the pose was last set.
+    difference: i64,
+    x: f32,
+    y: f32,
+    /// The wheels' positions of the last update, if any.
+    positions: Option<(i32, i32)>,
+}
+
+impl DifferentialOdometryF32 {
+    /// Creates odometry for wheels of the given `wheel_radius`, spaced `track_width` apart
+    /// (both in the same units, e.g. meters), with encoders of `cpr` counts per (wheel) revolution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `wheel_radius`, `track_width` or `cpr` are not positive.
+    pub fn new(wheel_radius: f32, track_width: f32, cpr: u32) -> Self {
+        assert!(
+            wheel_radius > 0.0 && track_width > 0.0 && cpr > 0,
+            "Expected positive wheel radius, track width and counts per revolution."
+        );
+        Self {
+            wheel_radius,
+            track_width,
+            cpr,
+            heading: 0.0,
+            difference: 0,
+            x: 0.0,
+            y: 0.0,
+            positions: None,
+        }
+    }
+
+    /// Returns the wheel radius.
+    pub fn wheel_radius(&self) -> f32 {
+        self.wheel_radius
+    }
+
+    /// Returns the track width (i.e. the distance between the wheels).
+    pub fn track_width(&self) -> f32 {
+        self.track_width
+    }
+
+    /// Returns the encoders' number of counts per (wheel) revolution.
+    pub fn cpr(&self) -> u32 {
+        self.cpr
+    }
+
+    /// Returns the current pose.
+    pub fn pose(&self) -> PoseF32 {
+        PoseF32 {
+            x: self.x,
+            y: self.y,
+            heading: self.heading(),
+        }
+    }
+
+    /// Sets the current pose (e.g. from an external reference).
+    pub fn set_pose(&mut self, pose: PoseF32) {
+        self.heading = normalize(pose.heading);
+        self.difference = 0;
+        self.x = pose.x;
+        self.y = pose.y;
+    }
+
+    /// Resets the pose back to the origin (retaining the wheels' positions of the last update, if any).
+    pub fn reset(&mut self) {
+        self.set_pose(PoseF32::default());
+    }
+
+    /// Updates the pose based on the wheels' (absolute) positions (e.g. `encoder.position()`),
+    /// in number of counts, returning the updated pose.
+    ///
+    /// The first update only establishes the wheels' initial positions.
+    pub fn update(&mut self, left: i32, right: i32) -> PoseF32 {
+        if let Some((last_left, last_right)) = self.positions.replace((left, right)) {
+            self.update_delta(left.wrapping_sub(last_left), right.wrapping_sub(last_right));
+        }
+
+        self.pose()
+    }
+
+    /// Updates the pose based on the wheels' (relative) movements (e.g. `±1` per detected movement),
+    /// in number of counts.
+    pub fn update_delta(&mut self, left: i32, right: i32) {
+        let start = self.heading();
+        let difference = i64::from(right) - i64::from(left);
+        self.difference += difference;
+
+        // The change of heading, i.e. `(right - left) * 2π * r / (cpr * track width)` radians:
+        let turn =
+            difference as f32 * TAU * self.wheel_radius / (self.cpr as f32 * self.track_width);
+
+        // Moving along the mid-point heading (i.e. along the chord of the arc):
+        let (sin, cos) = Float::sin_cos(start + turn / 2.0);
+
+        // The distance travelled by the center of the axis, i.e. `(left + right) / 2 * 2π * r / cpr`:
+        let sum = (i64::from(left) + i64::from(right)) as f32;
+        let distance = sum * PI * self.wheel_radius / self.cpr as f32;
+
+        self.x += distance * cos;
+        self.y += distance * sin;
+    }
+
+    /// Returns the (normalized) heading, derived from the total difference in wheel counts,
+    /// i.e. `(right - left) * 2π * r / (cpr * track width)` radians.
+    fn heading(&self) -> f32 {
+        // Only the fraction of a turn contributes to the heading,
+        // which gets computed in `f64` to retain its resolution over many turns:
+        let turns = self.difference as f64 * f64::from(self.wheel_radius)
+            / (f64::from(self.cpr) * f64::from(self.track_width));
+        let fraction = turns - Float::round(turns);
+        normalize(self.heading + (fraction * core::f64::consts::TAU) as f32)
+    }
+}
+
+/// Returns the given `angle` (in radians), normalized into `-π..π`.
+fn normalize(angle: f32) -> f32 {
+    angle - TAU * Float::round(angle / TAU)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    /// Wheels of 50mm radius, 300mm apart, with 1000 counts per revolution (in meters).
+    fn odometry() -> DifferentialOdometryF32 {
+        DifferentialOdometryF32::new(0.05, 0.3, 1000)
+    }
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{actual} vs. {expected}");
+    }
+
+    #[test]
+    fn straight() {
+        let mut odometry = odometry();
+
+        for _ in 0..1000 {
+            odometry.update_delta(1, 1);
+        }
+
+        // A single wheel revolution, i.e. `2π * 50mm`:
+        let pose = odometry.pose();
+        assert_near(pose.x, 0.1 * PI);
+        assert_near(pose.y, 0.0);
+        assert_near(pose.heading, 0.0);
+    }
+
+    #[test]
+    fn turn_in_place() {
+        let mut odometry = odometry();
+
+        // A quarter turn, i.e. `(right - left) = cpr * track width / (4 * r)`:
+        odometry.update_delta(-750, 750);
+        assert_near(odometry.pose().heading, FRAC_PI_2);
+
+        for _ in 0..1000 {
+            odometry.update_delta(1, 1);
+        }
+
+        let pose = odometry.pose();
+        assert_near(pose.x, 0.0);
+        assert_near(pose.y, 0.1 * PI);
+    }
+
+    #[test]
+    fn circle() {
+        let mut odometry = odometry();
+
+        // Each step turns by `1 / 6000` turns, completing a full circle:
+        for _ in 0..6000 {
+            odometry.update_delta(1, 2);
+        }
+
+        let pose = odometry.pose();
+        assert_near(pose.heading, 0.0);
+        assert_near(pose.x, 0.0);
+        assert_near(pose.y, 0.0);
+    }
+
+    #[test]
+    fn positions() {
+        let mut odometry = odometry();
+
+        // The first update only establishes the initial positions:
+        assert_eq!(odometry.update(100, -100), PoseF32::default());
+
+        let pose = odometry.update(-650, 650);
+        assert_near(pose.heading, FRAC_PI_2);
+        assert_near(pose.x, 0.0);
+        assert_near(pose.y, 0.0);
+    }
+
+    #[test]
+    fn set_pose_and_reset() {
+        let mut odometry = odometry();
+
+        let pose = PoseF32 {
+            x: 1.0,
+            y: -2.0,
+            heading: FRAC_PI_2,
+        };
+        odometry.set_pose(pose);
+        assert_eq!(odometry.pose(), pose);
+
+        for _ in 0..1000 {
+            odometry.update_delta(-1, -1);
+        }
+
+        let pose = odometry.pose();
+        assert_near(pose.x, 1.0);
+        assert_near(pose.y, -2.0 - 0.1 * PI);
+        assert_near(pose.heading, FRAC_PI_2);
+
+        odometry.reset();
+        assert_eq!(odometry.pose(), PoseF32::default());
+    }
+
+    #[test]
+    fn many_turns() {
+        // Wheels of 62.5mm radius, 250mm apart (i.e. exactly representable, with 4000 counts per turn):
+        let mut odometry = DifferentialOdometryF32::new(0.0625, 0.25, 1000);
+
+        // 50000 turns in place, followed by a quarter turn:
+        for _ in 0..1000 {
+            odometry.update_delta(-100_000, 100_000);
+        }
+        odometry.update_delta(-500, 500);
+        assert_near(odometry.pose().heading, FRAC_PI_2);
+
+        for _ in 0..1000 {
+            odometry.update_delta(1, 1);
+        }
+
+        let pose = odometry.pose();
+        assert_near(pose.x, 0.0);
+        assert_near(pose.y, 0.125 * PI);
+        assert_near(pose.heading, FRAC_PI_2);
+    }
+}
@@ -0,0 +1,289 @@
+//! Quadrature-based waveform generator.
+
+use core::marker::PhantomData;
+
+use num_traits::{One, SaturatingAdd, Signed, Zero};
+
+use crate::{state_transducer::Input, Change, QuadratureStepMode};
+
+/// The levels of a quadrature signal's pulse trains (aka channels) at a given point in time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Levels {
+    /// The level of the `a` pulse train.
+    pub a: bool,
+    /// The level of the `b` pulse train.
+    pub b: bool,
+    /// The level of the `z` (i.e. index) pulse train.
+    pub z: bool,
+}
+
+/// A quadrature waveform generator with support for multiple step-modes,
+/// i.e. the inverse of a quadrature decoder.
+///
+/// The generator turns position changes into the sequence of `a`/`b` levels
+/// that a decoder of the same step-mode decodes back into said changes,
+/// with each change spanning `4 / Mode::PULSES_PER_CYCLE` quarter cycles:
+///
+/// ```plain
+///       ────┐   ┌───┐   ┌───┐   ┌───┐   ┌─── high
+///     A     │   │   │   │   │   │   │   │
+///           └───┘   └───┘   └───┘   └───┘    low
+///       ──────┐   ┌───┐   ┌───┐   ┌───┐   ┌─ high
+///     B       │   │   │   │   │   │   │   │
+///             └───┘   └───┘   └───┘   └───┘  low
+/// Time: ─ ─ ─ ─ ─ ─ ─ ─ ─ ─ ─ ─ ─ ─ ─ ─ ─ ─▶
+/// ```
+///
+/// If an index period is provided, the `z` pulse train gets raised for a single
+/// quarter cycle whenever the position is a multiple of the index period.
+#[derive(Debug)]
+pub struct QuadratureGenerator<Mode, T = i32> {
+    position: T,
+    phase: u8,
+    quarters: i8,
+    index_period: Option<T>,
+    _phantom: PhantomData<Mode>,
+}
+
+impl<Mode, T> Default for QuadratureGenerator<Mode, T>
+where
+    Mode: QuadratureStepMode,
+    T: Zero,
+{
+    fn default() -> Self {
+        Self {
+            position: Zero::zero(),
+            phase: 0,
+            quarters: 0,
+            index_period: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Mode, T> QuadratureGenerator<Mode, T>
+where
+    Mode: QuadratureStepMode,
+    T: Copy + Zero + One + Signed + Ord + SaturatingAdd + From<i8>,
+{
+    /// The sequence of inputs of a forwards quarter-step cycle,
+    /// starting with the decoders' initial input (i.e. `A1B1`).
+    const PHASES: [Input; 4] = [Input::A1B1, Input::A0B1, Input::A0B0, Input::A1B0];
+
+    /// The number of quarter cycles per change.
+    const QUARTERS_PER_CHANGE: u8 = (4 / Mode::PULSES_PER_CYCLE) as u8;
+
+    /// Sets the generator's index period, in number of changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index_period` is not positive.
+    pub fn with_index_period(mut self, index_period: T) -> Self {
+        assert!(
+            index_period.is_positive(),
+            "Expected positive index period."
+        );
+        self.index_period = Some(index_period);
+        self
+    }
+
+    /// Returns the generator's index period, in number of changes, if any.
+    pub fn index_period(&self) -> Option<T> {
+        self.index_period
+    }
+
+    /// Returns the generator's position, in number of changes.
+    ///
+    /// Just like a decoder's counter the position only changes
+    /// once a change has been completed.
+    pub fn position(&self) -> T {
+        self.position
+    }
+
+    /// Returns the current levels of the generator's pulse trains.
+    pub fn levels(&self) -> Levels {
+        let input = Self::PHASES[self.phase as usize];
+
+        let is_at_index = self.quarters == 0
+            && self
+                .index_period
+                .is_some_and(|period| (self.position % period).is_zero());
+
+        Levels {
+            a: input.a(),
+            b: input.b(),
+            z: is_at_index,
+        }
+    }
+
+    /// Advances the generator by a single quarter cycle in the direction of `change`,
+    /// returning the resulting levels.
+    pub fn step(&mut self, change: Change) -> Levels {
+        let quarters_per_change = Self::QUARTERS_PER_CHANGE;
+
+        match change {
+            Change::Positive => {
+                self.phase = (self.phase + 1) % 4;
+                self.quarters += 1;
+            }
+            Change::Negative => {
+                self.phase = (self.phase + 3) % 4;
+                self.quarters -= 1;
+            }
+        }
+
+        // The position only changes once a full change's worth of
+        // quarter cycles has been travelled in either direction:
+        if self.quarters.unsigned_abs() == quarters_per_change {
+            let delta: T = (change as i8).into();
+            self.position = self.position.saturating_add(&delta);
+            self.quarters = 0;
+        }
+
+        self.levels()
+    }
+
+    /// Returns an iterator of the levels required for moving
+    /// to the given `target` position (in number of changes).
+    pub fn move_to(&mut self, target: T) -> Waveform<'_, Mode, T> {
+        Waveform {
+            generator: self,
+            target,
+        }
+    }
+
+    /// Returns an iterator of the levels required for moving
+    /// by the given `delta` (in number of changes).
+    pub fn move_by(&mut self, delta: T) -> Waveform<'_, Mode, T> {
+        let target = self.position.saturating_add(&delta);
+        self.move_to(target)
+    }
+
+    /// Resets the generator to its initial state and its position back to `0`.
+    pub fn reset(&mut self) {
+        self.position = Zero::zero();
+        self.phase = 0;
+        self.quarters = 0;
+    }
+
+    /// Sets the generator's position, in number of changes.
+    ///
+    /// The levels of the `a` and `b` pulse trains remain unaffected.
+    pub fn set_position(&mut self, position: T) {
+        self.position = position;
+    }
+}
+
+/// An iterator of the levels required for moving a [`QuadratureGenerator`] to a target position.
+///
+/// Returned by `generator.move_to(target)` and `generator.move_by(delta)`.
+#[derive(Debug)]
+pub struct Waveform<'a, Mode, T = i32> {
+    generator: &'a mut QuadratureGenerator<Mode, T>,
+    target: T,
+}
+
+impl<Mode, T> Iterator for Waveform<'_, Mode, T>
+where
+    Mode: QuadratureStepMode,
+    T: Copy + Zero + One + Signed + Ord + SaturatingAdd + From<i8>,
+{
+    type Item = Levels;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let change = match self.target.cmp(&self.generator.position) {
+            core::cmp::Ordering::Less => Change::Negative,
+            core::cmp::Ordering::Equal if self.generator.quarters == 0 => return None,
+            // Complete a partially travelled change, by returning to its start:
+            core::cmp::Ordering::Equal if self.generator.quarters > 0 => Change::Negative,
+            core::cmp::Ordering::Equal => Change::Positive,
+            core::cmp::Ordering::Greater => Change::Positive,
+        };
+
+        Some(self.generator.step(change))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FullStep, HalfStep, IncrementalDecoder, IndexedIncrementalDecoder, QuadStep};
+
+    use super::*;
+
+    fn round_trip<Mode>()
+    where
+        Mode: QuadratureStepMode,
+        IncrementalDecoder<Mode>: Default,
+    {
+        let mut generator: QuadratureGenerator<Mode> = Default::default();
+        let mut decoder: IncrementalDecoder<Mode> = Default::default();
+
+        for target in [5, -3, 0, 12, 11, 12] {
+            let waveform: Vec<Levels> = generator.move_to(target).collect();
+            for levels in waveform {
+                decoder.update(levels.a, levels.b).unwrap();
+            }
+            assert_eq!(decoder.counter(), target);
+            assert_eq!(generator.position(), target);
+        }
+
+        for delta in [1, -1, -1, 1] {
+            for levels in generator.move_by(delta) {
+                decoder.update(levels.a, levels.b).unwrap();
+            }
+            assert_eq!(decoder.counter(), generator.position());
+        }
+    }
+
+    #[test]
+    fn full_step() {
+        round_trip::<FullStep>();
+    }
+
+    #[test]
+    fn half_step() {
+        round_trip::<HalfStep>();
+    }
+
+    #[test]
+    fn quad_step() {
+        round_trip::<QuadStep>();
+    }
+
+    #[test]
+    fn partial_change() {
+        let mut generator: QuadratureGenerator<FullStep> = Default::default();
+
+        generator.step(Change::Positive);
+        generator.step(Change::Positive);
+
+        assert_eq!(generator.position(), 0);
+        assert_eq!(generator.move_to(0).count(), 2);
+        assert_eq!(
+            generator.levels(),
+            Levels {
+                a: true,
+                b: true,
+                z: false
+            }
+        );
+    }
+
+    #[test]
+    fn index() {
+        let mut generator: QuadratureGenerator<QuadStep> =
+            QuadratureGenerator::default().with_index_period(8);
+        let mut decoder: IndexedIncrementalDecoder<QuadStep> = Default::default();
+
+        let index_pulses = generator.move_to(20).filter(|levels| levels.z).count();
+        assert_eq!(index_pulses, 2);
+
+        generator.reset();
+
+        for levels in generator.move_to(10) {
+            decoder.update(levels.a, levels.b, levels.z).unwrap();
+        }
+
+        assert_eq!(decoder.counter(), 2);
+    }
+}
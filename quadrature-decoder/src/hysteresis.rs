@@ -0,0 +1,133 @@
+use crate::Change;
+
+/// A filter for suppressing direction-reversal chatter,
+/// e.g. of a shaft resting exactly on a transition.
+///
+/// After a direction reversal changes only pass the filter once
+/// the input has travelled more than `threshold` changes in the new direction.
+///
+/// ```plain
+///               threshold
+///              ├─────────┤
+/// Raw:      ───▶───▶───▶─┐
+///                  ◀───◀─┘ ◀── suppressed
+///           ◀───◀───◀───◀───◀───◀── passed
+/// ```
+#[derive(Default, Debug)]
+pub(crate) struct ReversalHysteresis {
+    threshold: u8,
+    direction: Option<Change>,
+    pending: u16,
+}
+
+impl ReversalHysteresis {
+    pub(crate) fn new(threshold: u8) -> Self {
+        Self {
+            threshold,
+            direction: None,
+            pending: 0,
+        }
+    }
+
+    pub(crate) fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Returns `Some(change)` if `change` passes the filter, otherwise `None`.
+    pub(crate) fn filter(&mut self, change: Change) -> Option<Change> {
+        let direction = *self.direction.get_or_insert(change);
+
+        if change == direction {
+            if self.pending > 0 {
+                // Moving back into the hysteresis band:
+                self.pending -= 1;
+                return None;
+            }
+            return Some(change);
+        }
+
+        self.pending += 1;
+
+        if self.pending <= u16::from(self.threshold) {
+            return None;
+        }
+
+        self.direction = Some(change);
+        self.pending = 0;
+
+        Some(change)
+    }
+
+    /// Resets the filter to its initial state.
+    pub(crate) fn reset(&mut self) {
+        self.direction = None;
+        self.pending = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_transducer::Input::{self, *},
+        Change::{self, *},
+        Error, IncrementalDecoder, QuadStep,
+    };
+
+    type Decoder = IncrementalDecoder<QuadStep>;
+
+    fn update(decoder: &mut Decoder, input: Input) -> Result<Option<Change>, Error> {
+        decoder.update(input.a(), input.b())
+    }
+
+    #[test]
+    fn disabled() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Positive)));
+        assert_eq!(update(&mut decoder, A1B1), Ok(Some(Negative)));
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Positive)));
+
+        assert_eq!(decoder.counter(), 1);
+        assert_eq!(decoder.raw_counter(), 1);
+    }
+
+    #[test]
+    fn chatter() {
+        let mut decoder = Decoder::default().with_hysteresis(1);
+
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Positive)));
+
+        for _ in 0..4 {
+            assert_eq!(update(&mut decoder, A1B1), Ok(None));
+            assert_eq!(decoder.raw_counter(), 0);
+            assert_eq!(update(&mut decoder, A0B1), Ok(None));
+            assert_eq!(decoder.raw_counter(), 1);
+        }
+
+        assert_eq!(decoder.counter(), 1);
+    }
+
+    #[test]
+    fn reversal() {
+        let mut decoder = Decoder::default().with_hysteresis(2);
+
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Positive)));
+        assert_eq!(update(&mut decoder, A0B0), Ok(Some(Positive)));
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Positive)));
+        assert_eq!(decoder.counter(), 3);
+
+        // Reversal, suppressed until travelled more than 2 changes:
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A0B1), Ok(None));
+        assert_eq!(update(&mut decoder, A1B1), Ok(Some(Negative)));
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Negative)));
+
+        assert_eq!(decoder.counter(), 1);
+        assert_eq!(decoder.raw_counter(), -1);
+
+        decoder.set_counter(0);
+
+        assert_eq!(decoder.counter(), 0);
+        assert_eq!(decoder.raw_counter(), 0);
+    }
+}
@@ -1,30 +1,615 @@
 //! Quadrature-based index decoder.
 
-/// A decoder for detecting the rising edge of the index signal (z channel).
+use num_traits::Bounded;
+
+use crate::{state_transducer::Input, Change};
+
+/// The edge of an index pulse at which its index event occurs.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum IndexEdge {
+    /// The pulse's leading (i.e. rising, unless active-low) edge, regardless of direction.
+    #[default]
+    Leading,
+    /// The pulse's leading edge when moving forwards and its trailing edge when moving backwards,
+    /// i.e. the same physical position in both directions.
+    ///
+    /// Pulses entered moving backwards only get accepted if they are left moving backwards as well.
+    Directional,
+    /// The pulse's center, i.e. the same physical position in both directions.
+    ///
+    /// Pulses only get accepted if they are left moving in the direction they were entered in.
+    Center,
+}
+
+/// The qualification criteria for accepting index pulses (z channel),
+/// rejecting spurious index pulses (e.g. due to noise).
+///
+/// By default any rising edge on the z channel gets accepted as an index event.
+///
+/// ```plain
+///                ┌───────┐       ┌───────┐       high
+///            A   │       │       │       │
+///              ──┘       └───────┘       └────── low
+///                    ┌───────┐       ┌───────┐   high
+///            B       │       │       │       │
+///              ──────┘       └───────┘       └── low
+///                  ┌─────────┐                   high
+///            Z     │  ┆gate┆ │
+///              ────┘  ┆    ┆ └────────────────── low
+///                  ◀── width ─▶
+/// ```
+///
+/// Pulse widths are measured in number of updates (i.e. samples),
+/// or in the units of the timestamps if updated with timestamps.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct IndexQualifier {
+    gate: Option<Input>,
+    edge: IndexEdge,
+    is_active_low: bool,
+    min_width: u32,
+    max_width: Option<u32>,
+    min_distance: Option<u32>,
+}
+
+impl IndexQualifier {
+    /// Creates a qualifier accepting any rising edge on the z channel.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the index gate, i.e. the `a` and `b` channel readings that need to coincide
+    /// with the active index pulse, with the index event occurring upon entering the gate.
+    pub fn with_gate(mut self, a: bool, b: bool) -> Self {
+        self.gate = Some(Input::new(a, b));
+        self
+    }
+
+    /// Sets the edge of the index pulse at which the index event occurs.
+    ///
+    /// Unless the index event occurs at the leading edge, it only gets accepted
+    /// (retroactively) at the end of the pulse.
+    pub fn with_edge(mut self, edge: IndexEdge) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    /// Makes the index pulse active-low, i.e. the index event occurring on the falling edge.
+    pub fn active_low(mut self) -> Self {
+        self.is_active_low = true;
+        self
+    }
+
+    /// Sets the minimum width of the index pulse, with narrower pulses getting rejected.
+    pub fn with_min_width(mut self, min_width: u32) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    /// Sets the maximum width of the index pulse, with wider pulses getting rejected.
+    ///
+    /// As a pulse's width is only known once it has ended, its index event
+    /// only gets accepted (retroactively) at the end of the pulse.
+    pub fn with_max_width(mut self, max_width: u32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets the minimum distance between accepted index events, in number of changes,
+    /// with index pulses closer to the previous index event getting rejected.
+    pub fn with_min_distance(mut self, min_distance: u32) -> Self {
+        self.min_distance = Some(min_distance);
+        self
+    }
+
+    /// Returns the index gate as `(a, b)`, if any.
+    pub fn gate(&self) -> Option<(bool, bool)> {
+        self.gate.map(|input| (input.a(), input.b()))
+    }
+
+    /// Returns the edge of the index pulse at which the index event occurs.
+    pub fn edge(&self) -> IndexEdge {
+        self.edge
+    }
+
+    /// Returns `true` if the index pulse is active-low, otherwise `false`.
+    pub fn is_active_low(&self) -> bool {
+        self.is_active_low
+    }
+
+    /// Returns the minimum width of the index pulse.
+    pub fn min_width(&self) -> u32 {
+        self.min_width
+    }
+
+    /// Returns the maximum width of the index pulse, if any.
+    pub fn max_width(&self) -> Option<u32> {
+        self.max_width
+    }
+
+    /// Returns the minimum distance between accepted index events, if any.
+    pub fn min_distance(&self) -> Option<u32> {
+        self.min_distance
+    }
+}
+
+/// An (active) index pulse.
+#[derive(Debug)]
+struct Pulse {
+    /// The time of the pulse's leading edge.
+    start: u32,
+    /// The number of changes since the pulse's leading edge.
+    travel: i32,
+    /// `true` if the pulse coincided with the gate (if any), otherwise `false`.
+    is_gated: bool,
+    /// `true` if the pulse's leading edge was crossed moving backwards, otherwise `false`.
+    is_backward: bool,
+    /// The number of changes since the pulse's (leading edge) index event, if any.
+    event: Option<i32>,
+    /// `true` if the pulse has been accepted or rejected already, otherwise `false`.
+    is_done: bool,
+}
+
+/// A decoder for detecting (qualified) index events on the index signal (z channel).
 ///
 /// ```plain
 ///               ┌─┐                         high
-/// Z             │ │                              
-///   ─ ─ ─ ──────┘ └────────────────── ─ ─ ─ low  
+/// Z             │ │
+///   ─ ─ ─ ──────┘ └────────────────── ─ ─ ─ low
 /// ```
 #[derive(Default, Debug)]
 pub(crate) struct IndexDecoder {
-    z: bool,
+    qualifier: IndexQualifier,
+    is_active: bool,
+    last_change: Option<Change>,
+    pulse: Option<Pulse>,
+    distance: Option<i32>,
 }
 
 impl IndexDecoder {
+    pub(crate) fn new(qualifier: IndexQualifier) -> Self {
+        Self {
+            qualifier,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the decoder's qualifier.
+    pub(crate) fn qualifier(&self) -> IndexQualifier {
+        self.qualifier
+    }
+
     /// Resets the decoder to the default state
-    pub fn reset(&mut self) {
-        self.z = false;
+    pub(crate) fn reset(&mut self) {
+        self.is_active = false;
+        self.last_change = None;
+        self.pulse = None;
+        self.distance = None;
+    }
+
+    /// Updates the internal state based on the z channel reading, the current `a` and `b`
+    /// channel `input` and the `change` it caused (if any) at time `now`.
+    ///
+    /// Returns the number of changes since the index event iff an index event got accepted,
+    /// otherwise `None`.
+    pub(crate) fn update(
+        &mut self,
+        z: bool,
+        input: Input,
+        change: Option<Change>,
+        now: u32,
+    ) -> Option<i32> {
+        if let Some(change) = change {
+            self.track(change);
+        }
+
+        let is_active = z != self.qualifier.is_active_low;
+        let was_active = core::mem::replace(&mut self.is_active, is_active);
+
+        match (was_active, is_active) {
+            (_, true) => {
+                if !was_active {
+                    self.pulse = Some(Pulse {
+                        start: now,
+                        travel: 0,
+                        is_gated: false,
+                        is_backward: self.last_change == Some(Change::Negative),
+                        event: None,
+                        is_done: false,
+                    });
+                }
+                self.update_pulse(input, now)
+            }
+            (true, false) => {
+                let pulse = self.pulse.take()?;
+                self.finish_pulse(pulse, now)
+            }
+            (false, false) => None,
+        }
+    }
+
+    fn track(&mut self, change: Change) {
+        let delta = i32::from(change as i8);
+
+        self.last_change = Some(change);
+
+        if let Some(distance) = self.distance.as_mut() {
+            *distance = distance.saturating_add(delta);
+        }
+
+        if let Some(pulse) = self.pulse.as_mut() {
+            pulse.travel = pulse.travel.saturating_add(delta);
+            if let Some(event) = pulse.event.as_mut() {
+                *event = event.saturating_add(delta);
+            }
+        }
+    }
+
+    fn update_pulse(&mut self, input: Input, now: u32) -> Option<i32> {
+        let qualifier = self.qualifier;
+        let pulse = self.pulse.as_mut()?;
+
+        if pulse.is_done {
+            return None;
+        }
+
+        let width = now.wrapping_sub(pulse.start);
+
+        if qualifier
+            .max_width
+            .is_some_and(|max_width| width > max_width)
+        {
+            pulse.is_done = true;
+            return None;
+        }
+
+        let is_gated = qualifier.gate.map_or(true, |gate| gate == input);
+        pulse.is_gated |= is_gated;
+
+        let is_leading = match qualifier.edge {
+            IndexEdge::Leading => true,
+            IndexEdge::Directional => !pulse.is_backward,
+            IndexEdge::Center => false,
+        };
+
+        if is_leading && is_gated && pulse.event.is_none() {
+            pulse.event = Some(0);
+        }
+
+        // Without a maximum width there is no need for waiting for the end of the pulse:
+        if qualifier.max_width.is_none() && width >= qualifier.min_width {
+            if let Some(event) = pulse.event {
+                pulse.is_done = true;
+                return self.accept(event);
+            }
+        }
+
+        None
+    }
+
+    fn finish_pulse(&mut self, pulse: Pulse, now: u32) -> Option<i32> {
+        let qualifier = self.qualifier;
+
+        if pulse.is_done || !pulse.is_gated {
+            return None;
+        }
+
+        let width = now.wrapping_sub(pulse.start);
+
+        if width < qualifier.min_width || qualifier.max_width.is_some_and(|max| width > max) {
+            return None;
+        }
+
+        // A pulse left through the edge it was entered by (i.e. after a reversal within the pulse)
+        // does not reveal the position of its other edge, nor of its center:
+        let is_traversed = pulse.is_backward == (self.last_change == Some(Change::Negative));
+
+        let event = match (qualifier.edge, pulse.event) {
+            (_, Some(event)) => event,
+            // Backwards, the leading edge's position is just past the trailing edge:
+            (IndexEdge::Directional, None) if pulse.is_backward && is_traversed => -1,
+            (IndexEdge::Center, None) if is_traversed => center_offset(pulse.travel),
+            _ => return None,
+        };
+
+        self.accept(event)
+    }
+
+    fn accept(&mut self, event: i32) -> Option<i32> {
+        if let (Some(min_distance), Some(distance)) = (self.qualifier.min_distance, self.distance) {
+            // The distance between the previous and the current index event:
+            let distance = distance.saturating_sub(event);
+            if distance.unsigned_abs() < min_distance {
+                return None;
+            }
+        }
+
+        self.distance = Some(event);
+
+        Some(event)
+    }
+}
+
+/// Returns the number of changes since a pulse's center, given its `travel`
+/// (i.e. the number of changes between its leading and its trailing edge).
+///
+/// For a pulse spanning `n` positions its center is at position `(n - 1) / 2`
+/// (relative to the pulse's first position, rounded down), regardless of direction.
+fn center_offset(travel: i32) -> i32 {
+    let span = travel.unsigned_abs().saturating_sub(1) as i32;
+    match travel.signum() {
+        1 => travel - span / 2,
+        -1 => -1 - span / 2,
+        _ => 0,
+    }
+}
+
+/// Converts `value` into a counter of type `T`, saturating at its bounds.
+pub(crate) fn counter_from<T>(value: i32) -> T
+where
+    T: Bounded + TryFrom<i32>,
+{
+    T::try_from(value).unwrap_or_else(|_| match value < 0 {
+        true => T::min_value(),
+        false => T::max_value(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Decoder = IndexDecoder;
+
+    /// Feeds the decoder with `(z, a, b, change)` samples, returning the accepted index events.
+    fn feed(decoder: &mut Decoder, samples: &[(bool, bool, bool, i8)]) -> Vec<Option<i32>> {
+        samples
+            .iter()
+            .enumerate()
+            .map(|(now, &(z, a, b, change))| {
+                let change = match change {
+                    1 => Some(Change::Positive),
+                    -1 => Some(Change::Negative),
+                    _ => None,
+                };
+                decoder.update(z, Input::new(a, b), change, now as u32)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rising_edge() {
+        let mut decoder = Decoder::default();
+
+        let events = feed(
+            &mut decoder,
+            &[
+                (false, true, true, 0),
+                (true, true, true, 0),
+                (true, true, true, 0),
+                (false, true, true, 0),
+            ],
+        );
+
+        assert_eq!(events, vec![None, Some(0), None, None]);
+    }
+
+    #[test]
+    fn gate() {
+        let mut decoder = Decoder::new(IndexQualifier::new().with_gate(true, true));
+
+        let events = feed(
+            &mut decoder,
+            &[
+                (true, false, true, 0),
+                (true, true, true, 1),
+                (true, true, false, 1),
+                (false, false, false, 1),
+                // Index pulse never coinciding with the gate:
+                (true, false, true, 0),
+                (false, false, true, 0),
+            ],
+        );
+
+        assert_eq!(events, vec![None, Some(0), None, None, None, None]);
+    }
+
+    #[test]
+    fn active_low() {
+        let mut decoder = Decoder::new(IndexQualifier::new().active_low());
+
+        let events = feed(
+            &mut decoder,
+            &[
+                (true, true, true, 0),
+                (false, true, true, 0),
+                (true, true, true, 0),
+            ],
+        );
+
+        assert_eq!(events, vec![None, Some(0), None]);
+    }
+
+    #[test]
+    fn pulse_width() {
+        let qualifier = IndexQualifier::new().with_min_width(2).with_max_width(3);
+        let mut decoder = Decoder::new(qualifier);
+
+        let events = feed(
+            &mut decoder,
+            &[
+                // Too narrow:
+                (true, true, true, 0),
+                (false, true, true, 0),
+                // Accepted retroactively, after 2 changes:
+                (true, true, true, 0),
+                (true, true, true, 1),
+                (true, true, true, 1),
+                (false, true, true, 0),
+                // Too wide:
+                (true, true, true, 0),
+                (true, true, true, 0),
+                (true, true, true, 0),
+                (true, true, true, 0),
+                (true, true, true, 0),
+                (false, true, true, 0),
+            ],
+        );
+
+        assert_eq!(events[..2], [None, None]);
+        assert_eq!(events[2..6], [None, None, None, Some(2)]);
+        assert!(events[6..].iter().all(Option::is_none));
     }
 
-    /// Updates the internal state and returns `true` iff it
-    /// detects a raising edge on the z channel, otherwise `false`.
-    pub fn update(&mut self, z: bool) -> bool {
-        let is_at_edge = self.z != z;
+    #[test]
+    fn min_width() {
+        let mut decoder = Decoder::new(IndexQualifier::new().with_min_width(1));
 
-        self.z = z;
+        let events = feed(
+            &mut decoder,
+            &[
+                (true, true, true, 0),
+                (true, true, true, 1),
+                (false, true, true, 0),
+            ],
+        );
+
+        // Accepted as soon as the minimum width is reached:
+        assert_eq!(events, vec![None, Some(1), None]);
+    }
+
+    #[test]
+    fn min_distance() {
+        let mut decoder = Decoder::new(IndexQualifier::new().with_min_distance(3));
+
+        let events = feed(
+            &mut decoder,
+            &[
+                (true, true, true, 0),
+                (false, true, true, 1),
+                (false, true, true, 1),
+                // Too close:
+                (true, true, true, 0),
+                (false, true, true, 1),
+                (true, true, true, 0),
+                (false, true, true, -1),
+                (false, true, true, -1),
+                (false, true, true, -1),
+                (false, true, true, -1),
+                // Far enough in the other direction:
+                (true, true, true, 0),
+            ],
+        );
+
+        assert_eq!(events[0], Some(0));
+        assert_eq!(events[5], Some(0));
+        assert_eq!(events[10], Some(0));
+        assert_eq!(events.iter().filter(|event| event.is_some()).count(), 3);
+    }
+
+    /// Sweeps the decoder across an index pulse spanning positions `5..=7`,
+    /// returning the physical position at which the accepted index event occurred.
+    fn sweep(edge: IndexEdge, is_forward: bool) -> Option<i32> {
+        let mut decoder = Decoder::new(IndexQualifier::new().with_edge(edge));
+        let positions: Vec<i32> = match is_forward {
+            true => (0..=12).collect(),
+            false => (0..=12).rev().collect(),
+        };
+
+        let change = match is_forward {
+            true => Change::Positive,
+            false => Change::Negative,
+        };
+
+        let mut zero = None;
+
+        for (now, &position) in positions.iter().enumerate() {
+            let change = (now > 0).then_some(change);
+            let z = (5..=7).contains(&position);
+            if let Some(event) = decoder.update(z, Input::new(true, true), change, now as u32) {
+                zero = Some(position - event);
+            }
+        }
+
+        zero
+    }
+
+    #[test]
+    fn leading_edge() {
+        assert_eq!(sweep(IndexEdge::Leading, true), Some(5));
+        assert_eq!(sweep(IndexEdge::Leading, false), Some(7));
+    }
+
+    #[test]
+    fn directional_edge() {
+        assert_eq!(sweep(IndexEdge::Directional, true), Some(5));
+        assert_eq!(sweep(IndexEdge::Directional, false), Some(5));
+    }
+
+    #[test]
+    fn center() {
+        assert_eq!(sweep(IndexEdge::Center, true), Some(6));
+        assert_eq!(sweep(IndexEdge::Center, false), Some(6));
+    }
+
+    /// Sweeps the decoder backwards into an index pulse spanning positions `5..=7`,
+    /// reversing at `reversal` and leaving it forwards again,
+    /// returning the physical position at which the accepted index event occurred.
+    fn reversing_sweep(edge: IndexEdge, reversal: i32) -> Option<i32> {
+        let mut decoder = Decoder::new(IndexQualifier::new().with_edge(edge));
+        let positions = (reversal..=12).rev().chain(reversal + 1..=12);
+
+        let mut zero = None;
+        let mut last_position = None;
+
+        for (now, position) in positions.enumerate() {
+            let change = last_position.map(|last| match position > last {
+                true => Change::Positive,
+                false => Change::Negative,
+            });
+            last_position = Some(position);
+            let z = (5..=7).contains(&position);
+            if let Some(event) = decoder.update(z, Input::new(true, true), change, now as u32) {
+                zero = Some(position - event);
+            }
+        }
+
+        zero
+    }
+
+    #[test]
+    fn reversal_within_pulse() {
+        for reversal in 5..=7 {
+            assert_eq!(reversing_sweep(IndexEdge::Directional, reversal), None);
+            assert_eq!(reversing_sweep(IndexEdge::Center, reversal), None);
+        }
+
+        // Reversing beyond the pulse traverses it in both directions:
+        assert_eq!(reversing_sweep(IndexEdge::Directional, 2), Some(5));
+        assert_eq!(reversing_sweep(IndexEdge::Center, 2), Some(6));
+    }
+
+    #[test]
+    fn center_without_travel() {
+        let mut decoder = Decoder::new(IndexQualifier::new().with_edge(IndexEdge::Center));
+
+        let events = feed(
+            &mut decoder,
+            &[
+                (true, true, true, 0),
+                (true, true, true, 0),
+                (false, true, true, 0),
+            ],
+        );
+
+        assert_eq!(events, vec![None, None, Some(0)]);
+    }
 
-        z && is_at_edge
+    #[test]
+    fn counter_conversion() {
+        assert_eq!(counter_from::<i32>(0), 0);
+        assert_eq!(counter_from::<i32>(-1000), -1000);
+        assert_eq!(counter_from::<i64>(i32::MIN), i64::from(i32::MIN));
+        assert_eq!(counter_from::<i16>(100_000), i16::MAX);
+        assert_eq!(counter_from::<i8>(-300), i8::MIN);
     }
 }
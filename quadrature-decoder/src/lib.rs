@@ -4,11 +4,43 @@
 #![cfg_attr(not(test), no_std)]
 
 mod decoder;
+mod differential;
+mod generator;
+mod hysteresis;
 mod index_decoder;
+mod linearity;
+mod monitor;
+mod odometer;
+#[cfg(feature = "simulator")]
+mod simulator;
+mod sin_cos;
 mod state_transducer;
+mod trigger;
 mod validator;
+mod vernier;
 
-pub use self::decoder::{IncrementalDecoder, IndexedIncrementalDecoder};
+pub use self::{
+    decoder::{
+        AbsoluteGrayDecoder, Alignment, BissDecoder, BissFrame, Coding, DistanceCodedDecoder,
+        HallAssistedDecoder, HallDecoder, IncrementalDecoder, IndexedIncrementalDecoder, Parity,
+        PwmDecoder, PwmFormat, SsiDecoder,
+    },
+    differential::{DifferentialOdometry, Pose},
+    generator::{Levels, QuadratureGenerator, Waveform},
+    index_decoder::{IndexEdge, IndexQualifier},
+    linearity::LinearityTable,
+    monitor::{SignalMonitor, SignalQuality},
+    odometer::{Odometer, OdometerStats},
+    sin_cos::{InterpolatedPosition, SinCosCalibration, SinCosDecoder},
+    trigger::PositionTrigger,
+    vernier::{Vernier, VernierDecoder},
+};
+
+#[cfg(feature = "float")]
+pub use self::differential::{DifferentialOdometryF32, PoseF32};
+
+#[cfg(feature = "simulator")]
+pub use self::simulator::{Anomalies, SignalSimulator, SimulatedSample, Simulation};
 
 use self::state_transducer::StateTransducer;
 
@@ -31,6 +63,136 @@ pub enum Error {
     E10_01 = 0b_10_01,
 }
 
+/// An error indicating an invalid absolute (i.e. parallel gray-code) encoder reading sequence.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AbsoluteError {
+    /// Invalid sequence of readings, differing by more than a single bit (as gray-code),
+    /// or by a single bit between non-adjacent positions.
+    Jump {
+        /// The reading before the jump (as gray-code).
+        from: u16,
+        /// The reading after the jump (as gray-code).
+        to: u16,
+    },
+}
+
+/// An error indicating an invalid PWM-output absolute encoder frame.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PwmError {
+    /// Missed edge, i.e. two consecutive edges of the same polarity.
+    Edge,
+    /// Frame period deviating from the previous frame's period.
+    Period,
+    /// High pulse shorter than the frame's init section.
+    Init,
+    /// High pulse ending within the frame's error section (i.e. the sensor reporting an error).
+    Sensor,
+    /// High pulse extending into the frame's exit section.
+    Exit,
+}
+
+/// An error indicating an invalid SSI (synchronous serial interface) frame.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SsiError {
+    /// Monoflop timeout violated, i.e. data line not (or no longer) held low by the encoder's monoflop.
+    Monoflop,
+    /// Parity mismatch.
+    Parity,
+    /// Error bit(s) set by the encoder.
+    Encoder,
+}
+
+/// An error indicating an invalid BiSS-C frame.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BissError {
+    /// Missing acknowledge, i.e. data line not pulled low by the slave.
+    Ack,
+    /// Missing start bit following the acknowledge.
+    Start,
+    /// Frame truncated, i.e. fewer bits received than expected.
+    Truncated,
+    /// CRC mismatch.
+    Crc,
+    /// Error bit set by the encoder.
+    Encoder,
+}
+
+/// An error indicating an invalid three-phase hall sensor (UVW) signal sequence.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HallError {
+    /// Invalid hall state `000` (i.e. all sensors low).
+    E000,
+    /// Invalid hall state `111` (i.e. all sensors high).
+    E111,
+    /// Invalid jump between two non-adjacent hall states (as `uvw` bits).
+    Jump {
+        /// The hall state before the jump.
+        from: u8,
+        /// The hall state after the jump.
+        to: u8,
+    },
+}
+
+/// An error indicating an invalid input of a hall-assisted incremental decoder.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HallAssistedError {
+    /// Invalid quadrature signal sequence.
+    Quadrature(Error),
+    /// Invalid hall sensor signal sequence.
+    Hall(HallError),
+}
+
+/// An error indicating an inconsistent distance-coded reference mark.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReferenceError {
+    /// Distance between two adjacent reference marks not matching any coded distance.
+    Spacing {
+        /// The distance between the reference marks (in number of changes).
+        distance: u32,
+    },
+    /// Reference mark at a position inconsistent with the established absolute position.
+    Mark {
+        /// The absolute position of the reference mark (in number of changes).
+        position: i32,
+    },
+}
+
+/// An error indicating an invalid input of a distance-coded incremental decoder.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DistanceCodedError {
+    /// Invalid quadrature signal sequence.
+    Quadrature(Error),
+    /// Inconsistent reference mark.
+    Reference(ReferenceError),
+}
+
+/// An error indicating an invalid linearity compensation table byte blob.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinearityError {
+    /// Unknown format tag.
+    Format,
+    /// Number of entries not matching the table's size.
+    Size,
+    /// Byte blob shorter or longer than expected.
+    Length,
+    /// Breakpoints not strictly increasing (or zero spacing).
+    Breakpoints,
+}
+
+/// An error indicating inconsistent phases of two vernier (aka nonius) tracks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VernierError {
+    /// Phases not corresponding to any position (within tolerance).
+    Inconsistent {
+        /// The deviation of the tracks' beat from the closest consistent position (in binary angle units).
+        deviation: u16,
+    },
+}
+
 /// The change detected by a quadrature decoder.
 #[repr(i8)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -103,3 +265,83 @@ impl StepMode for QuadStep {
     /// of a rotary encoder with 100 cycles per revolution (CPR): 400 PPR.
     const PULSES_PER_CYCLE: usize = 4;
 }
+
+/// A quadrature-based decoder's step mode (as opposed to a pulse-based one).
+pub trait QuadratureStepMode: StepMode {}
+
+impl QuadratureStepMode for FullStep {}
+impl QuadratureStepMode for HalfStep {}
+impl QuadratureStepMode for QuadStep {}
+
+/// A step mode that detects a "change" (e.g. movement) for every raising edge
+/// of a step pulse train (`a`), with the direction given by a direction pulse train (`b`):
+/// high for `Change::Positive`, low for `Change::Negative`.
+///
+/// Step/direction mode is commonly used by stepper and servo drives.
+///
+/// ```plain
+///                ┌─┐     ┌─┐     ┌─┐     ┌─┐     high
+///     Step (A)   │ │     │ │     │ │     │ │
+///              ──┘ └─────┘ └─────┘ └─────┘ └──── low
+///              ────────────────┐                 high
+///     Dir (B)                  │
+///                              └──────────────── low
+/// Change:         +       +       -       -
+/// ```
+pub struct StepDirection;
+
+impl sealed::Sealed for StepDirection {}
+impl StepMode for StepDirection {
+    /// The number of pulses per (step) cycle.
+    const PULSES_PER_CYCLE: usize = 1;
+}
+
+/// A step mode that detects a "change" (e.g. movement) for every raising and falling edge
+/// of a single pulse train (`a`), with the direction given by an external source (`b`):
+/// high for `Change::Positive`, low for `Change::Negative`.
+///
+/// Tachometer mode is used for sensors providing (or wired with) a single channel only,
+/// with the direction coming from a direction pin, a commanded direction
+/// (see `decoder.update_pulse(pulse, direction)`) or being constant for unidirectional movement.
+/// The initial level of the pulse train does not count as an edge.
+///
+/// ```plain
+///                ┌───┐   ┌───┐   ┌───┐           high
+///     Pulse (A)  │   │   │   │   │   │
+///              ──┘   └───┘   └───┘   └────────── low
+///              ────────────────┐                 high
+///     Dir (B)                  │
+///                              └──────────────── low
+/// Change:        +   +   +   +   -   -
+/// ```
+#[derive(Debug)]
+pub struct Tachometer;
+
+impl sealed::Sealed for Tachometer {}
+impl StepMode for Tachometer {
+    /// The number of pulses per (pulse) cycle, i.e. one per edge.
+    const PULSES_PER_CYCLE: usize = 2;
+}
+
+/// A step mode that detects a "change" (e.g. movement) for every raising edge
+/// of either an up pulse train (`a`, i.e. `Change::Positive`)
+/// or a down pulse train (`b`, i.e. `Change::Negative`).
+///
+/// Up/down mode is also commonly referred to as CW/CCW mode.
+///
+/// ```plain
+///                ┌─┐     ┌─┐                     high
+///     Up (A)     │ │     │ │
+///              ──┘ └─────┘ └──────────────────── low
+///                                ┌─┐     ┌─┐     high
+///     Down (B)                   │ │     │ │
+///              ──────────────────┘ └─────┘ └──── low
+/// Change:         +       +       -       -
+/// ```
+pub struct UpDown;
+
+impl sealed::Sealed for UpDown {}
+impl StepMode for UpDown {
+    /// The number of pulses per (up/down) cycle.
+    const PULSES_PER_CYCLE: usize = 1;
+}
@@ -0,0 +1,370 @@
+//! Linearity error compensation.
+
+use crate::LinearityError;
+
+/// The format tag of a table with equidistant breakpoints.
+const EQUIDISTANT: u8 = 0;
+
+/// The format tag of a table with arbitrary breakpoints.
+const ARBITRARY: u8 = 1;
+
+/// The size of a serialized table's header (i.e. format tag and number of entries), in bytes.
+const HEADER: usize = 3;
+
+/// The breakpoints of a linearity compensation table.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Breakpoints<const N: usize> {
+    /// Breakpoints at `start + i * spacing`.
+    Equidistant { start: i32, spacing: u32 },
+    /// Strictly increasing breakpoints.
+    Arbitrary([i32; N]),
+}
+
+/// A fixed-size linearity error compensation table (e.g. from a calibration against a laser interferometer),
+/// mapping positions to corrections, with linear interpolation between breakpoints.
+///
+/// ```plain
+/// Correction:              ●
+///                  ●      ╱ ╲
+///             ●───╱ ╲    ╱   ╲      ●───
+///                    ╲  ╱     ╲    ╱
+///                     ●        ╲  ╱
+///                               ●
+/// Position:   ┆   ┆   ┆    ┆    ┆   ┆
+///                 ◀─ breakpoints ─▶
+/// ```
+///
+/// Positions outside of the table's breakpoints get the correction of the closest breakpoint.
+///
+/// Tables can be loaded from byte blobs (see `LinearityTable::from_bytes(…)`),
+/// consisting of a format tag (`0`: equidistant, `1`: arbitrary breakpoints)
+/// and the number of entries (as `u16`), followed by either the first breakpoint (as `i32`)
+/// and the spacing (as `u32`), or the breakpoints (as `[i32; N]`), followed by the corrections
+/// (as `[i32; N]`), all in little-endian byte order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LinearityTable<const N: usize> {
+    breakpoints: Breakpoints<N>,
+    corrections: [i32; N],
+}
+
+impl<const N: usize> LinearityTable<N> {
+    /// Creates a table with equidistant breakpoints at `start + i * spacing` (in number of counts),
+    /// with `corrections[i]` being the correction at the `i`-th breakpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is empty, or if `spacing` is `0`.
+    pub fn equidistant(start: i32, spacing: u32, corrections: [i32; N]) -> Self {
+        assert!(N > 0, "Expected non-empty table.");
+        assert!(spacing > 0, "Expected non-zero spacing.");
+        Self {
+            breakpoints: Breakpoints::Equidistant { start, spacing },
+            corrections,
+        }
+    }
+
+    /// Creates a table with arbitrary `breakpoints` (in number of counts),
+    /// with `corrections[i]` being the correction at `breakpoints[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is empty, or if `breakpoints` are not strictly increasing.
+    pub fn new(breakpoints: [i32; N], corrections: [i32; N]) -> Self {
+        assert!(N > 0, "Expected non-empty table.");
+        assert!(
+            is_increasing(&breakpoints),
+            "Expected strictly increasing breakpoints."
+        );
+        Self {
+            breakpoints: Breakpoints::Arbitrary(breakpoints),
+            corrections,
+        }
+    }
+
+    /// Loads a table from the given byte blob (see `LinearityTable` for details on the format).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LinearityError> {
+        let mut reader = Reader { bytes };
+
+        let format = reader.read::<1>()?[0];
+        let count = u16::from_le_bytes(reader.read()?);
+
+        if N == 0 || usize::from(count) != N {
+            return Err(LinearityError::Size);
+        }
+
+        let breakpoints = match format {
+            EQUIDISTANT => {
+                let start = i32::from_le_bytes(reader.read()?);
+                let spacing = u32::from_le_bytes(reader.read()?);
+                if spacing == 0 {
+                    return Err(LinearityError::Breakpoints);
+                }
+                Breakpoints::Equidistant { start, spacing }
+            }
+            ARBITRARY => {
+                let breakpoints = reader.read_array()?;
+                if !is_increasing(&breakpoints) {
+                    return Err(LinearityError::Breakpoints);
+                }
+                Breakpoints::Arbitrary(breakpoints)
+            }
+            _ => return Err(LinearityError::Format),
+        };
+
+        let corrections = reader.read_array()?;
+
+        if !reader.bytes.is_empty() {
+            return Err(LinearityError::Length);
+        }
+
+        Ok(Self {
+            breakpoints,
+            corrections,
+        })
+    }
+
+    /// Returns the size of the table's byte blob (see `LinearityTable::from_bytes(…)`), in bytes.
+    pub fn byte_len(&self) -> usize {
+        let breakpoints = match self.breakpoints {
+            Breakpoints::Equidistant { .. } => 8,
+            Breakpoints::Arbitrary(_) => 4 * N,
+        };
+        HEADER + breakpoints + 4 * N
+    }
+
+    /// Returns the table's corrections.
+    pub fn corrections(&self) -> &[i32; N] {
+        &self.corrections
+    }
+
+    /// Returns the `i`-th breakpoint (as `i64`, as equidistant breakpoints may exceed `i32`).
+    fn breakpoint(&self, index: usize) -> i64 {
+        match &self.breakpoints {
+            Breakpoints::Equidistant { start, spacing } => {
+                i64::from(*start) + index as i64 * i64::from(*spacing)
+            }
+            Breakpoints::Arbitrary(breakpoints) => i64::from(breakpoints[index]),
+        }
+    }
+
+    /// Returns the index of the last breakpoint at or below `position`,
+    /// or `None` if `position` is below the first breakpoint.
+    fn segment(&self, position: i64) -> Option<usize> {
+        match &self.breakpoints {
+            Breakpoints::Equidistant { start, spacing } => {
+                let offset = position - i64::from(*start);
+                (offset >= 0).then(|| (offset / i64::from(*spacing)).min(N as i64 - 1) as usize)
+            }
+            Breakpoints::Arbitrary(breakpoints) => breakpoints
+                .partition_point(|&breakpoint| i64::from(breakpoint) <= position)
+                .checked_sub(1),
+        }
+    }
+
+    /// Returns the (interpolated) correction at `position`, in number of counts.
+    pub fn correction(&self, position: i32) -> i32 {
+        let position = i64::from(position);
+
+        let Some(index) = self.segment(position) else {
+            return self.corrections[0];
+        };
+
+        if index + 1 >= N {
+            return self.corrections[N - 1];
+        }
+
+        let (x0, x1) = (
+            i128::from(self.breakpoint(index)),
+            i128::from(self.breakpoint(index + 1)),
+        );
+        let (y0, y1) = (
+            i128::from(self.corrections[index]),
+            i128::from(self.corrections[index + 1]),
+        );
+
+        // Rounded to the nearest count (with ties rounding upwards),
+        // in 128-bit arithmetic, which cannot overflow for any `i32` table:
+        let span = x1 - x0;
+        let delta = (y1 - y0) * (i128::from(position) - x0);
+        let interpolated = y0 + (2 * delta + span).div_euclid(2 * span);
+
+        interpolated.clamp(i32::MIN.into(), i32::MAX.into()) as i32
+    }
+
+    /// Returns the compensated `position` (i.e. with the correction applied), in number of counts.
+    pub fn compensate(&self, position: i32) -> i32 {
+        position.saturating_add(self.correction(position))
+    }
+}
+
+/// Returns `true` if `values` are strictly increasing, otherwise `false`.
+fn is_increasing(values: &[i32]) -> bool {
+    values.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+/// A reader of little-endian values from a byte blob.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn read<const K: usize>(&mut self) -> Result<[u8; K], LinearityError> {
+        if self.bytes.len() < K {
+            return Err(LinearityError::Length);
+        }
+        let (head, tail) = self.bytes.split_at(K);
+        self.bytes = tail;
+
+        let mut array = [0; K];
+        array.copy_from_slice(head);
+        Ok(array)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[i32; N], LinearityError> {
+        let mut array = [0; N];
+        for value in array.iter_mut() {
+            *value = i32::from_le_bytes(self.read()?);
+        }
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the byte blob of a table with the given format tag, breakpoint bytes and corrections.
+    fn blob(format: u8, count: u16, breakpoints: &[u8], corrections: &[i32]) -> Vec<u8> {
+        let mut bytes = vec![format];
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend_from_slice(breakpoints);
+        for correction in corrections {
+            bytes.extend_from_slice(&correction.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn equidistant() {
+        let table = LinearityTable::equidistant(0, 100, [0, 10, -10, 0]);
+
+        assert_eq!(table.correction(-50), 0);
+        assert_eq!(table.correction(0), 0);
+        assert_eq!(table.correction(50), 5);
+        assert_eq!(table.correction(100), 10);
+        assert_eq!(table.correction(125), 5);
+        assert_eq!(table.correction(150), 0);
+        assert_eq!(table.correction(175), -5);
+        assert_eq!(table.correction(250), -5);
+        assert_eq!(table.correction(300), 0);
+        assert_eq!(table.correction(1000), 0);
+
+        assert_eq!(table.compensate(50), 55);
+        assert_eq!(table.compensate(-50), -50);
+    }
+
+    #[test]
+    fn arbitrary() {
+        let table = LinearityTable::new([-100, 0, 30], [4, -2, 1]);
+
+        assert_eq!(table.correction(-200), 4);
+        assert_eq!(table.correction(-50), 1);
+        assert_eq!(table.correction(0), -2);
+        assert_eq!(table.correction(10), -1);
+        assert_eq!(table.correction(15), 0);
+        assert_eq!(table.correction(30), 1);
+        assert_eq!(table.correction(i32::MAX), 1);
+    }
+
+    #[test]
+    fn rounding() {
+        let table = LinearityTable::new([0, 3], [0, 1]);
+
+        assert_eq!(table.correction(1), 0);
+        assert_eq!(table.correction(2), 1);
+
+        let table = LinearityTable::new([0, 4], [0, -2]);
+
+        assert_eq!(table.correction(1), 0);
+        assert_eq!(table.correction(3), -1);
+    }
+
+    #[test]
+    fn extreme_entries() {
+        let table = LinearityTable::new([i32::MIN, i32::MAX], [i32::MIN, i32::MAX]);
+
+        assert_eq!(table.correction(i32::MIN), i32::MIN);
+        assert_eq!(table.correction(0), 0);
+        assert_eq!(table.correction(i32::MAX), i32::MAX);
+        assert_eq!(table.compensate(0), 0);
+
+        let table = LinearityTable::equidistant(i32::MIN, u32::MAX, [i32::MAX, i32::MIN]);
+
+        assert_eq!(table.correction(-1), 0);
+        assert_eq!(table.correction(i32::MAX), i32::MIN);
+    }
+
+    #[test]
+    fn single_entry() {
+        let table = LinearityTable::equidistant(0, 1, [7]);
+
+        assert_eq!(table.correction(-1), 7);
+        assert_eq!(table.correction(1), 7);
+    }
+
+    #[test]
+    fn from_bytes() {
+        let mut breakpoints = Vec::new();
+        breakpoints.extend_from_slice(&(-100_i32).to_le_bytes());
+        breakpoints.extend_from_slice(&50_u32.to_le_bytes());
+        let bytes = blob(EQUIDISTANT, 3, &breakpoints, &[1, 2, 3]);
+
+        let table = LinearityTable::<3>::from_bytes(&bytes).unwrap();
+        assert_eq!(table, LinearityTable::equidistant(-100, 50, [1, 2, 3]));
+        assert_eq!(table.byte_len(), bytes.len());
+
+        let breakpoints: Vec<u8> = [-5_i32, 0, 5]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let bytes = blob(ARBITRARY, 3, &breakpoints, &[1, 2, 3]);
+
+        let table = LinearityTable::<3>::from_bytes(&bytes).unwrap();
+        assert_eq!(table, LinearityTable::new([-5, 0, 5], [1, 2, 3]));
+        assert_eq!(table.byte_len(), bytes.len());
+    }
+
+    #[test]
+    fn from_invalid_bytes() {
+        let breakpoints: Vec<u8> = [0_i32, 5].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let mut bytes = blob(ARBITRARY, 2, &breakpoints, &[1, 2]);
+
+        assert_eq!(
+            LinearityTable::<3>::from_bytes(&bytes),
+            Err(LinearityError::Size)
+        );
+        assert_eq!(
+            LinearityTable::<2>::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(LinearityError::Length)
+        );
+
+        bytes.push(0);
+        assert_eq!(
+            LinearityTable::<2>::from_bytes(&bytes),
+            Err(LinearityError::Length)
+        );
+
+        bytes[0] = 2;
+        assert_eq!(
+            LinearityTable::<2>::from_bytes(&bytes),
+            Err(LinearityError::Format)
+        );
+
+        let breakpoints: Vec<u8> = [5_i32, 0].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let bytes = blob(ARBITRARY, 2, &breakpoints, &[1, 2]);
+        assert_eq!(
+            LinearityTable::<2>::from_bytes(&bytes),
+            Err(LinearityError::Breakpoints)
+        );
+    }
+}
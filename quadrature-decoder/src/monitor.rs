@@ -0,0 +1,340 @@
+//! Signal-quality monitoring of quadrature pulse trains.
+
+use crate::{state_transducer::Input, validator::InputValidator, Change, Error};
+
+/// Soft-anomaly metrics gathered by a [`SignalMonitor`].
+///
+/// Unlike [`Error`], which reports definitive protocol violations,
+/// these metrics describe signal behavior that is still valid quadrature,
+/// but is indicative of a degrading signal (e.g. due to a damaged cable,
+/// a loose connector or a shaft resting exactly on a transition).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct SignalQuality {
+    /// Number of samples observed.
+    pub samples: u32,
+    /// Number of edges (i.e. input changes) observed.
+    pub edges: u32,
+    /// Number of invalid inputs observed (i.e. `Err(_)` results).
+    pub errors: u32,
+    /// Number of single-sample glitches observed (i.e. `x → y → x` input sequences).
+    pub glitches: u32,
+    /// Number of direction reversals observed within the monitor's flip window.
+    pub reversals: u32,
+    /// Smoothed mean interval between consecutive edges, in timestamp units.
+    ///
+    /// Only available if timestamps have been provided.
+    pub interval: u32,
+    /// Smoothed mean deviation between consecutive edge intervals, in timestamp units.
+    ///
+    /// Only available if timestamps have been provided.
+    pub jitter: u32,
+}
+
+impl SignalQuality {
+    /// Returns the number of anomalies (i.e. errors, glitches and reversals) observed.
+    pub fn anomalies(&self) -> u32 {
+        self.errors
+            .saturating_add(self.glitches)
+            .saturating_add(self.reversals)
+    }
+
+    /// Returns a coarse health score in the range of `0..=100`,
+    /// with `100` indicating a perfectly clean signal.
+    ///
+    /// The score is obtained by subtracting the percentage of anomalous edges
+    /// as well as the relative edge-timing jitter (in percent) from `100`.
+    pub fn health(&self) -> u8 {
+        let edges = u64::from(self.edges.max(1));
+        let anomaly_penalty = u64::from(self.anomalies()) * 100 / edges;
+
+        let jitter_penalty = match self.interval {
+            0 => 0,
+            interval => u64::from(self.jitter) * 100 / u64::from(interval),
+        };
+
+        100_u64.saturating_sub(anomaly_penalty + jitter_penalty) as u8
+    }
+}
+
+/// A monitor for tracking soft anomalies of a quadrature signal.
+///
+/// ```plain
+/// Glitch:            Reversal:                 Jitter:
+///     ┌─┐                ┌──┐  ┌──┐              ┌──┐   ┌─┐     ┌───┐
+/// A   │ │            A   │  │  │  │          A   │  │   │ │     │   │
+///   ──┘ └──            ──┘  └──┘  └──          ──┘  └───┘ └─────┘   └──
+/// ```
+#[derive(Clone, Debug)]
+pub struct SignalMonitor {
+    flip_window: u32,
+    quality: SignalQuality,
+    inputs: (Input, Input),
+    last_change: Option<Change>,
+    samples_since_change: u32,
+    last_edge_timestamp: Option<u32>,
+    last_interval: Option<u32>,
+    /// The smoothed interval and jitter estimates, scaled by 16 (as in RFC 3550),
+    /// to avoid truncation errors accumulating.
+    interval_estimate: u64,
+    jitter_estimate: u64,
+}
+
+impl Default for SignalMonitor {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_FLIP_WINDOW)
+    }
+}
+
+impl SignalMonitor {
+    /// The default number of samples within which
+    /// a direction reversal is considered a rapid flip.
+    pub const DEFAULT_FLIP_WINDOW: u32 = 8;
+
+    /// Creates a monitor that considers direction reversals
+    /// within `flip_window` samples of the previous change as rapid flips.
+    pub fn new(flip_window: u32) -> Self {
+        Self {
+            flip_window,
+            quality: Default::default(),
+            // The decoder's initial state corresponds to its initial input:
+            inputs: (InputValidator::INITIAL_INPUT, InputValidator::INITIAL_INPUT),
+            last_change: None,
+            samples_since_change: 0,
+            last_edge_timestamp: None,
+            last_interval: None,
+            interval_estimate: 0,
+            jitter_estimate: 0,
+        }
+    }
+
+    /// Returns the monitor's flip window in number of samples.
+    pub fn flip_window(&self) -> u32 {
+        self.flip_window
+    }
+
+    /// Returns the metrics gathered so far.
+    pub fn quality(&self) -> SignalQuality {
+        self.quality
+    }
+
+    /// Resets the monitor to its initial state, discarding all metrics.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.flip_window);
+    }
+
+    /// Records a single sample and the decoder's corresponding result.
+    pub(crate) fn observe(
+        &mut self,
+        input: Input,
+        result: Result<Option<Change>, Error>,
+        timestamp: Option<u32>,
+    ) {
+        let quality = &mut self.quality;
+
+        quality.samples = quality.samples.saturating_add(1);
+        self.samples_since_change = self.samples_since_change.saturating_add(1);
+
+        let (penultimate, last) = self.inputs;
+        self.inputs = (last, input);
+
+        if input != last {
+            quality.edges = quality.edges.saturating_add(1);
+
+            if input == penultimate && last != penultimate {
+                quality.glitches = quality.glitches.saturating_add(1);
+            }
+
+            if let Some(timestamp) = timestamp {
+                self.observe_edge_timing(timestamp);
+            }
+        }
+
+        match result {
+            Err(_) => {
+                self.quality.errors = self.quality.errors.saturating_add(1);
+            }
+            Ok(Some(change)) => {
+                let is_reversal = self.last_change.is_some_and(|last| last != change);
+                if is_reversal && self.samples_since_change <= self.flip_window {
+                    self.quality.reversals = self.quality.reversals.saturating_add(1);
+                }
+                self.last_change = Some(change);
+                self.samples_since_change = 0;
+            }
+            Ok(None) => {}
+        }
+    }
+
+    fn observe_edge_timing(&mut self, timestamp: u32) {
+        let last_timestamp = self.last_edge_timestamp.replace(timestamp);
+
+        let Some(last_timestamp) = last_timestamp else {
+            return;
+        };
+
+        let interval = timestamp.wrapping_sub(last_timestamp);
+        let last_interval = self.last_interval.replace(interval);
+
+        // Exponentially smoothed estimators with a gain of 1/16,
+        // as used for inter-arrival jitter in RFC 3550:
+        self.interval_estimate = match self.interval_estimate {
+            0 => u64::from(interval) << 4,
+            estimate => smooth(estimate, interval),
+        };
+
+        if let Some(last_interval) = last_interval {
+            let deviation = interval.abs_diff(last_interval);
+            self.jitter_estimate = smooth(self.jitter_estimate, deviation);
+        }
+
+        let quality = &mut self.quality;
+        quality.interval = unscale(self.interval_estimate);
+        quality.jitter = unscale(self.jitter_estimate);
+    }
+}
+
+/// Returns the updated `estimate` (scaled by 16) for the given (unscaled) `sample`,
+/// i.e. `estimate + (sample - estimate) / 16` (unscaled).
+fn smooth(estimate: u64, sample: u32) -> u64 {
+    estimate + u64::from(sample) - ((estimate + 8) >> 4)
+}
+
+/// Returns the given `estimate` (scaled by 16), unscaled and rounded.
+fn unscale(estimate: u64) -> u32 {
+    ((estimate + 8) >> 4).min(u64::from(u32::MAX)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IncrementalDecoder, QuadStep};
+
+    use super::*;
+
+    type Decoder = IncrementalDecoder<QuadStep>;
+
+    #[test]
+    fn clean() {
+        let mut decoder = Decoder::default().monitored(SignalMonitor::default());
+
+        let inputs = [(false, true), (false, false), (true, false), (true, true)];
+        for (index, (a, b)) in inputs.into_iter().cycle().take(16).enumerate() {
+            decoder.update_at(a, b, index as u32 * 100).unwrap();
+        }
+
+        let quality = decoder.monitor().unwrap().quality();
+
+        assert_eq!(quality.samples, 16);
+        // The first sample is an edge from the decoder's initial input (i.e. A1B1):
+        assert_eq!(quality.edges, 16);
+        assert_eq!(quality.anomalies(), 0);
+        assert_eq!(quality.jitter, 0);
+        assert_eq!(quality.health(), 100);
+    }
+
+    #[test]
+    fn glitch() {
+        let mut decoder = Decoder::default().monitored(SignalMonitor::default());
+
+        decoder.update(true, true).unwrap();
+        decoder.update(false, true).unwrap();
+        decoder.update(true, true).unwrap();
+
+        let quality = decoder.monitor().unwrap().quality();
+
+        assert_eq!(quality.glitches, 1);
+        assert_eq!(quality.reversals, 1);
+        assert!(quality.health() < 100);
+    }
+
+    #[test]
+    fn reversal_outside_window() {
+        let mut decoder = Decoder::default().monitored(SignalMonitor::new(2));
+
+        decoder.update(false, true).unwrap();
+        decoder.update(false, true).unwrap();
+        decoder.update(false, true).unwrap();
+        decoder.update(true, true).unwrap();
+
+        let quality = decoder.monitor().unwrap().quality();
+
+        assert_eq!(quality.glitches, 0);
+        assert_eq!(quality.reversals, 0);
+    }
+
+    #[test]
+    fn errors() {
+        let mut decoder = Decoder::default().monitored(SignalMonitor::default());
+
+        assert!(decoder.update(false, false).is_err());
+
+        let quality = decoder.monitor().unwrap().quality();
+
+        assert_eq!(quality.errors, 1);
+        assert_eq!(quality.health(), 0);
+    }
+
+    #[test]
+    fn jitter() {
+        let mut decoder = Decoder::default().monitored(SignalMonitor::default());
+
+        let inputs = [(false, true), (false, false), (true, false), (true, true)];
+        let timestamps = [0, 100, 300, 400, 600, 700, 900, 1000];
+        for ((a, b), timestamp) in inputs.into_iter().cycle().zip(timestamps) {
+            decoder.update_at(a, b, timestamp).unwrap();
+        }
+
+        let quality = decoder.monitor().unwrap().quality();
+
+        assert!(quality.jitter > 0);
+        assert!(quality.health() < 100);
+    }
+
+    #[test]
+    fn jitter_decay() {
+        let mut decoder = Decoder::default().monitored(SignalMonitor::default());
+
+        let inputs = [(false, true), (false, false), (true, false), (true, true)];
+
+        // A transient burst of jitter, followed by perfectly regular edges:
+        let burst = [0, 100, 300, 400, 600];
+        let regular = (1..=200).map(|index| 600 + index * 100);
+        for ((a, b), timestamp) in inputs
+            .into_iter()
+            .cycle()
+            .zip(burst.into_iter().chain(regular))
+        {
+            decoder.update_at(a, b, timestamp).unwrap();
+        }
+
+        let quality = decoder.monitor().unwrap().quality();
+
+        assert_eq!(quality.interval, 100);
+        assert_eq!(quality.jitter, 0);
+        assert_eq!(quality.health(), 100);
+    }
+
+    #[test]
+    fn smoothing() {
+        // Small deviations are not swallowed by truncation:
+        let mut estimate = 0;
+        for _ in 0..64 {
+            estimate = smooth(estimate, 15);
+        }
+        assert_eq!(unscale(estimate), 15);
+
+        for _ in 0..128 {
+            estimate = smooth(estimate, 0);
+        }
+        assert_eq!(unscale(estimate), 0);
+    }
+
+    #[test]
+    fn initial_edge() {
+        let mut decoder = Decoder::default().monitored(SignalMonitor::default());
+
+        // A single sample, deviating from the decoder's initial input:
+        decoder.update(false, true).unwrap();
+
+        assert_eq!(decoder.monitor().unwrap().quality().edges, 1);
+    }
+}
@@ -0,0 +1,252 @@
+//! Lifetime usage statistics (aka odometer) of quadrature encoders.
+
+use crate::Change;
+
+/// Lifetime usage statistics gathered by an [`Odometer`].
+///
+/// Statistics can be persisted (e.g. in non-volatile memory) via `stats.to_bytes()`
+/// and restored via `OdometerStats::from_bytes(…)` and `odometer.with_stats(…)`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct OdometerStats {
+    /// Total absolute travel (i.e. the sum of `|Δ|`), in number of changes.
+    pub travel: u64,
+    /// Number of direction reversals.
+    pub reversals: u32,
+    /// Shortest interval between two consecutive changes in the same direction
+    /// (i.e. the inverse of the maximum observed speed), in time units.
+    pub shortest_interval: Option<u32>,
+    /// Total time in motion (i.e. the sum of intervals between consecutive changes
+    /// not exceeding the odometer's standstill threshold), in time units.
+    pub motion: u64,
+}
+
+impl OdometerStats {
+    /// The size of the statistics' byte representation, in bytes.
+    pub const BYTES: usize = 24;
+
+    /// Returns the maximum observed speed, in number of changes per second,
+    /// given the number of time units per second (e.g. `1_000_000` for microseconds).
+    pub fn max_speed(&self, units_per_second: u32) -> u32 {
+        match self.shortest_interval {
+            Some(interval) => units_per_second / interval.max(1),
+            None => 0,
+        }
+    }
+
+    /// Returns the statistics' byte representation (in little-endian byte order),
+    /// with a missing shortest interval being represented as `0`.
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut bytes = [0; Self::BYTES];
+        bytes[0..8].copy_from_slice(&self.travel.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.reversals.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.shortest_interval.unwrap_or(0).to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.motion.to_le_bytes());
+        bytes
+    }
+
+    /// Returns the statistics of the given byte representation (see `stats.to_bytes()`).
+    pub fn from_bytes(bytes: &[u8; Self::BYTES]) -> Self {
+        let u32_at = |index: usize| {
+            let mut array = [0; 4];
+            array.copy_from_slice(&bytes[index..index + 4]);
+            u32::from_le_bytes(array)
+        };
+        let u64_at = |index: usize| {
+            let mut array = [0; 8];
+            array.copy_from_slice(&bytes[index..index + 8]);
+            u64::from_le_bytes(array)
+        };
+
+        Self {
+            travel: u64_at(0),
+            reversals: u32_at(8),
+            shortest_interval: Some(u32_at(12)).filter(|interval| *interval > 0),
+            motion: u64_at(16),
+        }
+    }
+}
+
+/// An odometer for gathering lifetime usage statistics (e.g. for scheduling maintenance
+/// of guide rails and bearings), i.e. total travel, reversals, maximum speed and time in motion.
+///
+/// Time is measured in number of updates, or in the units of the timestamps
+/// if updated with timestamps (which should not be mixed).
+///
+/// ```plain
+/// Changes:   + + +   + +  -  -             - -
+///            ◀─────────────────▶           ◀─▶
+///                 in motion      standstill
+/// ```
+#[derive(Clone, Debug)]
+pub struct Odometer {
+    standstill: u32,
+    stats: OdometerStats,
+    samples: u32,
+    last_change: Option<(Change, u32)>,
+}
+
+impl Default for Odometer {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_STANDSTILL)
+    }
+}
+
+impl Odometer {
+    /// The default interval between two consecutive changes (in time units)
+    /// beyond which the encoder is considered to have been standing still.
+    pub const DEFAULT_STANDSTILL: u32 = 1000;
+
+    /// Creates an odometer that considers intervals between two consecutive changes
+    /// beyond `standstill` time units as standstill.
+    pub fn new(standstill: u32) -> Self {
+        Self {
+            standstill,
+            stats: Default::default(),
+            samples: 0,
+            last_change: None,
+        }
+    }
+
+    /// Sets the odometer's statistics (e.g. as restored from non-volatile memory).
+    pub fn with_stats(mut self, stats: OdometerStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Returns the odometer's standstill threshold, in time units.
+    pub fn standstill(&self) -> u32 {
+        self.standstill
+    }
+
+    /// Returns the statistics gathered so far.
+    pub fn stats(&self) -> OdometerStats {
+        self.stats
+    }
+
+    /// Resets the odometer to its initial state, discarding all statistics.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.standstill);
+    }
+
+    /// Records the `change` (if any) of a single update, with time being measured in number of updates.
+    pub fn update(&mut self, change: Option<Change>) {
+        let now = self.samples;
+        self.samples = self.samples.wrapping_add(1);
+
+        self.record(change, now);
+    }
+
+    /// Records the `change` (if any) of a single update at the given `timestamp`
+    /// (in arbitrary, but monotonic units, e.g. microseconds).
+    pub fn update_at(&mut self, change: Option<Change>, timestamp: u32) {
+        self.record(change, timestamp);
+    }
+
+    fn record(&mut self, change: Option<Change>, now: u32) {
+        let Some(change) = change else {
+            return;
+        };
+
+        let stats = &mut self.stats;
+        stats.travel = stats.travel.saturating_add(1);
+
+        let Some((last_change, last_timestamp)) = self.last_change.replace((change, now)) else {
+            return;
+        };
+
+        let interval = now.wrapping_sub(last_timestamp);
+
+        if interval <= self.standstill {
+            stats.motion = stats.motion.saturating_add(u64::from(interval));
+        }
+
+        if change != last_change {
+            stats.reversals = stats.reversals.saturating_add(1);
+        } else {
+            let interval = interval.max(1);
+            stats.shortest_interval = Some(match stats.shortest_interval {
+                Some(shortest) => shortest.min(interval),
+                None => interval,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: Option<Change> = Some(Change::Positive);
+    const N: Option<Change> = Some(Change::Negative);
+
+    #[test]
+    fn travel_and_reversals() {
+        let mut odometer = Odometer::default();
+
+        for change in [P, P, None, P, N, N, None, P] {
+            odometer.update(change);
+        }
+
+        let stats = odometer.stats();
+        assert_eq!(stats.travel, 6);
+        assert_eq!(stats.reversals, 2);
+        assert_eq!(stats.shortest_interval, Some(1));
+        assert_eq!(stats.motion, 7);
+    }
+
+    #[test]
+    fn speed_and_motion() {
+        let mut odometer = Odometer::new(500);
+
+        // Moving at one change per 100µs, then per 50µs, standing still, moving again:
+        for (change, timestamp) in [
+            (P, 0),
+            (P, 100),
+            (P, 200),
+            (P, 250),
+            (P, 300),
+            (P, 5300),
+            (P, 5400),
+        ] {
+            odometer.update_at(change, timestamp);
+        }
+
+        let stats = odometer.stats();
+        assert_eq!(stats.travel, 7);
+        assert_eq!(stats.reversals, 0);
+        assert_eq!(stats.shortest_interval, Some(50));
+        assert_eq!(stats.max_speed(1_000_000), 20_000);
+        assert_eq!(stats.motion, 400);
+    }
+
+    #[test]
+    fn persistence() {
+        let mut odometer = Odometer::default();
+
+        for change in [P, P, N] {
+            odometer.update(change);
+        }
+
+        let bytes = odometer.stats().to_bytes();
+        let stats = OdometerStats::from_bytes(&bytes);
+        assert_eq!(stats, odometer.stats());
+
+        // Restored statistics keep accumulating:
+        let mut odometer = Odometer::default().with_stats(stats);
+        odometer.update(P);
+        assert_eq!(odometer.stats().travel, 4);
+
+        let empty = OdometerStats::from_bytes(&OdometerStats::default().to_bytes());
+        assert_eq!(empty, OdometerStats::default());
+    }
+
+    #[test]
+    fn reset() {
+        let mut odometer = Odometer::new(10);
+        odometer.update(P);
+        odometer.reset();
+
+        assert_eq!(odometer.stats(), OdometerStats::default());
+        assert_eq!(odometer.standstill(), 10);
+    }
+}
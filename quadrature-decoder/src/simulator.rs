@@ -0,0 +1,465 @@
+//! A deterministic simulator of noisy quadrature signals, for testing purposes.
+
+use num_traits::{One, SaturatingAdd, Signed, Zero};
+
+use crate::{Change, Levels, QuadratureGenerator, QuadratureStepMode};
+
+const MAX_PHASE_SKEW: u8 = 8;
+
+/// The anomalies injected into a simulated sample.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Anomalies {
+    /// The sample is part of a contact bounce, following an edge.
+    pub bounce: bool,
+    /// The sample has a single channel flipped (i.e. a glitch).
+    pub glitch: bool,
+    /// The number of (ideal) samples that got missed right before this sample.
+    pub missed: u8,
+    /// The sample's (delayed) `b` level differs from its ideal level due to phase skew.
+    ///
+    /// This refers to the skewed level that any glitch or bounce gets applied on top of,
+    /// i.e. it remains set on a glitched or bouncing sample, even if its `b` level got flipped.
+    pub skew: bool,
+}
+
+impl Anomalies {
+    /// Returns `true` if no anomalies got injected, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A sample produced by a [`SignalSimulator`], annotated with its ground-truth.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SimulatedSample<T = i32> {
+    /// The simulated levels, including injected anomalies.
+    pub levels: Levels,
+    /// The ideal levels, without any anomalies.
+    pub ideal: Levels,
+    /// The ideal position, in number of changes.
+    pub position: T,
+    /// The anomalies injected into the sample.
+    pub anomalies: Anomalies,
+}
+
+/// A seeded, deterministic simulator of noisy quadrature signals.
+///
+/// The simulator turns an ideal motion profile (i.e. a sequence of target positions,
+/// one per sample period) into a stream of samples suitable for feeding into
+/// a decoder's `update()` method, while injecting anomalies such as contact bounce,
+/// single-sample glitches, missed samples and phase skew between the channels.
+///
+/// Whenever a target position is more than a single quarter cycle away,
+/// the simulator emits a sample for every quarter cycle on its way to the target.
+///
+/// Probabilities are provided in per-mille (i.e. `0..=1000`).
+#[derive(Debug)]
+pub struct SignalSimulator<Mode, T = i32> {
+    generator: QuadratureGenerator<Mode, T>,
+    rng: XorShift32,
+    bounce_probability: u16,
+    bounces: u8,
+    glitch_probability: u16,
+    missed_probability: u16,
+    phase_skew: u8,
+}
+
+impl<Mode, T> SignalSimulator<Mode, T>
+where
+    Mode: QuadratureStepMode,
+    T: Copy + Zero + One + Signed + Ord + SaturatingAdd + From<i8>,
+{
+    /// The maximum supported phase skew, in number of samples.
+    pub const MAX_PHASE_SKEW: u8 = MAX_PHASE_SKEW;
+
+    /// Creates an anomaly-free simulator with the given `seed`.
+    pub fn new(seed: u32) -> Self {
+        Self::with_generator(seed, Default::default())
+    }
+
+    /// Creates an anomaly-free simulator with the given `seed`,
+    /// using the given generator (e.g. one with an index period).
+    pub fn with_generator(seed: u32, generator: QuadratureGenerator<Mode, T>) -> Self {
+        Self {
+            generator,
+            rng: XorShift32::new(seed),
+            bounce_probability: 0,
+            bounces: 0,
+            glitch_probability: 0,
+            missed_probability: 0,
+            phase_skew: 0,
+        }
+    }
+
+    /// Makes edges bounce `bounces` times with the given `probability` (in per-mille).
+    pub fn with_bounce(mut self, probability: u16, bounces: u8) -> Self {
+        self.bounce_probability = probability;
+        self.bounces = bounces;
+        self
+    }
+
+    /// Makes samples glitch with the given `probability` (in per-mille).
+    pub fn with_glitches(mut self, probability: u16) -> Self {
+        self.glitch_probability = probability;
+        self
+    }
+
+    /// Makes samples get missed with the given `probability` (in per-mille).
+    pub fn with_missed_samples(mut self, probability: u16) -> Self {
+        self.missed_probability = probability;
+        self
+    }
+
+    /// Delays the `b` channel by `phase_skew` samples relative to the `a` channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `phase_skew` exceeds `Self::MAX_PHASE_SKEW`.
+    pub fn with_phase_skew(mut self, phase_skew: u8) -> Self {
+        assert!(
+            phase_skew <= Self::MAX_PHASE_SKEW,
+            "Expected phase skew of at most {} samples.",
+            Self::MAX_PHASE_SKEW
+        );
+        self.phase_skew = phase_skew;
+        self
+    }
+
+    /// Returns the simulator's (ideal) position, in number of changes.
+    pub fn position(&self) -> T {
+        self.generator.position()
+    }
+
+    /// Returns an iterator of the simulated samples for the given motion `profile`.
+    pub fn simulate<I>(&mut self, profile: I) -> Simulation<'_, Mode, T, I::IntoIter>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let levels = self.generator.levels();
+        Simulation {
+            simulator: self,
+            profile: profile.into_iter(),
+            target: None,
+            ideal: levels,
+            skewed: levels,
+            b_history: [levels.b; MAX_PHASE_SKEW as usize + 1],
+            bounce: None,
+        }
+    }
+
+    fn chance(&mut self, probability: u16) -> bool {
+        probability > 0 && (self.rng.next() % 1000) < u32::from(probability)
+    }
+}
+
+#[derive(Debug)]
+struct Bounce {
+    levels: Levels,
+    flip_a: bool,
+    remaining: u8,
+}
+
+/// An iterator of simulated samples.
+///
+/// Returned by `simulator.simulate(profile)`.
+#[derive(Debug)]
+pub struct Simulation<'a, Mode, T, I> {
+    simulator: &'a mut SignalSimulator<Mode, T>,
+    profile: I,
+    target: Option<T>,
+    ideal: Levels,
+    skewed: Levels,
+    b_history: [bool; MAX_PHASE_SKEW as usize + 1],
+    bounce: Option<Bounce>,
+}
+
+impl<Mode, T, I> Simulation<'_, Mode, T, I>
+where
+    Mode: QuadratureStepMode,
+    T: Copy + Zero + One + Signed + Ord + SaturatingAdd + From<i8>,
+    I: Iterator<Item = T>,
+{
+    fn next_ideal(&mut self) -> Option<Levels> {
+        let generator = &mut self.simulator.generator;
+
+        let target = match self.target {
+            Some(target) if target != generator.position() => target,
+            _ => {
+                // Each target of the profile makes for at least one sample:
+                let target = self.profile.next()?;
+                self.target = Some(target);
+                if target == generator.position() {
+                    return Some(generator.levels());
+                }
+                target
+            }
+        };
+
+        let change = match target > generator.position() {
+            true => Change::Positive,
+            false => Change::Negative,
+        };
+
+        Some(generator.step(change))
+    }
+
+    fn skew(&mut self, ideal: Levels) -> Levels {
+        let skew = self.simulator.phase_skew as usize;
+
+        if skew == 0 {
+            return ideal;
+        }
+
+        self.b_history.rotate_right(1);
+        self.b_history[0] = ideal.b;
+
+        Levels {
+            b: self.b_history[skew],
+            ..ideal
+        }
+    }
+}
+
+impl<Mode, T, I> Iterator for Simulation<'_, Mode, T, I>
+where
+    Mode: QuadratureStepMode,
+    T: Copy + Zero + One + Signed + Ord + SaturatingAdd + From<i8>,
+    I: Iterator<Item = T>,
+{
+    type Item = SimulatedSample<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let position = self.simulator.position();
+
+        if let Some(bounce) = self.bounce.as_mut() {
+            bounce.remaining -= 1;
+
+            // Alternate between the edge's previous and new level:
+            let is_reverted = bounce.remaining % 2 == 1;
+            let mut levels = bounce.levels;
+            if is_reverted {
+                match bounce.flip_a {
+                    true => levels.a = !levels.a,
+                    false => levels.b = !levels.b,
+                }
+            }
+
+            let skew = bounce.levels.b != self.ideal.b;
+
+            if bounce.remaining == 0 {
+                self.bounce = None;
+            }
+
+            return Some(SimulatedSample {
+                levels,
+                ideal: self.ideal,
+                position,
+                anomalies: Anomalies {
+                    bounce: true,
+                    skew,
+                    ..Default::default()
+                },
+            });
+        }
+
+        let mut anomalies = Anomalies::default();
+
+        let ideal = loop {
+            let ideal = self.next_ideal()?;
+            self.ideal = ideal;
+
+            if !self.simulator.chance(self.simulator.missed_probability) {
+                break ideal;
+            }
+
+            anomalies.missed = anomalies.missed.saturating_add(1);
+        };
+
+        // Edges (and thus bounces) follow the skewed levels, rather than the ideal ones:
+        let previous = self.skewed;
+        let mut levels = self.skew(ideal);
+        self.skewed = levels;
+        anomalies.skew = levels.b != ideal.b;
+
+        if self.simulator.chance(self.simulator.glitch_probability) {
+            match self.simulator.rng.next() % 2 == 0 {
+                true => levels.a = !levels.a,
+                false => levels.b = !levels.b,
+            }
+            anomalies.glitch = true;
+        } else if previous != levels {
+            let bounces = self.simulator.bounces;
+            if bounces > 0 && self.simulator.chance(self.simulator.bounce_probability) {
+                self.bounce = Some(Bounce {
+                    levels,
+                    flip_a: previous.a != levels.a,
+                    remaining: bounces.saturating_mul(2),
+                });
+            }
+        }
+
+        Some(SimulatedSample {
+            levels,
+            ideal,
+            position: self.simulator.position(),
+            anomalies,
+        })
+    }
+}
+
+/// A minimal xorshift pseudo-random number generator,
+/// for deterministic (i.e. seeded) anomaly injection.
+#[derive(Debug)]
+struct XorShift32 {
+    state: u32,
+}
+
+impl XorShift32 {
+    fn new(seed: u32) -> Self {
+        // The all-zero state is a fixed point of xorshift:
+        let state = match seed {
+            0 => 0x9E37_79B9,
+            seed => seed,
+        };
+        Self { state }
+    }
+
+    fn next(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IncrementalDecoder, QuadStep};
+
+    use super::*;
+
+    type Simulator = SignalSimulator<QuadStep>;
+
+    fn profile() -> impl Iterator<Item = i32> {
+        (0..200)
+            .chain((0..200).rev())
+            .chain(core::iter::repeat(0).take(10))
+    }
+
+    #[test]
+    fn ideal() {
+        let mut simulator = Simulator::new(42);
+        let mut decoder: IncrementalDecoder<QuadStep> = Default::default();
+
+        for sample in simulator.simulate(profile()) {
+            assert!(sample.anomalies.is_empty());
+            assert_eq!(sample.levels, sample.ideal);
+            assert!(decoder.update(sample.levels.a, sample.levels.b).is_ok());
+            assert_eq!(decoder.counter(), sample.position);
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let samples = |seed| -> Vec<SimulatedSample> {
+            Simulator::new(seed)
+                .with_bounce(100, 2)
+                .with_glitches(50)
+                .with_missed_samples(50)
+                .simulate(profile())
+                .collect()
+        };
+
+        assert_eq!(samples(1), samples(1));
+        assert_ne!(samples(1), samples(2));
+    }
+
+    #[test]
+    fn bounce() {
+        let mut simulator = Simulator::new(7).with_bounce(200, 2);
+        let mut decoder: IncrementalDecoder<QuadStep> = Default::default();
+
+        let mut bounces = 0;
+        for sample in simulator.simulate(profile()) {
+            if sample.anomalies.bounce {
+                bounces += 1;
+            }
+            // Contact bounce is valid quadrature and must not cause errors:
+            assert!(decoder.update(sample.levels.a, sample.levels.b).is_ok());
+        }
+
+        assert!(bounces > 0);
+        assert_eq!(decoder.counter(), 0);
+    }
+
+    #[test]
+    fn glitches() {
+        let mut simulator = Simulator::new(7).with_glitches(50);
+
+        let mut glitches = 0;
+        for sample in simulator.simulate(profile()) {
+            let flipped = [
+                sample.levels.a != sample.ideal.a,
+                sample.levels.b != sample.ideal.b,
+            ];
+            match sample.anomalies.glitch {
+                true => {
+                    glitches += 1;
+                    assert_eq!(flipped.iter().filter(|&&flipped| flipped).count(), 1);
+                }
+                false => assert_eq!(flipped, [false, false]),
+            }
+        }
+
+        assert!(glitches > 0);
+    }
+
+    #[test]
+    fn missed_samples() {
+        let mut simulator = Simulator::new(3).with_missed_samples(100);
+        let mut decoder: IncrementalDecoder<QuadStep> = Default::default();
+
+        for sample in simulator.simulate(profile()) {
+            let result = decoder.update(sample.levels.a, sample.levels.b);
+            if result.is_err() {
+                assert!(sample.anomalies.missed > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn phase_skew() {
+        let mut simulator = Simulator::new(0).with_phase_skew(Simulator::MAX_PHASE_SKEW);
+
+        let skewed = simulator
+            .simulate(profile())
+            .filter(|sample| sample.anomalies.skew)
+            .count();
+
+        assert!(skewed > 0);
+    }
+
+    #[test]
+    fn phase_skew_with_anomalies() {
+        let mut simulator = Simulator::new(5)
+            .with_phase_skew(2)
+            .with_bounce(200, 2)
+            .with_glitches(100);
+
+        let mut disturbed = 0;
+        for sample in simulator.simulate(profile()) {
+            let is_disturbed = sample.anomalies.glitch || sample.anomalies.bounce;
+            // Unless disturbed on the `b` channel, the sample's `b` level is the skewed one:
+            if !is_disturbed || sample.levels.a != sample.ideal.a {
+                assert_eq!(sample.anomalies.skew, sample.levels.b != sample.ideal.b);
+            }
+            if sample.anomalies.skew && is_disturbed {
+                disturbed += 1;
+            }
+        }
+
+        assert!(disturbed > 0);
+    }
+}
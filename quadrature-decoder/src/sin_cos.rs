@@ -0,0 +1,598 @@
+//! Sine/cosine (e.g. 1 Vpp) analog encoder decoding and interpolation.
+
+use num_traits::{One, SaturatingAdd, Zero};
+
+use crate::{Change, Error, IncrementalDecoder, QuadratureStepMode};
+
+/// The fixed-point scale of normalized signals (i.e. Q14).
+const UNITY: i32 = 1 << 14;
+
+/// The number of bits of the binary angles, as returned by `atan2()`.
+const ANGLE_BITS: u32 = 16;
+
+/// The calibration of a pair of sine/cosine signals.
+///
+/// The `a` signal is expected to follow `offset_a + amplitude_a * cos(θ)`,
+/// while the `b` signal is expected to follow `offset_b + amplitude_b * sin(θ - φ)`,
+/// with `φ` being the phase error between the two signals.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SinCosCalibration {
+    /// The offset of the `a` signal, in ADC units.
+    pub offset_a: i32,
+    /// The offset of the `b` signal, in ADC units.
+    pub offset_b: i32,
+    /// The amplitude (i.e. gain) of the `a` signal, in ADC units.
+    pub amplitude_a: i32,
+    /// The amplitude (i.e. gain) of the `b` signal, in ADC units.
+    pub amplitude_b: i32,
+    /// The phase error between the two signals, as `sin(φ)` in Q14 fixed-point
+    /// (i.e. `16384` corresponding to `1.0`).
+    pub phase: i32,
+}
+
+impl Default for SinCosCalibration {
+    fn default() -> Self {
+        Self {
+            offset_a: 0,
+            offset_b: 0,
+            amplitude_a: UNITY,
+            amplitude_b: UNITY,
+            phase: 0,
+        }
+    }
+}
+
+impl SinCosCalibration {
+    /// Returns the given samples with offsets and gains removed, in Q14 fixed-point.
+    fn normalize(&self, a: i32, b: i32) -> (i32, i32) {
+        let normalize = |sample: i32, offset: i32, amplitude: i32| {
+            let value = (i64::from(sample) - i64::from(offset)) * i64::from(UNITY)
+                / i64::from(amplitude.max(1));
+            value.clamp(-2 * i64::from(UNITY), 2 * i64::from(UNITY)) as i32
+        };
+
+        (
+            normalize(a, self.offset_a, self.amplitude_a),
+            normalize(b, self.offset_b, self.amplitude_b),
+        )
+    }
+
+    /// Returns the given normalized samples with the phase error removed,
+    /// scaled by `cos(φ)` (which `atan2()` is invariant to).
+    fn orthogonalize(&self, a: i32, b: i32) -> (i32, i32) {
+        let sin = self.phase.clamp(-UNITY + 1, UNITY - 1);
+        let cos = isqrt((UNITY * UNITY - sin * sin) as u32) as i32;
+
+        (a * cos / UNITY, b + (a * sin / UNITY))
+    }
+}
+
+/// A fixed-point approximation of the sub-count position of a [`SinCosDecoder`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InterpolatedPosition<T> {
+    /// The whole number of counts.
+    pub counts: T,
+    /// The fraction of a count, in units of `1 / 65536` counts.
+    pub fraction: u16,
+}
+
+/// The per-cycle signal statistics used for tracking the calibration.
+#[derive(Clone, Copy, Debug)]
+struct CalibrationTracker {
+    min: (i32, i32),
+    max: (i32, i32),
+    max_sum: i32,
+    max_diff: i32,
+    travel: i32,
+}
+
+impl CalibrationTracker {
+    /// The gain with which per-cycle estimates get blended into the calibration, as a shift.
+    const GAIN_SHIFT: u32 = 2;
+
+    fn new() -> Self {
+        Self {
+            min: (i32::MAX, i32::MAX),
+            max: (i32::MIN, i32::MIN),
+            max_sum: 0,
+            max_diff: 0,
+            travel: 0,
+        }
+    }
+
+    fn observe(&mut self, raw: (i32, i32), normalized: (i32, i32), delta: i16) {
+        self.min = (self.min.0.min(raw.0), self.min.1.min(raw.1));
+        self.max = (self.max.0.max(raw.0), self.max.1.max(raw.1));
+        self.max_sum = self.max_sum.max((normalized.0 + normalized.1).abs());
+        self.max_diff = self.max_diff.max((normalized.0 - normalized.1).abs());
+        self.travel = self.travel.saturating_add(i32::from(delta));
+    }
+
+    /// Returns an updated calibration once a full signal cycle has been observed.
+    fn update(&mut self, calibration: &SinCosCalibration) -> Option<SinCosCalibration> {
+        if self.travel.unsigned_abs() < (1 << ANGLE_BITS) {
+            return None;
+        }
+
+        let sum_squared = i64::from(self.max_sum).pow(2);
+        let diff_squared = i64::from(self.max_diff).pow(2);
+        let phase_error =
+            (diff_squared - sum_squared) * i64::from(UNITY) / (diff_squared + sum_squared).max(1);
+
+        // The phase error gets estimated from normalized samples which had
+        // the current phase correction (as opposed to offsets and gains) not yet applied:
+        let estimate = SinCosCalibration {
+            offset_a: midpoint(self.min.0, self.max.0),
+            offset_b: midpoint(self.min.1, self.max.1),
+            amplitude_a: ((i64::from(self.max.0) - i64::from(self.min.0)) / 2).max(1) as i32,
+            amplitude_b: ((i64::from(self.max.1) - i64::from(self.min.1)) / 2).max(1) as i32,
+            phase: phase_error as i32,
+        };
+
+        let blend = |current: i32, estimate: i32| {
+            let delta = (i64::from(estimate) - i64::from(current)) >> Self::GAIN_SHIFT;
+            (i64::from(current) + delta) as i32
+        };
+
+        *self = Self::new();
+
+        Some(SinCosCalibration {
+            offset_a: blend(calibration.offset_a, estimate.offset_a),
+            offset_b: blend(calibration.offset_b, estimate.offset_b),
+            amplitude_a: blend(calibration.amplitude_a, estimate.amplitude_a),
+            amplitude_b: blend(calibration.amplitude_b, estimate.amplitude_b),
+            phase: blend(calibration.phase, estimate.phase),
+        })
+    }
+}
+
+/// A decoder for analog sine/cosine (e.g. 1 Vpp) encoders,
+/// based on pairs of ADC samples of their `a` (i.e. cosine) and `b` (i.e. sine) signals.
+///
+/// The decoder does three things:
+///
+/// 1. It squares the analog signals into digital `a`/`b` pulse trains
+///    (using Schmitt triggers with configurable hysteresis),
+///    which get decoded by a regular [`IncrementalDecoder`].
+/// 2. It interpolates the sub-count position using a fixed-point `atan2(b, a)`.
+/// 3. It tracks the signals' offset, gain and phase calibration,
+///    refining it with every full signal cycle.
+///
+/// Positive changes (i.e. `a` leading `b`) correspond to an increasing angle.
+#[derive(Debug)]
+pub struct SinCosDecoder<Mode, T = i32> {
+    decoder: IncrementalDecoder<Mode, T>,
+    calibration: SinCosCalibration,
+    tracker: Option<CalibrationTracker>,
+    hysteresis: i32,
+    levels: Option<(bool, bool)>,
+    angle: u16,
+    /// The number of full signal cycles the electrical angle has wrapped around by.
+    periods: i64,
+    /// The phase (in units of `1 / 65536` counts) at which the counter's current count begins,
+    /// or `None` prior to the first samples.
+    anchor: Option<i64>,
+}
+
+impl<Mode, T> Default for SinCosDecoder<Mode, T>
+where
+    Mode: QuadratureStepMode,
+    T: Zero,
+    IncrementalDecoder<Mode, T>: Default,
+{
+    fn default() -> Self {
+        Self {
+            decoder: Default::default(),
+            calibration: Default::default(),
+            tracker: Some(CalibrationTracker::new()),
+            hysteresis: 0,
+            levels: None,
+            angle: 0,
+            periods: 0,
+            anchor: None,
+        }
+    }
+}
+
+impl<Mode, T> SinCosDecoder<Mode, T>
+where
+    Mode: QuadratureStepMode,
+    T: Copy + Zero + One + SaturatingAdd + From<i8>,
+{
+    /// Sets the decoder's initial calibration.
+    ///
+    /// The initial calibration should at least be a rough estimate
+    /// (e.g. the ADC's mid-scale value as offsets), which then gets refined over time.
+    pub fn with_calibration(mut self, calibration: SinCosCalibration) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Enables or disables the tracking of the decoder's calibration (enabled by default).
+    pub fn with_tracking(mut self, tracking: bool) -> Self {
+        self.tracker = tracking.then(CalibrationTracker::new);
+        self
+    }
+
+    /// Sets the hysteresis of the decoder's Schmitt triggers, in ADC units.
+    ///
+    /// A signal only gets squared to high once it exceeds its offset by more than `hysteresis`,
+    /// and back to low once it falls below its offset by more than `hysteresis`.
+    pub fn with_schmitt_hysteresis(mut self, hysteresis: i32) -> Self {
+        self.hysteresis = hysteresis.abs();
+        self
+    }
+
+    /// Returns the hysteresis of the decoder's Schmitt triggers, in ADC units.
+    pub fn schmitt_hysteresis(&self) -> i32 {
+        self.hysteresis
+    }
+
+    /// Returns the decoder's current calibration.
+    pub fn calibration(&self) -> SinCosCalibration {
+        self.calibration
+    }
+
+    /// Sets the decoder's current calibration.
+    pub fn set_calibration(&mut self, calibration: SinCosCalibration) {
+        self.calibration = calibration;
+    }
+
+    /// Returns a borrow of the decoder's underlying quadrature decoder.
+    pub fn decoder(&self) -> &IncrementalDecoder<Mode, T> {
+        &self.decoder
+    }
+
+    /// Updates the decoder's state based on the given `a` and `b` ADC samples,
+    /// returning the direction if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid input (i.e. a positional "jump") was detected.
+    pub fn update(&mut self, a: i32, b: i32) -> Result<Option<Change>, Error> {
+        let calibration = self.calibration;
+        let (normalized_a, normalized_b) = calibration.normalize(a, b);
+        let (x, y) = calibration.orthogonalize(normalized_a, normalized_b);
+
+        let angle = atan2(y, x);
+        let delta = match self.anchor {
+            Some(_) => angle.wrapping_sub(self.angle) as i16,
+            None => 0,
+        };
+        self.update_angle(angle, delta);
+
+        if let Some(tracker) = self.tracker.as_mut() {
+            tracker.observe((a, b), (normalized_a, normalized_b), delta);
+            if let Some(calibration) = tracker.update(&calibration) {
+                self.calibration = calibration;
+            }
+        }
+
+        let (a, b) = self.square(a, b);
+
+        let result = self.decoder.update(a, b);
+
+        // Anchor the interpolation to the quadrant boundary at which the counter changed,
+        // i.e. the most recent one behind the current angle (as the Schmitt triggers lag behind):
+        let quadrant = (1 << (ANGLE_BITS - 2)) * Mode::PULSES_PER_CYCLE as i64;
+        let phase = self.phase();
+        match result {
+            Ok(Some(Change::Positive)) => {
+                self.anchor = Some(phase.div_euclid(quadrant) * quadrant);
+            }
+            Ok(Some(Change::Negative)) => {
+                let boundary = -(-phase).div_euclid(quadrant) * quadrant;
+                self.anchor = Some(boundary - (1 << ANGLE_BITS));
+            }
+            _ => {
+                self.anchor
+                    .get_or_insert(phase.div_euclid(quadrant) * quadrant);
+            }
+        }
+
+        result
+    }
+
+    fn update_angle(&mut self, angle: u16, delta: i16) {
+        if delta > 0 && angle < self.angle {
+            self.periods += 1;
+        } else if delta < 0 && angle > self.angle {
+            self.periods -= 1;
+        }
+
+        self.angle = angle;
+    }
+
+    /// Returns the phase of the electrical angle, in units of `1 / 65536` counts.
+    fn phase(&self) -> i64 {
+        ((self.periods << ANGLE_BITS) + i64::from(self.angle)) * Mode::PULSES_PER_CYCLE as i64
+    }
+
+    fn square(&mut self, a: i32, b: i32) -> (bool, bool) {
+        let calibration = &self.calibration;
+        let hysteresis = i64::from(self.hysteresis);
+
+        let trigger = |sample: i32, offset: i32, level: Option<bool>| {
+            let value = i64::from(sample) - i64::from(offset);
+            match level {
+                _ if value > hysteresis => true,
+                _ if value < -hysteresis => false,
+                Some(level) => level,
+                None => value >= 0,
+            }
+        };
+
+        let levels = self.levels.unzip();
+        let levels = (
+            trigger(a, calibration.offset_a, levels.0),
+            trigger(b, calibration.offset_b, levels.1),
+        );
+
+        self.levels = Some(levels);
+
+        levels
+    }
+
+    /// Resets the decoder to its initial state and its counter back to `0`.
+    ///
+    /// The decoder's calibration remains unaffected.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+        self.levels = None;
+        self.angle = 0;
+        self.periods = 0;
+        self.anchor = None;
+    }
+
+    /// Returns the decoder's counter, as per its underlying quadrature decoder.
+    pub fn counter(&self) -> T {
+        self.decoder.counter()
+    }
+
+    /// Returns the (calibrated) electrical angle of the most recent samples,
+    /// in units of `1 / 65536` signal cycles.
+    pub fn angle(&self) -> u16 {
+        self.angle
+    }
+
+    /// Returns the interpolated position, in (fractional) number of counts.
+    ///
+    /// The whole number of counts always matches the counter,
+    /// with the fraction being measured from the quadrant boundary at which the counter last changed
+    /// (or the quadrant boundary preceding the first samples, if it hasn't changed yet).
+    /// While the Schmitt triggers' hysteresis delays a change of the counter,
+    /// the fraction saturates at the end of the current count.
+    pub fn interpolated(&self) -> InterpolatedPosition<T> {
+        let fraction = match self.anchor {
+            Some(anchor) => (self.phase() - anchor).clamp(0, (1 << ANGLE_BITS) - 1),
+            None => 0,
+        };
+
+        InterpolatedPosition {
+            counts: self.counter(),
+            fraction: fraction as u16,
+        }
+    }
+}
+
+/// Returns the angle of the vector `(x, y)`, in units of `1 / 65536` turns,
+/// computed via the CORDIC algorithm (in vectoring mode).
+pub(crate) fn atan2(y: i32, x: i32) -> u16 {
+    /// The values of `atan(2^-i)`, in units of `1 / 2^32` turns.
+    const ATANS: [u32; 24] = [
+        536870912, 316933406, 167458907, 85004756, 42667331, 21354465, 10679838, 5340245, 2670163,
+        1335087, 667544, 333772, 166886, 83443, 41722, 20861, 10430, 5215, 2608, 1304, 652, 326,
+        163, 81,
+    ];
+
+    // Additional fractional bits, for reducing truncation errors:
+    const SHIFT: u32 = 16;
+
+    let (mut x, mut y) = (i64::from(x) << SHIFT, i64::from(y) << SHIFT);
+
+    // Rotate by 180° into the right half-plane, within which CORDIC converges:
+    let mut angle: u32 = if x < 0 {
+        (x, y) = (-x, -y);
+        1 << 31
+    } else {
+        0
+    };
+
+    for (i, atan) in ATANS.into_iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if y > 0 {
+            (x, y) = (x + dx, y - dy);
+            angle = angle.wrapping_add(atan);
+        } else {
+            (x, y) = (x - dx, y + dy);
+            angle = angle.wrapping_sub(atan);
+        }
+    }
+
+    (angle.wrapping_add(1 << 15) >> 16) as u16
+}
+
+/// Returns the integer square root of `value`.
+fn isqrt(value: u32) -> u32 {
+    let mut root = 0;
+    let mut bit = 1 << 30;
+    let mut value = value;
+
+    while bit > value {
+        bit >>= 2;
+    }
+
+    while bit != 0 {
+        if value >= root + bit {
+            value -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    root
+}
+
+fn midpoint(a: i32, b: i32) -> i32 {
+    ((i64::from(a) + i64::from(b)) / 2) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FullStep, QuadStep};
+
+    use super::*;
+
+    fn sample(angle: f64, calibration: &SinCosCalibration) -> (i32, i32) {
+        let phase = (calibration.phase as f64 / UNITY as f64).asin();
+        let a = calibration.offset_a as f64 + calibration.amplitude_a as f64 * angle.cos();
+        let b =
+            calibration.offset_b as f64 + calibration.amplitude_b as f64 * (angle - phase).sin();
+        (a.round() as i32, b.round() as i32)
+    }
+
+    #[test]
+    fn atan2_accuracy() {
+        for degrees in (0..360).step_by(5) {
+            let radians = (degrees as f64).to_radians();
+            let (x, y) = (
+                (radians.cos() * 10_000.0) as i32,
+                (radians.sin() * 10_000.0) as i32,
+            );
+            let expected = (degrees as f64 / 360.0 * 65536.0) as i32;
+            let actual = atan2(y, x) as i32;
+            let error = (actual - expected + 32768).rem_euclid(65536) - 32768;
+            assert!(error.abs() <= 4, "{degrees}°: {actual} vs. {expected}");
+        }
+    }
+
+    #[test]
+    fn isqrt_accuracy() {
+        for value in [0, 1, 2, 3, 4, 15, 16, 17, 1 << 28, u32::MAX] {
+            let root = isqrt(value) as u64;
+            assert!(root * root <= value as u64);
+            assert!((root + 1) * (root + 1) > value as u64);
+        }
+    }
+
+    #[test]
+    fn interpolation() {
+        let calibration = SinCosCalibration::default();
+        let mut decoder: SinCosDecoder<QuadStep> = SinCosDecoder::default()
+            .with_calibration(calibration)
+            .with_tracking(false);
+
+        // Start at 45°, moving forwards by 2 cycles and 3/16th:
+        let steps = 2 * 64 + 12;
+        for step in 0..=steps {
+            let angle = core::f64::consts::TAU * (step as f64 + 8.0) / 64.0;
+            let (a, b) = sample(angle, &calibration);
+            decoder.update(a, b).unwrap();
+        }
+
+        // Ending at 112.5°, i.e. a quarter count into the second quadrant of the third cycle:
+        assert_eq!(decoder.counter(), 9);
+
+        let position = decoder.interpolated();
+        assert_eq!(position.counts, 9);
+        assert!(position.fraction.abs_diff(16384) < 64);
+    }
+
+    #[test]
+    fn backwards() {
+        let calibration = SinCosCalibration::default();
+        let mut decoder: SinCosDecoder<FullStep> = SinCosDecoder::default()
+            .with_calibration(calibration)
+            .with_tracking(false);
+
+        for step in 0..=(3 * 64) {
+            let angle = core::f64::consts::TAU * (8.0 - step as f64) / 64.0;
+            let (a, b) = sample(angle, &calibration);
+            decoder.update(a, b).unwrap();
+        }
+
+        assert_eq!(decoder.counter(), -3);
+        assert_eq!(decoder.interpolated().counts, -3);
+    }
+
+    #[test]
+    fn interpolation_follows_counter() {
+        let calibration = SinCosCalibration::default();
+        let mut decoder: SinCosDecoder<QuadStep> = SinCosDecoder::default()
+            .with_calibration(calibration)
+            .with_schmitt_hysteresis(2000)
+            .with_tracking(false);
+
+        // Sweep forwards by a cycle and a half, then back again:
+        let steps = (0..96).chain((0..=96).rev());
+        let mut last: Option<(i32, i64)> = None;
+        for step in steps {
+            let angle = core::f64::consts::TAU * (step as f64 + 3.0) / 64.0;
+            let (a, b) = sample(angle, &calibration);
+            decoder.update(a, b).unwrap();
+
+            let position = decoder.interpolated();
+            assert_eq!(position.counts, decoder.counter());
+
+            // The interpolated position never moves against the direction of the sweep:
+            let position = (i64::from(position.counts) << 16) + i64::from(position.fraction);
+            if let Some((last_step, last)) = last {
+                match step > last_step {
+                    true => assert!(position >= last, "{step}: {position} vs. {last}"),
+                    false => assert!(position <= last, "{step}: {position} vs. {last}"),
+                }
+            }
+            last = Some((step, position));
+        }
+    }
+
+    #[test]
+    fn tracking() {
+        let actual = SinCosCalibration {
+            offset_a: 2048,
+            offset_b: 2000,
+            amplitude_a: 1000,
+            amplitude_b: 800,
+            phase: 1000,
+        };
+        let mut decoder: SinCosDecoder<QuadStep> =
+            SinCosDecoder::default().with_calibration(SinCosCalibration {
+                offset_a: 2048,
+                offset_b: 2048,
+                amplitude_a: 1024,
+                amplitude_b: 1024,
+                phase: 0,
+            });
+
+        for step in 0..(64 * 64) {
+            let angle = core::f64::consts::TAU * step as f64 / 64.0;
+            let (a, b) = sample(angle, &actual);
+            decoder.update(a, b).unwrap();
+        }
+
+        let calibration = decoder.calibration();
+        assert!((calibration.offset_a - actual.offset_a).abs() <= 4);
+        assert!((calibration.offset_b - actual.offset_b).abs() <= 4);
+        assert!((calibration.amplitude_a - actual.amplitude_a).abs() <= 4);
+        assert!((calibration.amplitude_b - actual.amplitude_b).abs() <= 4);
+        assert!((calibration.phase - actual.phase).abs() <= 64);
+
+        // Once calibrated, the angle matches the signals' actual angle:
+        let (a, b) = sample(core::f64::consts::TAU / 8.0, &actual);
+        decoder.update(a, b).unwrap();
+        assert!(decoder.angle().abs_diff(8192) < 128);
+    }
+
+    #[test]
+    fn schmitt_hysteresis() {
+        let mut decoder: SinCosDecoder<QuadStep> = SinCosDecoder::default()
+            .with_schmitt_hysteresis(100)
+            .with_tracking(false);
+
+        // Noise around the offset of the `a` signal gets suppressed:
+        for a in [50, -50, 80, -80, 50] {
+            assert_eq!(decoder.update(a, 10_000), Ok(None));
+        }
+
+        assert_eq!(decoder.update(-150, 10_000), Ok(Some(Change::Positive)));
+    }
+}
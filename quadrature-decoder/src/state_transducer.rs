@@ -3,7 +3,11 @@
 
 pub(crate) mod full_step;
 pub(crate) mod half_step;
+pub(crate) mod hall;
 pub(crate) mod quad_step;
+pub(crate) mod step_direction;
+pub(crate) mod tachometer;
+pub(crate) mod up_down;
 
 /// A type defining the FST's inputs.
 #[repr(u8)]
@@ -25,7 +29,6 @@ impl Input {
         }
     }
 
-    #[cfg_attr(not(test), allow(dead_code))]
     pub(crate) const fn a(&self) -> bool {
         match self {
             Input::A0B0 | Input::A0B1 => false,
@@ -33,7 +36,6 @@ impl Input {
         }
     }
 
-    #[cfg_attr(not(test), allow(dead_code))]
     pub(crate) const fn b(&self) -> bool {
         match self {
             Input::A0B0 | Input::A1B0 => false,
@@ -0,0 +1,269 @@
+//! A finite-state-transducer implementing three-phase hall sensor (UVW) decoding.
+//!
+//! The transducer's states correspond to the six valid commutation sectors
+//! (plus an initial state for when the sector is not yet known),
+//! with its inputs being the concatenated 3-bit `uvw` readings:
+//!
+//! ```plain
+//!     ┌──────────────────────────F!──────────────────────────┐
+//!     ▼                                                      │
+//! ┌──────┐      ┌──────┐      ┌──────┐      ┌──────┐      ┌──────┐      ┌──────┐
+//! │  S0  │─F!──▶│  S1  │─F!──▶│  S2  │─F!──▶│  S3  │─F!──▶│  S4  │─F!──▶│  S5  │
+//! │ 100  │◀──R!─│ 110  │◀──R!─│ 010  │◀──R!─│ 011  │◀──R!─│ 001  │◀──R!─│ 101  │
+//! └──────┘      └──────┘      └──────┘      └──────┘      └──────┘      └──────┘
+//!     │                                                      ▲
+//!     └──────────────────────────R!──────────────────────────┘
+//! ```
+//!
+//! Where `F!` and `R!` denote transitions that emit a forwards or backwards output, respectively.
+//! Invalid inputs (i.e. `000` and `111`) as well as jumps between non-adjacent sectors
+//! emit an error output, with the latter re-synchronizing the transducer to the new sector.
+
+use crate::state_transducer::Output;
+
+/// A type defining the hall FST's inputs, i.e. `uvw` bits.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct HallInput {
+    bits: u8,
+}
+
+impl HallInput {
+    pub(crate) const fn new(u: bool, v: bool, w: bool) -> Self {
+        Self {
+            bits: ((u as u8) << 2) | ((v as u8) << 1) | (w as u8),
+        }
+    }
+
+    pub(crate) const fn bits(&self) -> u8 {
+        self.bits
+    }
+}
+
+/// A type defining the hall FST's states.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub(crate) enum HallState {
+    S0, // Sector 0: 100
+    S1, // Sector 1: 110
+    S2, // Sector 2: 010
+    S3, // Sector 3: 011
+    S4, // Sector 4: 001
+    S5, // Sector 5: 101
+    X,  // Unknown sector
+}
+
+impl HallState {
+    /// Returns the state's commutation sector, or `None` if unknown.
+    pub(crate) const fn sector(&self) -> Option<u8> {
+        match self {
+            Self::X => None,
+            state => Some(*state as u8),
+        }
+    }
+
+    /// Returns the state's `uvw` bits, or `None` if unknown.
+    pub(crate) const fn bits(&self) -> Option<u8> {
+        match self {
+            Self::S0 => Some(0b_100),
+            Self::S1 => Some(0b_110),
+            Self::S2 => Some(0b_010),
+            Self::S3 => Some(0b_011),
+            Self::S4 => Some(0b_001),
+            Self::S5 => Some(0b_101),
+            Self::X => None,
+        }
+    }
+}
+
+/// A type defining the hall FST's transitions.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct HallTransition {
+    state: HallState,
+    output: Output,
+}
+
+impl HallTransition {
+    pub(crate) const fn new(state: HallState, output: Output) -> Self {
+        Self { state, output }
+    }
+}
+
+/// The transition table that defines the hall finite-state-transducer.
+///
+/// Rows correspond to a set of transitions per state,
+/// with the integer value of the state indicating the row index.
+/// Columns correspond to individual transitions per state,
+/// with the integer value of the input indicating the column index.
+pub(crate) static TRANSITIONS: [[HallTransition; 8]; 7] = {
+    use self::{HallState::*, Output::*};
+
+    macro_rules! t {
+        ($s:expr, $o:expr) => {
+            HallTransition::new($s, $o)
+        };
+    }
+
+    // columns: `000`, `001`, `010`, `011`, `100`, `101`, `110`, `111`
+    #[rustfmt::skip]
+    let transitions = [
+        [t!(S0, E), t!(S4, E),  t!(S2, E),  t!(S3, E),  t!(S0, N),  t!(S5, BA), t!(S1, AB), t!(S0, E)], // row: `S0`
+        [t!(S1, E), t!(S4, E),  t!(S2, AB), t!(S3, E),  t!(S0, BA), t!(S5, E),  t!(S1, N),  t!(S1, E)], // row: `S1`
+        [t!(S2, E), t!(S4, E),  t!(S2, N),  t!(S3, AB), t!(S0, E),  t!(S5, E),  t!(S1, BA), t!(S2, E)], // row: `S2`
+        [t!(S3, E), t!(S4, AB), t!(S2, BA), t!(S3, N),  t!(S0, E),  t!(S5, E),  t!(S1, E),  t!(S3, E)], // row: `S3`
+        [t!(S4, E), t!(S4, N),  t!(S2, E),  t!(S3, BA), t!(S0, E),  t!(S5, AB), t!(S1, E),  t!(S4, E)], // row: `S4`
+        [t!(S5, E), t!(S4, BA), t!(S2, E),  t!(S3, E),  t!(S0, AB), t!(S5, N),  t!(S1, E),  t!(S5, E)], // row: `S5`
+        [t!(X, E),  t!(S4, N),  t!(S2, N),  t!(S3, N),  t!(S0, N),  t!(S5, N),  t!(S1, N),  t!(X, E)], // row: `X`
+    ];
+
+    transitions
+};
+
+/// A finite-state transducer (FST) for decoding three-phase hall sensor (UVW) readings.
+#[derive(Debug)]
+pub(crate) struct HallTransducer {
+    state: HallState,
+}
+
+impl Default for HallTransducer {
+    fn default() -> Self {
+        Self {
+            state: Self::INITIAL_STATE,
+        }
+    }
+}
+
+impl HallTransducer {
+    const INITIAL_STATE: HallState = HallState::X;
+
+    pub(crate) fn state(&self) -> HallState {
+        self.state
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.state = Self::INITIAL_STATE;
+    }
+
+    pub(crate) fn step(&mut self, input: HallInput) -> Output {
+        let state_index = self.state as usize;
+        let input_index = input.bits() as usize;
+        let transition = TRANSITIONS[state_index][input_index];
+
+        self.state = transition.state;
+
+        transition.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_transducer::{
+            hall::{HallInput, HallState, HallTransducer},
+            Output,
+        },
+        Change::*,
+        HallDecoder, HallError,
+    };
+
+    const SEQUENCE: [(bool, bool, bool); 6] = [
+        (true, false, false),
+        (true, true, false),
+        (false, true, false),
+        (false, true, true),
+        (false, false, true),
+        (true, false, true),
+    ];
+
+    #[test]
+    fn initial_state() {
+        let transducer = HallTransducer::default();
+
+        assert_eq!(transducer.state(), HallState::X);
+    }
+
+    #[test]
+    fn identity() {
+        let mut transducer = HallTransducer::default();
+
+        for (sector, (u, v, w)) in SEQUENCE.into_iter().enumerate() {
+            transducer.step(HallInput::new(u, v, w));
+            assert_eq!(transducer.state().sector(), Some(sector as u8));
+            assert_eq!(transducer.step(HallInput::new(u, v, w)), Output::N);
+            assert_eq!(transducer.state().sector(), Some(sector as u8));
+        }
+    }
+
+    #[test]
+    fn forwards() {
+        let mut decoder: HallDecoder = Default::default();
+
+        assert_eq!(decoder.update(true, false, false), Ok(None));
+        assert_eq!(decoder.sector(), Some(0));
+
+        for (u, v, w) in SEQUENCE.into_iter().cycle().skip(1).take(12) {
+            assert_eq!(decoder.update(u, v, w), Ok(Some(Positive)));
+        }
+
+        assert_eq!(decoder.counter(), 12);
+        assert_eq!(decoder.sector(), Some(0));
+    }
+
+    #[test]
+    fn backwards() {
+        let mut decoder: HallDecoder = Default::default();
+
+        assert_eq!(decoder.update(true, false, false), Ok(None));
+
+        for (u, v, w) in SEQUENCE.into_iter().rev().cycle().take(12) {
+            assert_eq!(decoder.update(u, v, w), Ok(Some(Negative)));
+        }
+
+        assert_eq!(decoder.counter(), -12);
+        assert_eq!(decoder.sector(), Some(0));
+    }
+
+    #[test]
+    fn direction_change() {
+        let mut decoder: HallDecoder = Default::default();
+
+        assert_eq!(decoder.update(true, false, false), Ok(None));
+        assert_eq!(decoder.update(true, true, false), Ok(Some(Positive)));
+        assert_eq!(decoder.update(true, false, false), Ok(Some(Negative)));
+        assert_eq!(decoder.update(true, false, true), Ok(Some(Negative)));
+
+        assert_eq!(decoder.counter(), -1);
+        assert_eq!(decoder.sector(), Some(5));
+    }
+
+    #[test]
+    fn invalid() {
+        let mut decoder: HallDecoder = Default::default();
+
+        assert_eq!(decoder.update(false, false, false), Err(HallError::E000));
+        assert_eq!(decoder.sector(), None);
+
+        assert_eq!(decoder.update(true, false, false), Ok(None));
+        assert_eq!(decoder.update(true, true, true), Err(HallError::E111));
+        assert_eq!(decoder.sector(), Some(0));
+        assert_eq!(decoder.update(true, true, false), Ok(Some(Positive)));
+    }
+
+    #[test]
+    fn jump() {
+        let mut decoder: HallDecoder = Default::default();
+
+        assert_eq!(decoder.update(true, false, false), Ok(None));
+        assert_eq!(
+            decoder.update(false, true, true),
+            Err(HallError::Jump {
+                from: 0b_100,
+                to: 0b_011
+            })
+        );
+
+        // Re-synchronized to the new sector:
+        assert_eq!(decoder.sector(), Some(3));
+        assert_eq!(decoder.update(false, false, true), Ok(Some(Positive)));
+        assert_eq!(decoder.counter(), 1);
+    }
+}
@@ -357,4 +357,41 @@ mod tests {
             }
         }
     }
+
+    mod gated {
+        use super::*;
+
+        #[test]
+        fn forwards() {
+            let mut decoder = Decoder::default();
+
+            assert_eq!(update(&mut decoder, A0B1), Ok(Some(Positive)));
+
+            decoder.set_enabled(false);
+
+            // Tracked, but neither reported, nor counted:
+            assert_eq!(update(&mut decoder, A0B0), Ok(None));
+            assert_eq!(update(&mut decoder, A1B0), Ok(None));
+            assert_eq!(update(&mut decoder, A1B1), Ok(None));
+            assert_eq!(decoder.counter(), 1);
+            assert_eq!(decoder.raw_counter(), 1);
+
+            decoder.set_enabled(true);
+
+            // Resumed without any (false) errors:
+            assert_eq!(update(&mut decoder, A0B1), Ok(Some(Positive)));
+            assert_eq!(update(&mut decoder, A0B0), Ok(Some(Positive)));
+            assert_eq!(decoder.counter(), 3);
+        }
+
+        #[test]
+        fn noise() {
+            let mut decoder = Decoder::default();
+            decoder.set_enabled(false);
+
+            // Invalid inputs are still reported while disabled:
+            assert_eq!(update(&mut decoder, A0B0), Err(Error::E11_00));
+            assert_eq!(decoder.counter(), 0);
+        }
+    }
 }
@@ -0,0 +1,133 @@
+//! A finite-state-transducer implementing step/direction decoding,
+//! with the `a` pulse train carrying the step pulses
+//! and the `b` pulse train carrying the direction.
+//!
+//! The transducer's states mirror the step pulse train's most recent level,
+//! emitting an output for every raising edge on the step pulse train:
+//!
+//! ```plain
+//!    State: │ Input: 00  │ 01   │ 10   │ 11
+//!   ────────┼────────────┼──────┼──────┼──────
+//!    N0  1x │ N2         │ N2   │ N0   │ N0
+//!    N2  0x │ N2         │ N2   │ N0 R!│ N0 F!
+//! ```
+//!
+//! Where `F!` and `R!` denote transitions that emit a forwards or backwards output, respectively.
+
+use crate::state_transducer::{Output, State, Transition, Transitions};
+
+/// The transition table that defines the step/direction finite-state-transducer.
+///
+/// Rows correspond to a set of transitions per state,
+/// with the integer value of the state indicating the row index.
+/// Columns correspond to individual transitions per state,
+/// with the integer value of the input indicating the column index.
+pub(crate) static TRANSITIONS: Transitions<8, 4> = {
+    use self::{Output::*, State::*};
+
+    macro_rules! t {
+        ($s:expr, $o:expr) => {
+            Transition::new($s, $o)
+        };
+    }
+
+    // columns: `A0B0`, `A0B1`, `A1B0`, `A1B1`
+    [
+        [t!(N2, N), t!(N2, N), t!(N0, N), t!(N0, N)], // row: `N0`
+        // These rows are unused in step/direction mode, but need to be provided
+        // as it expects a transition matrix of certain dimensions:
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `F1`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `F2`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `F3`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `R1`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `R2`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `R3`
+        [t!(N2, N), t!(N2, N), t!(N0, BA), t!(N0, AB)], // row: `N2`
+    ]
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_transducer::{
+            step_direction::TRANSITIONS,
+            Input::{self, *},
+            Output, State, StateTransducer,
+        },
+        Change::{self, *},
+        Error, IncrementalDecoder, StepDirection,
+    };
+
+    type Decoder = IncrementalDecoder<StepDirection>;
+
+    fn update(decoder: &mut Decoder, input: Input) -> Result<Option<Change>, Error> {
+        decoder.update(input.a(), input.b())
+    }
+
+    #[test]
+    fn initial_state() {
+        let transducer = StateTransducer::new(&TRANSITIONS);
+
+        assert_eq!(transducer.state(), State::N0);
+    }
+
+    #[test]
+    fn identity() {
+        let mut transducer = StateTransducer::new(&TRANSITIONS);
+
+        let scenarios = [
+            (State::N0, Input::A1B0),
+            (State::N0, Input::A1B1),
+            (State::N2, Input::A0B0),
+            (State::N2, Input::A0B1),
+        ];
+
+        for (state, input) in scenarios {
+            transducer.set_state(state);
+            let output = transducer.step(input);
+            assert_eq!(output, Output::N);
+            assert_eq!(transducer.state(), state);
+        }
+    }
+
+    #[test]
+    fn forwards() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B1), Ok(None));
+        assert_eq!(update(&mut decoder, A1B1), Ok(Some(Positive)));
+        assert_eq!(update(&mut decoder, A1B1), Ok(None)); // Redundant input
+        assert_eq!(update(&mut decoder, A0B1), Ok(None));
+        assert_eq!(update(&mut decoder, A1B1), Ok(Some(Positive)));
+
+        assert_eq!(decoder.counter(), 2);
+    }
+
+    #[test]
+    fn backwards() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Negative)));
+        assert_eq!(update(&mut decoder, A1B0), Ok(None)); // Redundant input
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Negative)));
+
+        assert_eq!(decoder.counter(), -2);
+    }
+
+    #[test]
+    fn direction_change() {
+        let mut decoder = Decoder::default();
+
+        // Direction changes while the step pulse is high must not count:
+        assert_eq!(update(&mut decoder, A1B0), Ok(None));
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        // Simultaneous step and direction changes are valid:
+        assert_eq!(update(&mut decoder, A1B1), Ok(Some(Positive)));
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Negative)));
+
+        assert_eq!(decoder.counter(), 0);
+    }
+}
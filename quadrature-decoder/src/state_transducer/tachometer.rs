@@ -0,0 +1,162 @@
+//! A finite-state-transducer implementing single-channel (aka tachometer) decoding,
+//! with the `a` pulse train carrying the pulses
+//! and the `b` pulse train carrying the (externally provided) direction.
+//!
+//! The transducer's states mirror the pulse train's most recent level
+//! (with `N0` denoting the initial state of an unknown level),
+//! emitting an output for every raising and falling edge on the pulse train:
+//!
+//! ```plain
+//!    State: │ Input: 00  │ 01   │ 10   │ 11
+//!   ────────┼────────────┼──────┼──────┼──────
+//!    N0  ?x │ R1         │ R1   │ F1   │ F1
+//!    F1  1x │ R1 R!      │ R1 F!│ F1   │ F1
+//!    R1  0x │ R1         │ R1   │ F1 R!│ F1 F!
+//! ```
+//!
+//! Where `F!` and `R!` denote transitions that emit a forwards or backwards output, respectively.
+
+use crate::state_transducer::{Output, State, Transition, Transitions};
+
+/// The transition table that defines the tachometer finite-state-transducer.
+///
+/// Rows correspond to a set of transitions per state,
+/// with the integer value of the state indicating the row index.
+/// Columns correspond to individual transitions per state,
+/// with the integer value of the input indicating the column index.
+pub(crate) static TRANSITIONS: Transitions<8, 4> = {
+    use self::{Output::*, State::*};
+
+    macro_rules! t {
+        ($s:expr, $o:expr) => {
+            Transition::new($s, $o)
+        };
+    }
+
+    // columns: `A0B0`, `A0B1`, `A1B0`, `A1B1`
+    [
+        [t!(R1, N), t!(R1, N), t!(F1, N), t!(F1, N)], // row: `N0`
+        [t!(R1, BA), t!(R1, AB), t!(F1, N), t!(F1, N)], // row: `F1`
+        // These rows are unused in tachometer mode, but need to be provided
+        // as it expects a transition matrix of certain dimensions:
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `F2`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `F3`
+        [t!(R1, N), t!(R1, N), t!(F1, BA), t!(F1, AB)], // row: `R1`
+        // These rows are unused in tachometer mode, but need to be provided
+        // as it expects a transition matrix of certain dimensions:
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `R2`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `R3`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `N2`
+    ]
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_transducer::{
+            tachometer::TRANSITIONS,
+            Input::{self, *},
+            Output, State, StateTransducer,
+        },
+        Change::{self, *},
+        Error, IncrementalDecoder, Tachometer,
+    };
+
+    type Decoder = IncrementalDecoder<Tachometer>;
+
+    fn update(decoder: &mut Decoder, input: Input) -> Result<Option<Change>, Error> {
+        decoder.update(input.a(), input.b())
+    }
+
+    #[test]
+    fn initial_state() {
+        let transducer = StateTransducer::new(&TRANSITIONS);
+
+        assert_eq!(transducer.state(), State::N0);
+    }
+
+    #[test]
+    fn identity() {
+        let mut transducer = StateTransducer::new(&TRANSITIONS);
+
+        let scenarios = [
+            (State::F1, Input::A1B0),
+            (State::F1, Input::A1B1),
+            (State::R1, Input::A0B0),
+            (State::R1, Input::A0B1),
+        ];
+
+        for (state, input) in scenarios {
+            transducer.set_state(state);
+            let output = transducer.step(input);
+            assert_eq!(output, Output::N);
+            assert_eq!(transducer.state(), state);
+        }
+    }
+
+    #[test]
+    fn initial_level() {
+        // The initial level must not count, regardless of being high or low:
+        for input in [A0B1, A1B1] {
+            let mut decoder = Decoder::default();
+
+            assert_eq!(update(&mut decoder, input), Ok(None));
+            assert_eq!(decoder.counter(), 0);
+        }
+    }
+
+    #[test]
+    fn forwards() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B1), Ok(None));
+        assert_eq!(update(&mut decoder, A1B1), Ok(Some(Positive)));
+        assert_eq!(update(&mut decoder, A1B1), Ok(None)); // Redundant input
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Positive)));
+        assert_eq!(update(&mut decoder, A1B1), Ok(Some(Positive)));
+
+        assert_eq!(decoder.counter(), 3);
+    }
+
+    #[test]
+    fn backwards() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A1B0), Ok(None));
+        assert_eq!(update(&mut decoder, A0B0), Ok(Some(Negative)));
+        assert_eq!(update(&mut decoder, A0B0), Ok(None)); // Redundant input
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Negative)));
+
+        assert_eq!(decoder.counter(), -2);
+    }
+
+    #[test]
+    fn direction_change() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B1), Ok(None));
+        // Direction changes without a pulse edge must not count:
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A0B1), Ok(None));
+        // Simultaneous pulse and direction changes are valid:
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Negative)));
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Positive)));
+
+        assert_eq!(decoder.counter(), 0);
+    }
+
+    #[test]
+    fn commanded_direction() {
+        let mut decoder = Decoder::default();
+
+        for pulse in [false, true, false, true] {
+            decoder.update_pulse(pulse, Positive).unwrap();
+        }
+        assert_eq!(decoder.counter(), 3);
+
+        for pulse in [false, true] {
+            decoder.update_pulse(pulse, Negative).unwrap();
+        }
+        assert_eq!(decoder.counter(), 1);
+    }
+}
@@ -0,0 +1,149 @@
+//! A finite-state-transducer implementing up/down (aka CW/CCW) decoding,
+//! with the `a` pulse train carrying the up (i.e. clockwise) pulses
+//! and the `b` pulse train carrying the down (i.e. counter-clockwise) pulses.
+//!
+//! The transducer's states mirror its most recent input,
+//! emitting an output for every raising edge on either pulse train:
+//!
+//! ```plain
+//!    State: │ Input: 00  │ 01   │ 10   │ 11
+//!   ────────┼────────────┼──────┼──────┼──────
+//!    N0  11 │ N2         │ F1   │ R1   │ N0
+//!    F1  01 │ N2         │ F1   │ R1 F!│ N0 F!
+//!    R1  10 │ N2         │ F1 R!│ R1   │ N0 R!
+//!    N2  00 │ N2         │ F1 R!│ R1 F!│ N0
+//! ```
+//!
+//! Where `F!` and `R!` denote transitions that emit a forwards or backwards output, respectively.
+
+use crate::state_transducer::{Output, State, Transition, Transitions};
+
+/// The transition table that defines the up/down finite-state-transducer.
+///
+/// Rows correspond to a set of transitions per state,
+/// with the integer value of the state indicating the row index.
+/// Columns correspond to individual transitions per state,
+/// with the integer value of the input indicating the column index.
+pub(crate) static TRANSITIONS: Transitions<8, 4> = {
+    use self::{Output::*, State::*};
+
+    macro_rules! t {
+        ($s:expr, $o:expr) => {
+            Transition::new($s, $o)
+        };
+    }
+
+    // columns: `A0B0`, `A0B1`, `A1B0`, `A1B1`
+    [
+        [t!(N2, N), t!(F1, N), t!(R1, N), t!(N0, N)], // row: `N0`
+        [t!(N2, N), t!(F1, N), t!(R1, AB), t!(N0, AB)], // row: `F1`
+        // These rows are unused in up/down mode, but need to be provided
+        // as it expects a transition matrix of certain dimensions:
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `F2`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `F3`
+        [t!(N2, N), t!(F1, BA), t!(R1, N), t!(N0, BA)], // row: `R1`
+        // These rows are unused in up/down mode, but need to be provided
+        // as it expects a transition matrix of certain dimensions:
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `R2`
+        [t!(N0, E), t!(N0, E), t!(N0, E), t!(N0, E)], // row: `R3`
+        [t!(N2, N), t!(F1, BA), t!(R1, AB), t!(N0, N)], // row: `N2`
+    ]
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_transducer::{
+            up_down::TRANSITIONS,
+            Input::{self, *},
+            Output, State, StateTransducer,
+        },
+        Change::{self, *},
+        Error, IncrementalDecoder, UpDown,
+    };
+
+    type Decoder = IncrementalDecoder<UpDown>;
+
+    fn update(decoder: &mut Decoder, input: Input) -> Result<Option<Change>, Error> {
+        decoder.update(input.a(), input.b())
+    }
+
+    #[test]
+    fn initial_state() {
+        let transducer = StateTransducer::new(&TRANSITIONS);
+
+        assert_eq!(transducer.state(), State::N0);
+    }
+
+    #[test]
+    fn identity() {
+        let mut transducer = StateTransducer::new(&TRANSITIONS);
+
+        let scenarios = [
+            (State::N0, Input::A1B1),
+            (State::F1, Input::A0B1),
+            (State::R1, Input::A1B0),
+            (State::N2, Input::A0B0),
+        ];
+
+        for (state, input) in scenarios {
+            transducer.set_state(state);
+            let output = transducer.step(input);
+            assert_eq!(output, Output::N);
+            assert_eq!(transducer.state(), state);
+        }
+    }
+
+    #[test]
+    fn up() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Positive)));
+        assert_eq!(update(&mut decoder, A1B0), Ok(None)); // Redundant input
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Positive)));
+
+        assert_eq!(decoder.counter(), 2);
+    }
+
+    #[test]
+    fn down() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Negative)));
+        assert_eq!(update(&mut decoder, A0B1), Ok(None)); // Redundant input
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Negative)));
+
+        assert_eq!(decoder.counter(), -2);
+    }
+
+    #[test]
+    fn interleaved() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B1), Ok(None));
+        // Up pulse raising while down pulse falling:
+        assert_eq!(update(&mut decoder, A1B0), Ok(Some(Positive)));
+        // Down pulse raising while up pulse falling:
+        assert_eq!(update(&mut decoder, A0B1), Ok(Some(Negative)));
+        // Up pulse raising while down pulse remaining high:
+        assert_eq!(update(&mut decoder, A1B1), Ok(Some(Positive)));
+
+        assert_eq!(decoder.counter(), 1);
+    }
+
+    #[test]
+    fn noise() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+        // Both pulse trains raising simultaneously is ambiguous:
+        assert_eq!(update(&mut decoder, A1B1), Err(Error::E00_11));
+        assert_eq!(update(&mut decoder, A0B0), Ok(None));
+
+        assert_eq!(decoder.counter(), 0);
+    }
+}
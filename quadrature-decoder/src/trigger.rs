@@ -0,0 +1,246 @@
+//! Position-synchronized output (PSO), i.e. periodic position triggers.
+
+use crate::Change;
+
+/// A periodic position trigger (aka position-synchronized output, or PSO),
+/// firing every time the position crosses the next one of `count` equidistant target positions
+/// (i.e. `start`, `start ± spacing`, `start ± 2 * spacing`, …) in the configured direction,
+/// e.g. for triggering line-scan cameras or laser pulses.
+///
+/// ```plain
+/// Position:  ───────┼─────────┼─────────┼─────────┼────▶
+///                 start   + spacing   + 2 ×     + 3 ×
+/// Trigger:          ▲         ▲         ▲         ▲
+/// ```
+///
+/// Each target position fires at most once: moving back and forth across a target
+/// (e.g. due to jitter, or a reversal) does not re-fire it, with the next target
+/// only firing once the position has moved beyond it in the configured direction.
+/// A target is only considered crossed if the position has been before it,
+/// i.e. a trigger armed beyond its start position waits for the position to return first.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PositionTrigger {
+    start: i32,
+    spacing: u32,
+    count: u32,
+    direction: Change,
+    fired: u32,
+    is_armed: bool,
+}
+
+impl PositionTrigger {
+    /// Creates a trigger for `count` target positions, `spacing` apart, beginning at `start`,
+    /// firing in positive direction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `spacing` is `0`.
+    pub fn new(start: i32, spacing: u32, count: u32) -> Self {
+        assert!(spacing > 0, "Expected non-zero spacing.");
+        Self {
+            start,
+            spacing,
+            count,
+            direction: Change::Positive,
+            fired: 0,
+            is_armed: false,
+        }
+    }
+
+    /// Sets the direction in which target positions get crossed (and in which they are spaced).
+    pub fn with_direction(mut self, direction: Change) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns the position of the first target.
+    pub fn start(&self) -> i32 {
+        self.start
+    }
+
+    /// Returns the spacing between consecutive targets.
+    pub fn spacing(&self) -> u32 {
+        self.spacing
+    }
+
+    /// Returns the number of targets.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns the direction in which target positions get crossed.
+    pub fn direction(&self) -> Change {
+        self.direction
+    }
+
+    /// Returns the number of targets fired so far.
+    pub fn fired(&self) -> u32 {
+        self.fired
+    }
+
+    /// Returns `true` if all targets have been fired, otherwise `false`.
+    pub fn is_done(&self) -> bool {
+        self.fired >= self.count
+    }
+
+    /// Returns the position of the next target, or `None` if all targets have been fired.
+    ///
+    /// Targets beyond the range of `i32` (which are never crossed) saturate at its bounds.
+    pub fn next_target(&self) -> Option<i32> {
+        (!self.is_done()).then(|| {
+            let target = self.target(self.fired);
+            i32::try_from(target).unwrap_or(if target < 0 { i32::MIN } else { i32::MAX })
+        })
+    }
+
+    /// Resets the trigger, re-arming all of its targets.
+    pub fn reset(&mut self) {
+        self.fired = 0;
+        self.is_armed = false;
+    }
+
+    /// Updates the trigger based on the given `position`, returning the index of the fired target
+    /// if a target was crossed, otherwise `None`.
+    ///
+    /// If the position skips across multiple targets within a single update
+    /// (e.g. due to infrequent updates) they only fire once, returning the index of the last one.
+    pub fn update(&mut self, position: i32) -> Option<u32> {
+        if self.is_done() {
+            return None;
+        }
+
+        // The (signed) distance travelled beyond the next target, in the configured direction:
+        let beyond = match self.direction {
+            Change::Positive => i64::from(position) - self.target(self.fired),
+            Change::Negative => self.target(self.fired) - i64::from(position),
+        };
+
+        if beyond < 0 {
+            self.is_armed = true;
+            return None;
+        }
+
+        if !self.is_armed {
+            return None;
+        }
+
+        let crossed = (beyond / i64::from(self.spacing)) as u32;
+        let index = self.fired.saturating_add(crossed).min(self.count - 1);
+        self.fired = index + 1;
+
+        Some(index)
+    }
+
+    /// Returns the position of the target with the given `index`.
+    fn target(&self, index: u32) -> i64 {
+        let offset = i64::from(index) * i64::from(self.spacing);
+        match self.direction {
+            Change::Positive => i64::from(self.start) + offset,
+            Change::Negative => i64::from(self.start) - offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fire(
+        trigger: &mut PositionTrigger,
+        positions: impl IntoIterator<Item = i32>,
+    ) -> Vec<(i32, u32)> {
+        positions
+            .into_iter()
+            .filter_map(|position| trigger.update(position).map(|index| (position, index)))
+            .collect()
+    }
+
+    #[test]
+    fn forwards() {
+        let mut trigger = PositionTrigger::new(10, 5, 3);
+
+        let fired = fire(&mut trigger, 0..40);
+
+        assert_eq!(fired, vec![(10, 0), (15, 1), (20, 2)]);
+        assert!(trigger.is_done());
+        assert_eq!(trigger.next_target(), None);
+    }
+
+    #[test]
+    fn backwards() {
+        let mut trigger = PositionTrigger::new(10, 5, 3).with_direction(Change::Negative);
+
+        // Moving forwards does not fire:
+        assert_eq!(fire(&mut trigger, 0..=20), vec![]);
+
+        let fired = fire(&mut trigger, (-10..20).rev());
+
+        assert_eq!(fired, vec![(10, 0), (5, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn jitter() {
+        let mut trigger = PositionTrigger::new(10, 5, 3);
+
+        let fired = fire(
+            &mut trigger,
+            [8, 9, 10, 9, 10, 11, 10, 9, 10, 14, 15, 14, 15],
+        );
+
+        assert_eq!(fired, vec![(10, 0), (15, 1)]);
+        assert_eq!(trigger.next_target(), Some(20));
+    }
+
+    #[test]
+    fn armed_beyond_start() {
+        let mut trigger = PositionTrigger::new(10, 5, 3);
+
+        // Armed beyond the first target, waiting for the position to return:
+        assert_eq!(fire(&mut trigger, 12..30), vec![]);
+
+        let fired = fire(&mut trigger, 0..30);
+        assert_eq!(fired, vec![(10, 0), (15, 1), (20, 2)]);
+    }
+
+    #[test]
+    fn skipped_targets() {
+        let mut trigger = PositionTrigger::new(10, 5, 4);
+
+        let fired = fire(&mut trigger, [0, 21, 22, 25]);
+
+        assert_eq!(fired, vec![(21, 2), (25, 3)]);
+        assert!(trigger.is_done());
+    }
+
+    #[test]
+    fn reset() {
+        let mut trigger = PositionTrigger::new(0, 1, 2);
+
+        assert_eq!(fire(&mut trigger, -1..5), vec![(0, 0), (1, 1)]);
+
+        trigger.reset();
+        assert_eq!(trigger.fired(), 0);
+        assert_eq!(fire(&mut trigger, (-1..5).rev()), vec![]);
+        assert_eq!(fire(&mut trigger, -1..5), vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn out_of_range_targets() {
+        let mut trigger = PositionTrigger::new(i32::MAX - 5, 10, 3);
+
+        assert_eq!(
+            fire(&mut trigger, [0, i32::MAX - 5]),
+            vec![(i32::MAX - 5, 0)]
+        );
+        assert_eq!(trigger.next_target(), Some(i32::MAX));
+        assert_eq!(fire(&mut trigger, [i32::MAX]), vec![]);
+
+        let mut trigger =
+            PositionTrigger::new(i32::MIN + 5, 10, 3).with_direction(Change::Negative);
+        assert_eq!(
+            fire(&mut trigger, [0, i32::MIN + 5]),
+            vec![(i32::MIN + 5, 0)]
+        );
+        assert_eq!(trigger.next_target(), Some(i32::MIN));
+    }
+}
@@ -1,22 +1,51 @@
-use crate::{state_transducer::Input, Error};
+use crate::{state_transducer::Input, AbsoluteError, Error};
 
-/// A validator for checking conformance of inputs against quadrature protocol.
+/// The protocol that inputs are expected to conform to.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub(crate) enum Protocol {
+    /// Quadrature (i.e. gray-code) protocol.
+    #[default]
+    Quadrature,
+    /// Step/direction protocol.
+    StepDirection,
+    /// Up/down (aka CW/CCW) protocol.
+    UpDown,
+}
+
+/// A validator for checking conformance of inputs against a protocol.
 #[derive(Debug)]
 pub(crate) struct InputValidator {
     input: Input,
+    protocol: Protocol,
 }
 
 impl InputValidator {
-    const INITIAL_INPUT: Input = Input::A1B1;
+    pub(crate) const INITIAL_INPUT: Input = Input::A1B1;
+
+    pub(crate) fn new(protocol: Protocol) -> Self {
+        // We expect transducers to have `State::N0` as initial state,
+        // and the identity input of `State::N0` (i.e. an input that
+        // does not cause a state change) is `Input::A1B1`:
+        Self {
+            input: Self::INITIAL_INPUT,
+            protocol,
+        }
+    }
 
     pub(crate) fn validate(&mut self, input: Input) -> Result<(), Error> {
         let last_input = core::mem::replace(&mut self.input, input);
-        match (last_input, input) {
-            (Input::A0B0, Input::A1B1) => Err(Error::E00_11),
-            (Input::A0B1, Input::A1B0) => Err(Error::E01_10),
-            (Input::A1B0, Input::A0B1) => Err(Error::E10_01),
-            (Input::A1B1, Input::A0B0) => Err(Error::E11_00),
-            _ => Ok(()),
+        match (self.protocol, last_input, input) {
+            // Any input sequence is valid for step/direction,
+            // as step and direction may change simultaneously:
+            (Protocol::StepDirection, _, _) => Ok(()),
+            // Both pulse trains raising simultaneously is ambiguous for up/down:
+            (Protocol::UpDown, Input::A0B0, Input::A1B1) => Err(Error::E00_11),
+            (Protocol::UpDown, _, _) => Ok(()),
+            (Protocol::Quadrature, Input::A0B0, Input::A1B1) => Err(Error::E00_11),
+            (Protocol::Quadrature, Input::A0B1, Input::A1B0) => Err(Error::E01_10),
+            (Protocol::Quadrature, Input::A1B0, Input::A0B1) => Err(Error::E10_01),
+            (Protocol::Quadrature, Input::A1B1, Input::A0B0) => Err(Error::E11_00),
+            (Protocol::Quadrature, _, _) => Ok(()),
         }
     }
 
@@ -28,11 +57,31 @@ impl InputValidator {
 
 impl Default for InputValidator {
     fn default() -> Self {
-        // We expect transducers to have `State::N0` as initial state,
-        // and the identity input of `State::N0` (i.e. an input that
-        // does not cause a state change) is `Input::A1B1`:
-        Self {
-            input: Self::INITIAL_INPUT,
+        Self::new(Protocol::default())
+    }
+}
+
+/// A validator for checking that consecutive N-bit gray-code inputs differ by at most a single bit,
+/// i.e. the N-bit equivalent of `InputValidator` with `Protocol::Quadrature`.
+#[derive(Debug, Default)]
+pub(crate) struct GrayValidator {
+    input: Option<u16>,
+}
+
+impl GrayValidator {
+    pub(crate) fn validate(&mut self, input: u16) -> Result<(), AbsoluteError> {
+        let last_input = self.input.replace(input);
+        match last_input {
+            Some(last_input) if (last_input ^ input).count_ones() > 1 => Err(AbsoluteError::Jump {
+                from: last_input,
+                to: input,
+            }),
+            _ => Ok(()),
         }
     }
+
+    /// Resets the validator to its initial state.
+    pub(crate) fn reset(&mut self) {
+        self.input = None;
+    }
 }
@@ -0,0 +1,342 @@
+//! Vernier (aka nonius) absolute position from two incremental tracks.
+
+use num_traits::{One, SaturatingAdd, Zero};
+
+use crate::{Change, Error, QuadratureStepMode, SinCosDecoder, VernierError};
+
+/// The number of binary angle units per signal period (i.e. a phase of `1.0`).
+const PERIOD: i64 = 1 << 16;
+
+/// A vernier (aka nonius) combinator for two incremental tracks with coprime numbers of signal periods
+/// per revolution (e.g. `32` and `31`), computing the absolute position within the combined period
+/// (i.e. a full revolution) from the phases of both tracks, without the need for a homing move.
+///
+/// ```plain
+/// Track A:  ╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲╱╲   (periods_a)
+/// Track B:  ╱ ╲╱ ╲╱ ╲╱ ╲╱ ╲╱ ╲╱ ╲╱ ╲╱ ╲╱ ╲╱ ╲   (periods_b)
+/// Beat:     ◀──────── combined period ───────▶
+/// ```
+///
+/// Phases are given as binary angles within their track's signal period
+/// (i.e. `0..65536` corresponding to `0.0..1.0`), e.g. as reported by `SinCosDecoder::angle()`
+/// (see [`VernierDecoder`] for decoding a pair of sine/cosine tracks).
+///
+/// Only interpolated (e.g. sine/cosine) tracks are supported: digital quadrature tracks
+/// (as decoded by `IncrementalDecoder`) resolve no more than a quarter period,
+/// which is too coarse for any pair of tracks (see below), and their counters
+/// are relative to their initial positions, rather than absolute phases.
+///
+/// As the phase errors of both tracks get amplified by the number of periods of the respective other track,
+/// the tracks are expected to be accurate to within a fraction of `1 / (periods_a + periods_b)` of a period.
+/// Phases deviating from any consistent position by more than the tolerance
+/// get rejected as inconsistent.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Vernier {
+    periods_a: u16,
+    periods_b: u16,
+    /// The modular inverse of `periods_b` modulo `periods_a`.
+    inverse: u16,
+    tolerance: u16,
+}
+
+impl Vernier {
+    /// The default tolerance (i.e. a quarter of a period), in binary angle units.
+    pub const DEFAULT_TOLERANCE: u16 = 1 << 14;
+
+    /// Creates a vernier combinator for two tracks with `periods_a` and `periods_b`
+    /// signal periods per combined period (i.e. per revolution).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `periods_a` or `periods_b` are `0`, or if they are not coprime.
+    pub fn new(periods_a: u16, periods_b: u16) -> Self {
+        assert!(
+            periods_a > 0 && periods_b > 0,
+            "Expected non-zero number of periods."
+        );
+        let inverse = inverse(periods_b, periods_a).expect("Expected coprime numbers of periods.");
+        Self {
+            periods_a,
+            periods_b,
+            inverse,
+            tolerance: Self::DEFAULT_TOLERANCE,
+        }
+    }
+
+    /// Sets the tolerance (i.e. the maximum deviation of the tracks' beat from a consistent position),
+    /// in binary angle units (i.e. `65536` corresponding to a full period).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tolerance` is not less than half a period (i.e. `32768`).
+    pub fn with_tolerance(mut self, tolerance: u16) -> Self {
+        assert!(
+            i64::from(tolerance) < PERIOD / 2,
+            "Expected tolerance below half a period."
+        );
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Returns the number of signal periods of track A per combined period.
+    pub fn periods_a(&self) -> u16 {
+        self.periods_a
+    }
+
+    /// Returns the number of signal periods of track B per combined period.
+    pub fn periods_b(&self) -> u16 {
+        self.periods_b
+    }
+
+    /// Returns the tolerance, in binary angle units.
+    pub fn tolerance(&self) -> u16 {
+        self.tolerance
+    }
+
+    /// Returns the absolute position within the combined period for the given phases of tracks A and B,
+    /// in units of `1 / 65536` of a track A period (i.e. the index of track A's current period,
+    /// followed by its phase), or `Err(_)` if the phases are inconsistent.
+    pub fn position(&self, phase_a: u16, phase_b: u16) -> Result<u32, VernierError> {
+        let periods_a = i64::from(self.periods_a);
+        let periods_b = i64::from(self.periods_b);
+
+        // The beat, i.e. `periods_b * k_a - periods_a * k_b` for the tracks' period indices `k_a` and `k_b`:
+        let beat = periods_a * i64::from(phase_b) - periods_b * i64::from(phase_a);
+        let cycles = (beat + PERIOD / 2).div_euclid(PERIOD);
+
+        let deviation = (beat - cycles * PERIOD).unsigned_abs();
+        if deviation > u64::from(self.tolerance) {
+            return Err(VernierError::Inconsistent {
+                deviation: deviation as u16,
+            });
+        }
+
+        let period = (cycles * i64::from(self.inverse)).rem_euclid(periods_a);
+
+        Ok((period * PERIOD + i64::from(phase_a)) as u32)
+    }
+}
+
+/// A vernier (aka nonius) decoder for a pair of sine/cosine tracks,
+/// each of which gets decoded by a [`SinCosDecoder`], with their interpolated phases
+/// getting combined into the absolute position within the combined period by a [`Vernier`].
+///
+/// Track A serves as the reference track, i.e. the reported changes
+/// and the absolute position are the ones of track A.
+#[derive(Debug)]
+pub struct VernierDecoder<Mode, T = i32> {
+    vernier: Vernier,
+    track_a: SinCosDecoder<Mode, T>,
+    track_b: SinCosDecoder<Mode, T>,
+}
+
+impl<Mode, T> VernierDecoder<Mode, T>
+where
+    Mode: QuadratureStepMode,
+    T: Copy + Zero + One + SaturatingAdd + From<i8>,
+{
+    /// Creates a vernier decoder for the given combinator and the decoders of tracks A and B.
+    pub fn new(
+        vernier: Vernier,
+        track_a: SinCosDecoder<Mode, T>,
+        track_b: SinCosDecoder<Mode, T>,
+    ) -> Self {
+        Self {
+            vernier,
+            track_a,
+            track_b,
+        }
+    }
+
+    /// Returns the decoder's vernier combinator.
+    pub fn vernier(&self) -> Vernier {
+        self.vernier
+    }
+
+    /// Returns a borrow of track A's decoder.
+    pub fn track_a(&self) -> &SinCosDecoder<Mode, T> {
+        &self.track_a
+    }
+
+    /// Returns a borrow of track B's decoder.
+    pub fn track_b(&self) -> &SinCosDecoder<Mode, T> {
+        &self.track_b
+    }
+
+    /// Updates the decoder's state based on the given `(a, b)` ADC samples of tracks A and B,
+    /// returning track A's direction if a change was detected, `None` if no change was detected,
+    /// or `Err(_)` if an invalid input (i.e. a positional "jump") was detected on either track.
+    ///
+    /// Both tracks get updated on every call, even if track A reports an error.
+    pub fn update(
+        &mut self,
+        track_a: (i32, i32),
+        track_b: (i32, i32),
+    ) -> Result<Option<Change>, Error> {
+        let result_a = self.track_a.update(track_a.0, track_a.1);
+        let result_b = self.track_b.update(track_b.0, track_b.1);
+
+        result_b?;
+        result_a
+    }
+
+    /// Resets both tracks' decoders to their initial state.
+    pub fn reset(&mut self) {
+        self.track_a.reset();
+        self.track_b.reset();
+    }
+
+    /// Returns the absolute position within the combined period for the most recent samples,
+    /// in units of `1 / 65536` of a track A period, or `Err(_)` if the tracks' phases are inconsistent.
+    pub fn position(&self) -> Result<u32, VernierError> {
+        self.vernier
+            .position(self.track_a.angle(), self.track_b.angle())
+    }
+}
+
+/// Returns the modular inverse of `value` modulo `modulus`, or `None` if they are not coprime.
+fn inverse(value: u16, modulus: u16) -> Option<u16> {
+    let (mut r0, mut r1) = (i64::from(modulus), i64::from(value) % i64::from(modulus));
+    let (mut t0, mut t1) = (0_i64, 1_i64);
+
+    while r1 != 0 {
+        let quotient = r0 / r1;
+        (r0, r1) = (r1, r0 - quotient * r1);
+        (t0, t1) = (t1, t0 - quotient * t1);
+    }
+
+    match (r0, modulus) {
+        // Any value is its own inverse modulo `1`:
+        (_, 1) => Some(0),
+        (1, _) => Some(t0.rem_euclid(i64::from(modulus)) as u16),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::QuadStep;
+
+    use super::*;
+
+    /// Returns the phase of a track with `periods` periods per revolution at `position`,
+    /// given in units of `1 / 65536` of a track A period (with `periods_a` periods per revolution).
+    fn phase(position: u64, periods: u16, periods_a: u16) -> u16 {
+        ((position * u64::from(periods) / u64::from(periods_a)) % PERIOD as u64) as u16
+    }
+
+    fn check(periods_a: u16, periods_b: u16) {
+        let vernier = Vernier::new(periods_a, periods_b);
+        let revolution = u64::from(periods_a) * PERIOD as u64;
+
+        for position in (0..revolution).step_by(997) {
+            let phase_a = phase(position, periods_a, periods_a);
+            let phase_b = phase(position, periods_b, periods_a);
+            assert_eq!(
+                vernier.position(phase_a, phase_b),
+                Ok(position as u32),
+                "{position}"
+            );
+        }
+    }
+
+    #[test]
+    fn adjacent() {
+        check(32, 31);
+        check(31, 32);
+    }
+
+    #[test]
+    fn coprime() {
+        check(16, 13);
+        check(1, 7);
+        check(7, 1);
+    }
+
+    #[test]
+    fn noise() {
+        let vernier = Vernier::new(32, 31);
+
+        // 1/4 through period 5, with track B's phase being off by a bit:
+        let position = 5 * PERIOD as u64 + PERIOD as u64 / 4;
+        let phase_a = phase(position, 32, 32);
+        let phase_b = phase(position, 31, 32);
+
+        for error in [-300_i32, 300] {
+            let phase_b = (i32::from(phase_b) + error) as u16;
+            assert_eq!(vernier.position(phase_a, phase_b), Ok(position as u32));
+        }
+    }
+
+    #[test]
+    fn inconsistent() {
+        let vernier = Vernier::new(32, 31).with_tolerance(1 << 12);
+
+        // Half a period off, corresponding to no position at all:
+        let phase_a = 0;
+        let phase_b = (PERIOD / 64) as u16;
+
+        assert_eq!(
+            vernier.position(phase_a, phase_b),
+            Err(VernierError::Inconsistent { deviation: 1 << 15 })
+        );
+    }
+
+    #[test]
+    fn modular_inverse() {
+        assert_eq!(inverse(31, 32), Some(31));
+        assert_eq!(inverse(3, 7), Some(5));
+        assert_eq!(inverse(4, 8), None);
+        assert_eq!(inverse(5, 1), Some(0));
+    }
+
+    /// Returns the samples of a (calibrated) sine/cosine track with `periods` periods
+    /// per revolution, at the given fraction of a revolution.
+    fn samples(revolution: f64, periods: u16) -> (i32, i32) {
+        let angle = revolution * f64::from(periods) * core::f64::consts::TAU;
+        let amplitude = 16384.0;
+        (
+            (amplitude * angle.cos()).round() as i32,
+            (amplitude * angle.sin()).round() as i32,
+        )
+    }
+
+    fn decoder() -> VernierDecoder<QuadStep> {
+        let track = || SinCosDecoder::default().with_tracking(false);
+        VernierDecoder::new(Vernier::new(32, 31), track(), track())
+    }
+
+    #[test]
+    fn sin_cos_tracks() {
+        let mut decoder = decoder();
+        let revolution = 32 * PERIOD;
+
+        // A full revolution, in steps of 1/64 of a track A period:
+        for step in 0..=(32 * 64) {
+            let position = step * PERIOD / 64;
+            let fraction = position as f64 / revolution as f64;
+            assert!(decoder
+                .update(samples(fraction, 32), samples(fraction, 31))
+                .is_ok());
+
+            let actual = i64::from(decoder.position().unwrap());
+            let error =
+                (actual - position + revolution / 2).rem_euclid(revolution) - revolution / 2;
+            assert!(error.abs() <= 8, "{position}: {actual}");
+        }
+    }
+
+    #[test]
+    fn inconsistent_tracks() {
+        let mut decoder = decoder();
+
+        // Track B being off by 1/64 of a period, corresponding to no position at all:
+        let track_b = samples(1.0 / (64.0 * 31.0), 31);
+        assert!(decoder.update(samples(0.0, 32), track_b).is_ok());
+
+        assert!(matches!(
+            decoder.position(),
+            Err(VernierError::Inconsistent { .. })
+        ));
+    }
+}
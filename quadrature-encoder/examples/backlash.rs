@@ -0,0 +1,50 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{LinearEncoder, QuadStep};
+
+fn main() {
+    // Initial quadrature readings, followed by readings moving forwards by 3 changes, then backwards by 3 changes:
+    let pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+    let pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    // A dead band of 2 changes after every change of direction:
+    let mut encoder = LinearEncoder::<_, _, QuadStep>::new(pin_clk, pin_dt).with_backlash(2);
+
+    for _ in 0..7 {
+        match encoder.poll() {
+            Ok(Some(movement)) => println!("Movement detected: {:?}.", movement),
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+
+        println!(
+            "Encoder is at position: {:?} (compensated: {:?}).",
+            encoder.position(),
+            encoder.compensated_position()
+        );
+    }
+
+    let (mut pin_clk, mut pin_dt) = encoder.release();
+    pin_clk.done();
+    pin_dt.done();
+}
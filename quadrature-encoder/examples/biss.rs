@@ -0,0 +1,53 @@
+use embedded_hal_mock::eh1::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+
+use quadrature_encoder::{BissDecoder, BissEncoder, RotaryMovement};
+
+fn main() {
+    // Two 18-bit frames (preceded by idle and acknowledge bits, followed by CRC and timeout bits),
+    // i.e. 28 bits plus up to 8 bits of latency, read as 5 bytes each (reporting positions 1000 and 1001):
+    let spi = SpiMock::new(&[
+        SpiTransaction::transaction_start(),
+        SpiTransaction::read_vec(vec![
+            0b_1100_0100,
+            0b_0000_0001,
+            0b_1111_0100,
+            0b_0110_1101,
+            0b_0111_1111,
+        ]),
+        SpiTransaction::transaction_end(),
+        SpiTransaction::transaction_start(),
+        SpiTransaction::read_vec(vec![
+            0b_1100_0100,
+            0b_0000_0001,
+            0b_1111_0100,
+            0b_1110_1011,
+            0b_0111_1111,
+        ]),
+        SpiTransaction::transaction_end(),
+    ]);
+
+    let decoder = BissDecoder::new(18);
+
+    let mut encoder: BissEncoder<_> = BissEncoder::new(spi, decoder);
+
+    for _ in 0..2 {
+        match encoder.poll() {
+            Ok(Some(movement)) => {
+                let direction = match movement {
+                    RotaryMovement::Clockwise => "clockwise",
+                    RotaryMovement::CounterClockwise => "counter-clockwise",
+                };
+                println!("Movement detected in {:?} direction.", direction)
+            }
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+    }
+
+    println!("Encoder is at angle: {:?}.", encoder.angle());
+    println!("Encoder is at position: {:?}.", encoder.position());
+    println!("Encoder reports warning: {:?}.", encoder.warning());
+
+    let mut spi = encoder.release();
+    spi.done();
+}
@@ -0,0 +1,59 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{DistanceCodedDecoder, DistanceCodedLinearEncoder, QuadStep};
+
+fn main() {
+    // Quadrature readings, moving forwards by 5 changes:
+    let pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+    ]);
+    let pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    // Reference marks, 3 changes apart:
+    let pin_idx = PinMock::new(&[
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+    ]);
+
+    // Fixed reference marks every 8 changes, with coded distances growing by 1 change:
+    let decoder: DistanceCodedDecoder<QuadStep> = DistanceCodedDecoder::new(8, 1);
+
+    let mut encoder: DistanceCodedLinearEncoder<_, _, _, QuadStep> =
+        DistanceCodedLinearEncoder::new(pin_clk, pin_dt, pin_idx, decoder);
+
+    for _ in 0..6 {
+        match encoder.poll() {
+            Ok(Some(movement)) => println!("Movement detected: {:?}.", movement),
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+
+        println!(
+            "Encoder is at position: {:?} (referenced: {:?}).",
+            encoder.position(),
+            encoder.is_referenced()
+        );
+    }
+
+    let (mut pin_clk, mut pin_dt, mut pin_idx) = encoder.release();
+    pin_clk.done();
+    pin_dt.done();
+    pin_idx.done();
+}
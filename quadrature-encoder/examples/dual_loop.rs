@@ -0,0 +1,60 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{DualLoopEncoder, LinearEncoder, LinearMovement, RotaryEncoder};
+
+fn main() {
+    let motor_pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    let motor_pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    let load_pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    let load_pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    let motor = RotaryEncoder::<_, _>::new(motor_pin_clk, motor_pin_dt);
+    let load = LinearEncoder::<_, _>::new(load_pin_clk, load_pin_dt);
+
+    // A motor encoder with 4096 pulses per revolution, driving a lead-screw
+    // with a 5mm pitch and a load encoder with 1000 pulses per millimeter:
+    let mut encoder = DualLoopEncoder::new(motor, load)
+        .with_ratio(4096, 5000)
+        .with_time_constant(16);
+
+    match encoder.poll() {
+        Ok(Some(movement)) => {
+            let direction = match movement {
+                LinearMovement::Forward => "forward",
+                LinearMovement::Backward => "backward",
+            };
+            println!("Movement detected in {:?} direction.", direction)
+        }
+        Ok(_) => println!("No movement detected."),
+        Err(error) => println!("Error detected: {:?}.", error),
+    }
+
+    println!("Load is at position: {:?}.", encoder.load_position());
+    println!("Motor deviates by: {:?}.", encoder.deviation());
+    println!(
+        "Encoder is at (blended) position: {:?}.",
+        encoder.position()
+    );
+
+    let (motor, load) = encoder.release();
+    let (mut motor_pin_clk, mut motor_pin_dt) = motor.release();
+    let (mut load_pin_clk, mut load_pin_dt) = load.release();
+    motor_pin_clk.done();
+    motor_pin_dt.done();
+    load_pin_clk.done();
+    load_pin_dt.done();
+}
@@ -0,0 +1,54 @@
+use embedded_hal_compat::eh1_0::digital::InputPin;
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{LinearEncoder, QuadStep};
+
+fn main() {
+    // Initial quadrature readings, followed by readings moving forwards by 4 changes:
+    let pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    let pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    // Gate readings, only counting during the first and last change:
+    let mut pin_gate = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    let mut encoder = LinearEncoder::<_, _, QuadStep>::new(pin_clk, pin_dt);
+
+    for _ in 0..4 {
+        match pin_gate.is_high() {
+            Ok(is_high) => encoder.set_enabled(is_high),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+
+        match encoder.poll() {
+            Ok(Some(movement)) => println!("Movement detected: {:?}.", movement),
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+    }
+
+    println!("Gated distance: {:?}.", encoder.position());
+
+    let (mut pin_clk, mut pin_dt) = encoder.release();
+    pin_clk.done();
+    pin_dt.done();
+    pin_gate.done();
+}
@@ -0,0 +1,64 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{HallAssistedDecoder, HallAssistedEncoder, QuadStep, Rotary};
+
+fn main() {
+    // Quadrature readings, moving forwards by 2 changes:
+    let pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+    ]);
+    let pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+    ]);
+    // Hall readings, starting in sector 1 (`110`), crossing into sector 2 (`010`):
+    let pin_u = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+    ]);
+    let pin_v = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    let pin_w = PinMock::new(&[
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+    ]);
+
+    // 1000 changes per electrical revolution:
+    let decoder: HallAssistedDecoder<QuadStep> = HallAssistedDecoder::new(1000);
+
+    let mut encoder: HallAssistedEncoder<Rotary, _, _, _, _, _, QuadStep> =
+        HallAssistedEncoder::new(pin_clk, pin_dt, pin_u, pin_v, pin_w, decoder);
+
+    for _ in 0..3 {
+        match encoder.poll() {
+            Ok(Some(movement)) => println!("Movement detected: {:?}.", movement),
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+
+        println!(
+            "Electrical angle: {:?} ({:?}).",
+            encoder.electrical_angle(),
+            encoder.alignment()
+        );
+    }
+
+    println!("Encoder is at position: {:?}.", encoder.position());
+
+    let (mut pin_clk, mut pin_dt, mut pin_u, mut pin_v, mut pin_w) = encoder.release();
+    pin_clk.done();
+    pin_dt.done();
+    pin_u.done();
+    pin_v.done();
+    pin_w.done();
+}
@@ -0,0 +1,56 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{LinearEncoder, LinearityTable, QuadStep};
+
+/// A calibration table with 3 equidistant breakpoints (at `-2`, `0` and `2`),
+/// as stored in non-volatile memory.
+const CALIBRATION: [u8; 23] = [
+    0, // Format tag (equidistant)
+    3, 0, // Number of entries
+    0xFE, 0xFF, 0xFF, 0xFF, // First breakpoint
+    2, 0, 0, 0, // Spacing
+    0, 0, 0, 0, // Correction at `-2`
+    0, 0, 0, 0, // Correction at `0`
+    4, 0, 0, 0, // Correction at `2`
+];
+
+fn main() {
+    // Initial quadrature readings, followed by readings moving forwards by 3 changes:
+    let pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+    let pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+    ]);
+
+    let table: LinearityTable<3> =
+        LinearityTable::from_bytes(&CALIBRATION).expect("Expected valid calibration table.");
+
+    let mut encoder = LinearEncoder::<_, _, QuadStep>::new(pin_clk, pin_dt);
+
+    for _ in 0..3 {
+        match encoder.poll() {
+            Ok(Some(movement)) => println!("Movement detected: {:?}.", movement),
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+
+        println!(
+            "Encoder is at position: {:?} (corrected: {:?}).",
+            encoder.position(),
+            encoder.corrected_position(&table)
+        );
+    }
+
+    let (mut pin_clk, mut pin_dt) = encoder.release();
+    pin_clk.done();
+    pin_dt.done();
+}
@@ -0,0 +1,59 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{LinearEncoder, Odometer, OdometerStats, QuadStep};
+
+fn main() {
+    // Initial quadrature readings, followed by readings moving forwards by 2 changes,
+    // then backwards by 1 change:
+    let pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+    ]);
+    let pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    // Statistics, as restored from non-volatile memory:
+    let stored = OdometerStats {
+        travel: 1000,
+        ..Default::default()
+    }
+    .to_bytes();
+    let stats = OdometerStats::from_bytes(&stored);
+
+    // Intervals beyond 10ms are considered standstill:
+    let odometer = Odometer::new(10_000).with_stats(stats);
+
+    let mut encoder = LinearEncoder::<_, _, QuadStep>::new(pin_clk, pin_dt).with_odometer(odometer);
+
+    // Polled every 100µs:
+    for timestamp in [0, 100, 200] {
+        match encoder.poll_at(timestamp) {
+            Ok(Some(movement)) => println!("Movement detected: {:?}.", movement),
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+    }
+
+    if let Some(odometer) = encoder.odometer() {
+        let stats = odometer.stats();
+        println!("Total travel: {:?}.", stats.travel);
+        println!("Reversals: {:?}.", stats.reversals);
+        println!("Maximum speed: {:?}/s.", stats.max_speed(1_000_000));
+        println!("Time in motion: {:?}µs.", stats.motion);
+
+        // Persisted in non-volatile memory:
+        let _bytes: [u8; OdometerStats::BYTES] = stats.to_bytes();
+    }
+
+    let (mut pin_clk, mut pin_dt) = encoder.release();
+    pin_clk.done();
+    pin_dt.done();
+}
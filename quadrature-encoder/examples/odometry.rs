@@ -0,0 +1,69 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{DifferentialOdometry, QuadStep, RotaryEncoder};
+
+fn main() {
+    // Initial quadrature readings, followed by readings with both wheels moving forwards by 2 changes,
+    // then only the right wheel moving forwards by another change:
+    let left_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+    ]);
+    let left_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+    ]);
+    let right_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+    let right_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+    ]);
+
+    let mut left = RotaryEncoder::<_, _, QuadStep>::new(left_clk, left_dt);
+    let mut right = RotaryEncoder::<_, _, QuadStep>::new(right_clk, right_dt);
+
+    // Wheels of 50mm radius, 300mm apart, with 1000 counts per revolution (in micrometers):
+    let mut odometry = DifferentialOdometry::new(50_000, 300_000, 1000);
+
+    // Establishing the wheels' initial positions:
+    odometry.update(left.position(), right.position());
+
+    for _ in 0..3 {
+        for (name, encoder) in [("left", &mut left), ("right", &mut right)] {
+            match encoder.poll() {
+                Ok(Some(movement)) => {
+                    println!("Movement of {name} wheel detected: {:?}.", movement)
+                }
+                Ok(_) => println!("No movement of {name} wheel detected."),
+                Err(error) => println!("Error detected: {:?}.", error),
+            }
+        }
+
+        let pose = odometry.update(left.position(), right.position());
+        println!("Robot is at pose: {:?}.", pose);
+    }
+
+    // Re-localized from an external reference:
+    odometry.reset();
+
+    let (mut left_clk, mut left_dt) = left.release();
+    left_clk.done();
+    left_dt.done();
+
+    let (mut right_clk, mut right_dt) = right.release();
+    right_clk.done();
+    right_dt.done();
+}
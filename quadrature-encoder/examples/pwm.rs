@@ -0,0 +1,42 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{PwmEncoder, PwmFormat, RotaryMovement};
+
+fn main() {
+    let pin_pwm = PinMock::new(&[
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    let mut encoder: PwmEncoder<_> = PwmEncoder::new(pin_pwm, PwmFormat::AS5600);
+
+    // Timestamps (e.g. in microseconds) of a sensor with a frame period of 4351µs,
+    // reporting an angle of 1000, followed by an angle of 1010:
+    let timestamps = [0, 1128, 4351, 5489, 8702];
+
+    for timestamp in timestamps {
+        match encoder.poll(timestamp) {
+            Ok(Some(movement)) => {
+                let direction = match movement {
+                    RotaryMovement::Clockwise => "clockwise",
+                    RotaryMovement::CounterClockwise => "counter-clockwise",
+                };
+                println!("Movement detected in {:?} direction.", direction)
+            }
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+    }
+
+    println!("Encoder is at angle: {:?}.", encoder.angle());
+    println!("Encoder is at position: {:?}.", encoder.position());
+
+    let mut pin_pwm = encoder.release();
+    pin_pwm.done();
+}
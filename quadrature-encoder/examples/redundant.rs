@@ -0,0 +1,56 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{QuadStep, RedundantEncoder, RotaryEncoder, RotaryMovement};
+
+fn main() {
+    let primary_pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+    ]);
+    let primary_pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    let secondary_pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    let secondary_pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    // A quad-step primary encoder, paired with a full-step secondary encoder
+    // of the same resolution, resulting in a ratio of 4:1:
+    let primary = RotaryEncoder::<_, _, QuadStep>::new(primary_pin_clk, primary_pin_dt);
+    let secondary = RotaryEncoder::<_, _>::new(secondary_pin_clk, secondary_pin_dt);
+
+    let mut encoder = RedundantEncoder::new(primary, secondary)
+        .with_ratio(4, 1)
+        .with_tolerance(4);
+
+    match encoder.poll() {
+        Ok(Some(movement)) => {
+            let direction = match movement {
+                RotaryMovement::Clockwise => "clockwise",
+                RotaryMovement::CounterClockwise => "counter-clockwise",
+            };
+            println!("Movement detected in {:?} direction.", direction)
+        }
+        Ok(_) => println!("No movement detected."),
+        Err(error) => println!("Error detected: {:?}.", error),
+    }
+
+    println!("Encoder is at position: {:?}.", encoder.position());
+    println!("Encoders deviate by: {:?}.", encoder.deviation());
+
+    let (primary, secondary) = encoder.release();
+    let (mut primary_pin_clk, mut primary_pin_dt) = primary.release();
+    let (mut secondary_pin_clk, mut secondary_pin_dt) = secondary.release();
+    primary_pin_clk.done();
+    primary_pin_dt.done();
+    secondary_pin_clk.done();
+    secondary_pin_dt.done();
+}
@@ -0,0 +1,42 @@
+use embedded_hal_mock::eh1::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+
+use quadrature_encoder::{Coding, Parity, RotaryMovement, SsiDecoder, SsiEncoder, SsiSpi};
+
+fn main() {
+    // Two 13-bit gray-code frames with an even parity bit and a trailing monoflop bit,
+    // i.e. 15 bits, read as 2 bytes each (reporting positions 0 and 1):
+    let spi = SpiMock::new(&[
+        SpiTransaction::transaction_start(),
+        SpiTransaction::read_vec(vec![0b_0000_0000, 0b_0000_0000]),
+        SpiTransaction::transaction_end(),
+        SpiTransaction::transaction_start(),
+        SpiTransaction::read_vec(vec![0b_0000_0000, 0b_0000_1100]),
+        SpiTransaction::transaction_end(),
+    ]);
+
+    let decoder = SsiDecoder::new(13)
+        .with_coding(Coding::Gray)
+        .with_parity(Parity::Even);
+
+    let mut encoder: SsiEncoder<_> = SsiEncoder::new(SsiSpi::new(spi), decoder);
+
+    for _ in 0..2 {
+        match encoder.poll() {
+            Ok(Some(movement)) => {
+                let direction = match movement {
+                    RotaryMovement::Clockwise => "clockwise",
+                    RotaryMovement::CounterClockwise => "counter-clockwise",
+                };
+                println!("Movement detected in {:?} direction.", direction)
+            }
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+    }
+
+    println!("Encoder is at angle: {:?}.", encoder.angle());
+    println!("Encoder is at position: {:?}.", encoder.position());
+
+    let mut spi = encoder.release().release();
+    spi.done();
+}
@@ -0,0 +1,41 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{RotaryMovement, TachometerRotaryEncoder};
+
+fn main() {
+    let pin = PinMock::new(&[
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    let mut encoder: TachometerRotaryEncoder<_> =
+        TachometerRotaryEncoder::new(pin).with_direction(RotaryMovement::Clockwise);
+
+    for index in 0..4 {
+        // The direction is commanded externally (e.g. by a motor driver):
+        if index == 3 {
+            encoder.set_direction(RotaryMovement::CounterClockwise);
+        }
+
+        match encoder.poll() {
+            Ok(Some(movement)) => {
+                let direction = match movement {
+                    RotaryMovement::Clockwise => "clockwise",
+                    RotaryMovement::CounterClockwise => "counter-clockwise",
+                };
+                println!("Movement detected in {:?} direction.", direction)
+            }
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+    }
+
+    println!("Encoder is at position: {:?}.", encoder.position());
+
+    let mut pin = encoder.release();
+    pin.done();
+}
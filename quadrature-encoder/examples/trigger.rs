@@ -0,0 +1,69 @@
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTransaction,
+};
+
+use quadrature_encoder::{Change, LinearEncoder, PositionTrigger, QuadStep, TriggerOutput};
+
+fn main() {
+    // Initial quadrature readings, followed by readings moving forwards by 3 changes,
+    // then backwards by 1 change (i.e. jitter) and forwards by 2 changes again:
+    let pin_clk = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+    ]);
+    let pin_dt = PinMock::new(&[
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::High),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::Low),
+        PinTransaction::get(PinState::High),
+    ]);
+
+    // A pulse when crossing position 1, then another one when crossing position 3
+    // (only fired once, despite the jitter):
+    let pin_out = PinMock::new(&[
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::Low),
+        PinTransaction::set(PinState::High),
+        PinTransaction::set(PinState::Low),
+    ]);
+
+    let mut encoder = LinearEncoder::<_, _, QuadStep>::new(pin_clk, pin_dt);
+
+    // Firing at positions 1, 3 and 5 when moving forwards:
+    let trigger = PositionTrigger::new(1, 2, 3).with_direction(Change::Positive);
+    let mut output = TriggerOutput::new(trigger, pin_out);
+
+    // Arming the trigger at the initial position:
+    output.update(encoder.position()).unwrap();
+
+    for _ in 0..6 {
+        match encoder.poll() {
+            Ok(Some(movement)) => println!("Movement detected: {:?}.", movement),
+            Ok(_) => println!("No movement detected."),
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+
+        match output.update(encoder.position()) {
+            Ok(Some(index)) => println!("Trigger {:?} fired at {:?}.", index, encoder.position()),
+            Ok(_) => {}
+            Err(error) => println!("Error detected: {:?}.", error),
+        }
+    }
+
+    println!("Next target: {:?}.", output.trigger().next_target());
+
+    let (mut pin_clk, mut pin_dt) = encoder.release();
+    pin_clk.done();
+    pin_dt.done();
+
+    let (_trigger, mut pin_out) = output.release();
+    pin_out.done();
+}
@@ -1,9 +1,31 @@
 //! Quadrature-based encoder drivers.
 
+mod backlash;
+mod biss;
+mod distance_coded;
+mod dual_loop;
+mod hall_assisted;
 mod incremental;
 mod indexed;
+mod pwm;
+mod ratio;
+mod redundant;
+mod ssi;
+mod tachometer;
+mod trigger;
 
 pub use self::{
+    biss::BissEncoder,
+    distance_coded::{
+        DistanceCodedEncoder, DistanceCodedLinearEncoder, DistanceCodedRotaryEncoder,
+    },
+    dual_loop::DualLoopEncoder,
+    hall_assisted::HallAssistedEncoder,
     incremental::{IncrementalEncoder, LinearEncoder, RotaryEncoder},
     indexed::{IndexedIncrementalEncoder, IndexedLinearEncoder, IndexedRotaryEncoder},
+    pwm::PwmEncoder,
+    redundant::RedundantEncoder,
+    ssi::{SsiBus, SsiEncoder, SsiPins, SsiSpi},
+    tachometer::{TachometerEncoder, TachometerLinearEncoder, TachometerRotaryEncoder},
+    trigger::TriggerOutput,
 };
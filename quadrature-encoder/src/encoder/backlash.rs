@@ -0,0 +1,177 @@
+//! A backlash (aka lost motion) compensator.
+
+use num_traits::{One, SaturatingAdd, Zero};
+
+/// A backlash compensator, applying a dead band of `width` changes after every change of direction
+/// before the compensated position starts following the (raw) movements again.
+///
+/// ```plain
+/// Raw:          0 → 1 → 2 → 3 → 2 → 1 → 0 → 1
+/// Compensated:  0 → 1 → 2 → 3 → 3 → 3 → 2 → 2    (width: 2)
+/// ```
+///
+/// The first movement after creation (or a reset) is assumed to be engaged already.
+#[derive(Debug)]
+pub(crate) struct Backlash<T> {
+    width: u32,
+    /// The play within the dead band (in the range of `0..=width`),
+    /// or `None` if no movement has been observed yet.
+    play: Option<u32>,
+    position: T,
+}
+
+impl<T> Default for Backlash<T>
+where
+    T: Zero,
+{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<T> Backlash<T>
+where
+    T: Zero,
+{
+    pub(crate) fn new(width: u32) -> Self {
+        Self {
+            width,
+            play: None,
+            position: Zero::zero(),
+        }
+    }
+}
+
+impl<T> Backlash<T>
+where
+    T: Copy + Zero + One + SaturatingAdd + From<i8>,
+{
+    /// Returns the width of the dead band, in number of changes.
+    pub(crate) fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Updates the compensated position based on a (raw) movement in positive or negative direction.
+    pub(crate) fn update(&mut self, is_positive: bool) {
+        let width = self.width;
+        let play = self.play.unwrap_or(if is_positive { width } else { 0 });
+
+        let (play, is_engaged) = match is_positive {
+            true if play < width => (play + 1, false),
+            false if play > 0 => (play - 1, false),
+            _ => (play, true),
+        };
+
+        self.play = Some(play);
+
+        if is_engaged {
+            let delta: T = if is_positive { T::one() } else { T::from(-1) };
+            self.position = self.position.saturating_add(&delta);
+        }
+    }
+
+    /// Resets the compensator to its initial state and its position back to `0`.
+    pub(crate) fn reset(&mut self) {
+        self.play = None;
+        self.position = Zero::zero();
+    }
+
+    /// Returns the compensated position.
+    pub(crate) fn position(&self) -> T {
+        self.position
+    }
+
+    /// Sets the compensated position, assuming the next movement to be engaged already.
+    pub(crate) fn set_position(&mut self, position: T) {
+        self.play = None;
+        self.position = position;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    use crate::{QuadStep, RotaryEncoder};
+
+    use super::*;
+
+    /// Feeds the given (raw) movements, returning the compensated positions.
+    fn feed(backlash: &mut Backlash<i32>, movements: &[bool]) -> Vec<i32> {
+        movements
+            .iter()
+            .map(|&is_positive| {
+                backlash.update(is_positive);
+                backlash.position()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dead_band() {
+        let mut backlash = Backlash::new(2);
+
+        // Raw:  1, 2, 3, 2, 1, 0, 1
+        let positions = feed(
+            &mut backlash,
+            &[true, true, true, false, false, false, true],
+        );
+
+        assert_eq!(positions, [1, 2, 3, 3, 3, 2, 2]);
+    }
+
+    #[test]
+    fn zero_width() {
+        let mut backlash = Backlash::default();
+
+        let positions = feed(&mut backlash, &[true, false, false, true, false]);
+
+        assert_eq!(backlash.width(), 0);
+        assert_eq!(positions, [1, 0, -1, 0, -1]);
+    }
+
+    #[test]
+    fn clearing_play() {
+        let mut backlash = Backlash::new(2);
+
+        assert_eq!(feed(&mut backlash, &[true, true, false]), [1, 2, 2]);
+        backlash.set_position(10);
+        assert_eq!(feed(&mut backlash, &[false]), [9]);
+
+        assert_eq!(feed(&mut backlash, &[true]), [9]);
+        backlash.reset();
+        assert_eq!(backlash.position(), 0);
+        assert_eq!(feed(&mut backlash, &[false]), [-1]);
+    }
+
+    #[test]
+    fn reversed_encoder() {
+        use PinState::{High, Low};
+
+        let pin = |states: &[PinState]| {
+            let transactions: Vec<_> = states.iter().copied().map(PinTransaction::get).collect();
+            PinMock::new(&transactions)
+        };
+
+        // Two (raw) positive changes, followed by a negative one:
+        let mut encoder = RotaryEncoder::<_, _, QuadStep>::new(
+            pin(&[High, Low, Low, Low]),
+            pin(&[High, High, Low, High]),
+        )
+        .reversed()
+        .with_backlash(1);
+
+        for _ in 0..3 {
+            assert!(encoder.poll().is_ok());
+        }
+
+        assert_eq!(encoder.position(), -1);
+        assert_eq!(encoder.compensated_position(), -2);
+
+        let (mut clk, mut dt) = encoder.release();
+        clk.done();
+        dt.done();
+    }
+}
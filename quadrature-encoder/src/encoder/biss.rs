@@ -0,0 +1,225 @@
+//! A BiSS-C absolute encoder driver.
+
+use num_traits::{SaturatingAdd, WrappingNeg, Zero};
+use quadrature_decoder::BissDecoder;
+
+#[allow(unused_imports)]
+use crate::{
+    mode::{Movement, RotaryMovement},
+    traits::*,
+    Error,
+};
+
+/// The maximum number of bits read per frame.
+const MAX_BITS: u8 = 128;
+
+/// A BiSS-C (point-to-point) absolute rotary encoder, driven by an SPI peripheral acting as master.
+///
+/// The SPI device is expected to be configured with a clock idling high (i.e. `CPOL = 1`),
+/// with data getting sampled on the rising edge (i.e. `CPHA = 1`).
+/// As the slave's acknowledge is of variable length, each frame gets read with
+/// a number of surplus bits (i.e. the maximum latency), rounded up to whole bytes.
+///
+/// The encoder's absolute angle gets unwrapped into a multi-turn position,
+/// which is reported just like the position of a `RotaryEncoder`.
+#[derive(Debug)]
+pub struct BissEncoder<Spi, T = i32> {
+    decoder: BissDecoder<T>,
+    spi: Spi,
+    max_latency: u8,
+    is_reversed: bool,
+}
+
+impl<Spi, T> BissEncoder<Spi, T>
+where
+    Spi: SpiDevice,
+    T: Copy + Zero + SaturatingAdd + WrappingNeg + From<i32>,
+{
+    /// The default maximum latency (i.e. idle and acknowledge bits preceding the start bit), in bits.
+    pub const DEFAULT_MAX_LATENCY: u8 = 8;
+
+    /// Creates a BiSS-C encoder driver for the given SPI device and frame decoder.
+    pub fn new(spi: Spi, decoder: BissDecoder<T>) -> Self {
+        Self {
+            decoder,
+            spi,
+            max_latency: Self::DEFAULT_MAX_LATENCY,
+            is_reversed: false,
+        }
+    }
+
+    /// Sets the maximum latency (i.e. idle and acknowledge bits preceding the start bit), in bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting frame exceeds 128 bits.
+    pub fn with_max_latency(mut self, max_latency: u8) -> Self {
+        assert!(
+            u16::from(self.decoder.frame_bits()) + u16::from(max_latency) <= u16::from(MAX_BITS),
+            "Expected frame of at most 128 bits."
+        );
+        self.max_latency = max_latency;
+        self
+    }
+
+    /// Returns the maximum latency (i.e. idle and acknowledge bits preceding the start bit), in bits.
+    pub fn max_latency(&self) -> u8 {
+        self.max_latency
+    }
+
+    /// Sets the encoder's reversed mode, making it report flipped movements and positions.
+    pub fn reversed(mut self) -> Self {
+        self.is_reversed = true;
+        self
+    }
+
+    /// Returns `true` if the encoder is reversed, otherwise `false`.
+    pub fn is_reversed(&self) -> bool {
+        self.is_reversed
+    }
+
+    /// Returns a borrow of the encoder's frame decoder.
+    pub fn decoder(&self) -> &BissDecoder<T> {
+        &self.decoder
+    }
+
+    /// Returns a mutable borrow for the SPI device.
+    pub fn spi_mut(&mut self) -> &mut Spi {
+        &mut self.spi
+    }
+
+    /// Consumes self, returning the SPI device.
+    pub fn release(self) -> Spi {
+        self.spi
+    }
+
+    /// Reads a frame from the encoder, returning the direction if a movement was detected,
+    /// `None` if no movement was detected, or `Err(_)` if an invalid frame was detected.
+    pub fn poll(&mut self) -> Result<Option<RotaryMovement>, Error> {
+        let bits = self.decoder.frame_bits() + self.max_latency;
+        let bytes = usize::from(bits.div_ceil(8));
+        let mut buffer = [0_u8; MAX_BITS as usize / 8];
+
+        self.spi
+            .read(&mut buffer[..bytes])
+            .map_err(|_| Error::Bus)?;
+
+        let movement: Option<RotaryMovement> = self
+            .decoder
+            .update(&buffer[..bytes])
+            .map_err(Error::Biss)?
+            .map(From::from);
+
+        Ok(movement.map(|movement| {
+            if self.is_reversed() {
+                movement.flipped()
+            } else {
+                movement
+            }
+        }))
+    }
+
+    /// Resets the encoder to its initial state.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
+    /// Returns `true` if the most recent valid frame reported a warning, otherwise `false`.
+    pub fn warning(&self) -> bool {
+        self.decoder.frame().is_some_and(|frame| frame.warning)
+    }
+
+    /// Returns the encoder's most recent absolute angle (in the range of `0..resolution`),
+    /// or `None` if no valid frame has been read yet.
+    pub fn angle(&self) -> Option<u32> {
+        let resolution = self.decoder.resolution();
+        self.decoder.angle().map(|angle| match self.is_reversed {
+            true => (resolution - angle) % resolution,
+            false => angle,
+        })
+    }
+
+    /// Returns the encoder's (multi-turn) position in number of positions.
+    pub fn position(&self) -> T {
+        match self.is_reversed {
+            true => self.decoder.position().wrapping_neg(),
+            false => self.decoder.position(),
+        }
+    }
+
+    /// Sets the encoder's position.
+    pub fn set_position(&mut self, position: T) {
+        match self.is_reversed {
+            true => self.decoder.set_position(position.wrapping_neg()),
+            false => self.decoder.set_position(position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+    use quadrature_decoder::BissError;
+
+    use super::*;
+
+    /// Returns the transactions of reading 18-bit frames reporting positions 1000 and 1001
+    /// (preceded by idle and acknowledge bits, followed by CRC and timeout bits).
+    fn frames(crc: u8) -> Vec<SpiTransaction<u8>> {
+        [0b_0110_1101, crc]
+            .into_iter()
+            .flat_map(|last| {
+                [
+                    SpiTransaction::transaction_start(),
+                    SpiTransaction::read_vec(vec![
+                        0b_1100_0100,
+                        0b_0000_0001,
+                        0b_1111_0100,
+                        last,
+                        0b_0111_1111,
+                    ]),
+                    SpiTransaction::transaction_end(),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn full_frame() {
+        let spi = SpiMock::new(&frames(0b_1110_1011));
+        let mut encoder: BissEncoder<_> = BissEncoder::new(spi, BissDecoder::new(18));
+
+        assert_eq!(encoder.poll(), Ok(None));
+        assert_eq!(encoder.poll(), Ok(Some(RotaryMovement::Clockwise)));
+        assert_eq!(encoder.angle(), Some(1001));
+        assert_eq!(encoder.position(), 1001);
+        assert!(!encoder.warning());
+
+        encoder.release().done();
+    }
+
+    #[test]
+    fn reversed() {
+        let spi = SpiMock::new(&frames(0b_1110_1011));
+        let mut encoder: BissEncoder<_> = BissEncoder::new(spi, BissDecoder::new(18)).reversed();
+
+        assert_eq!(encoder.poll(), Ok(None));
+        assert_eq!(encoder.poll(), Ok(Some(RotaryMovement::CounterClockwise)));
+        assert_eq!(encoder.angle(), Some((1 << 18) - 1001));
+        assert_eq!(encoder.position(), -1001);
+
+        encoder.release().done();
+    }
+
+    #[test]
+    fn crc_mismatch() {
+        let spi = SpiMock::new(&frames(0b_1110_1001));
+        let mut encoder: BissEncoder<_> = BissEncoder::new(spi, BissDecoder::new(18));
+
+        assert_eq!(encoder.poll(), Ok(None));
+        assert_eq!(encoder.poll(), Err(Error::Biss(BissError::Crc)));
+        assert_eq!(encoder.position(), 1000);
+
+        encoder.release().done();
+    }
+}
@@ -0,0 +1,241 @@
+//! A distance-coded incremental encoder driver.
+
+use core::marker::PhantomData;
+
+use num_traits::{Bounded, One, SaturatingAdd, WrappingNeg, Zero};
+use quadrature_decoder::{Change, DistanceCodedDecoder, DistanceCodedError, FullStep, StepMode};
+
+#[allow(unused_imports)]
+use crate::{
+    mode::{Movement, OperationMode},
+    traits::*,
+    Error, InputPinError, Linear, Rotary,
+};
+
+/// Rotary encoder with distance-coded reference marks.
+pub type DistanceCodedRotaryEncoder<Clk, Dt, Idx, Steps = FullStep, T = i32> =
+    DistanceCodedEncoder<Rotary, Clk, Dt, Idx, Steps, T>;
+/// Linear encoder with distance-coded reference marks.
+pub type DistanceCodedLinearEncoder<Clk, Dt, Idx, Steps = FullStep, T = i32> =
+    DistanceCodedEncoder<Linear, Clk, Dt, Idx, Steps, T>;
+
+/// An incremental encoder with distance-coded reference marks (e.g. a linear scale),
+/// establishing its absolute position after traversing any two adjacent reference marks.
+///
+/// See `DistanceCodedDecoder` for details.
+#[derive(Debug)]
+pub struct DistanceCodedEncoder<Mode, Clk, Dt, Idx, Steps = FullStep, T = i32> {
+    decoder: DistanceCodedDecoder<Steps, T>,
+    pin_clk: Clk,
+    pin_dt: Dt,
+    pin_idx: Idx,
+    is_reversed: bool,
+    _mode: PhantomData<Mode>,
+}
+
+impl<Mode, Clk, Dt, Idx, Steps, T> DistanceCodedEncoder<Mode, Clk, Dt, Idx, Steps, T>
+where
+    Mode: OperationMode,
+    Clk: InputPin,
+    Dt: InputPin,
+    Idx: InputPin,
+    Steps: StepMode,
+    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + Bounded + From<i8> + TryFrom<i32>,
+{
+    /// Creates a distance-coded encoder driver for the given pins and decoder.
+    pub fn new(
+        pin_clk: Clk,
+        pin_dt: Dt,
+        pin_idx: Idx,
+        decoder: DistanceCodedDecoder<Steps, T>,
+    ) -> Self {
+        Self {
+            decoder,
+            pin_clk,
+            pin_dt,
+            pin_idx,
+            is_reversed: false,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Sets the encoder's reversed mode, making it report flipped movements and positions.
+    pub fn reversed(mut self) -> Self {
+        self.is_reversed = true;
+        self
+    }
+
+    /// Returns `true` if the encoder is reversed, otherwise `false`.
+    pub fn is_reversed(&self) -> bool {
+        self.is_reversed
+    }
+
+    /// Returns a borrow of the encoder's decoder.
+    pub fn decoder(&self) -> &DistanceCodedDecoder<Steps, T> {
+        &self.decoder
+    }
+
+    /// Returns mutable borrows for the signal channel pins and the index pin.
+    pub fn pins_mut(&mut self) -> (&mut Clk, &mut Dt, &mut Idx) {
+        (&mut self.pin_clk, &mut self.pin_dt, &mut self.pin_idx)
+    }
+
+    /// Consumes self, returning the signal channel pins and the index pin.
+    pub fn release(self) -> (Clk, Dt, Idx) {
+        (self.pin_clk, self.pin_dt, self.pin_idx)
+    }
+
+    /// Updates the encoder's state based on the given **clock**, **data**, and **index** pins,
+    /// returning the direction if a movement was detected, `None` if no movement was detected,
+    /// or `Err(_)` if an invalid input (i.e. a positional "jump", or an inconsistent reference mark)
+    /// was detected.
+    pub fn poll(&mut self) -> Result<Option<Mode::Movement>, Error> {
+        let clk = self
+            .pin_clk
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinClk))?;
+        let dt = self
+            .pin_dt
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinDt))?;
+        let idx = self
+            .pin_idx
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinIdx))?;
+
+        let change: Option<Change> =
+            self.decoder
+                .update(clk, dt, idx)
+                .map_err(|error| match error {
+                    DistanceCodedError::Quadrature(error) => Error::Quadrature(error),
+                    DistanceCodedError::Reference(error) => Error::Reference(error),
+                })?;
+        let movement: Option<Mode::Movement> = change.map(From::from);
+
+        Ok(movement.map(|movement| {
+            if self.is_reversed() {
+                movement.flipped()
+            } else {
+                movement
+            }
+        }))
+    }
+
+    /// Resets the encoder to its initial (i.e. unreferenced) state.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
+    /// Returns `true` if the absolute position has been established, otherwise `false`.
+    pub fn is_referenced(&self) -> bool {
+        self.decoder.is_referenced()
+    }
+
+    /// Returns the encoder's position in number of cycles, relative to its initial position
+    /// until the absolute position has been established, and absolute afterwards.
+    pub fn position(&self) -> T {
+        match self.is_reversed {
+            true => self.decoder.counter().wrapping_neg(),
+            false => self.decoder.counter(),
+        }
+    }
+
+    /// Sets the encoder's position.
+    pub fn set_position(&mut self, position: T) {
+        match self.is_reversed {
+            true => self.decoder.set_counter(position.wrapping_neg()),
+            false => self.decoder.set_counter(position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+    use quadrature_decoder::ReferenceError;
+
+    use crate::{LinearMovement, QuadStep};
+
+    use super::*;
+
+    /// The nominal increment of the test scale.
+    const NOMINAL: u32 = 40;
+    /// The basic spacing of the test scale.
+    const SPACING: u32 = 2;
+
+    /// The absolute positions of the test scale's reference marks.
+    const MARKS: [i32; 6] = [0, 22, 40, 64, 80, 106];
+
+    /// Returns the pins reading a travel along the given absolute `positions`,
+    /// with each of the reference `marks` spanning a full quadrature cycle.
+    ///
+    /// Travels should start at a multiple of 4, matching the decoder's initial levels.
+    fn pins(marks: &[i32], positions: impl Iterator<Item = i32>) -> (PinMock, PinMock, PinMock) {
+        let (mut clk, mut dt, mut idx) = (vec![], vec![], vec![]);
+
+        for position in positions {
+            let (a, b) = [(true, true), (false, true), (false, false), (true, false)]
+                [position.rem_euclid(4) as usize];
+            let z = marks
+                .iter()
+                .any(|mark| (*mark..*mark + 4).contains(&position));
+
+            for (transactions, level) in [(&mut clk, a), (&mut dt, b), (&mut idx, z)] {
+                let state = match level {
+                    true => PinState::High,
+                    false => PinState::Low,
+                };
+                transactions.push(PinTransaction::get(state));
+            }
+        }
+
+        (PinMock::new(&clk), PinMock::new(&dt), PinMock::new(&idx))
+    }
+
+    #[test]
+    fn reversed() {
+        let (clk, dt, idx) = pins(&MARKS, 32..=72);
+        let decoder = DistanceCodedDecoder::<QuadStep>::new(NOMINAL, SPACING);
+        let mut encoder = DistanceCodedLinearEncoder::new(clk, dt, idx, decoder).reversed();
+
+        assert_eq!(encoder.poll(), Ok(None));
+        for _ in 33..=72 {
+            assert_eq!(encoder.poll(), Ok(Some(LinearMovement::Backward)));
+        }
+
+        assert!(encoder.is_referenced());
+        assert_eq!(encoder.decoder().counter(), 72);
+        assert_eq!(encoder.position(), -72);
+
+        encoder.set_position(-100);
+        assert_eq!(encoder.decoder().counter(), 100);
+        assert_eq!(encoder.position(), -100);
+
+        let (mut clk, mut dt, mut idx) = encoder.release();
+        clk.done();
+        dt.done();
+        idx.done();
+    }
+
+    #[test]
+    fn reference_error() {
+        let (clk, dt, idx) = pins(&[35, 46], 32..=60);
+        let decoder = DistanceCodedDecoder::<QuadStep>::new(NOMINAL, SPACING);
+        let mut encoder = DistanceCodedLinearEncoder::new(clk, dt, idx, decoder);
+
+        let errors: Vec<_> = (32..=60).filter_map(|_| encoder.poll().err()).collect();
+
+        assert_eq!(
+            errors,
+            [Error::Reference(ReferenceError::Spacing { distance: 11 })]
+        );
+        assert!(!encoder.is_referenced());
+
+        let (mut clk, mut dt, mut idx) = encoder.release();
+        clk.done();
+        dt.done();
+        idx.done();
+    }
+}
@@ -0,0 +1,311 @@
+//! A dual-loop encoder driver, fusing a motor-side and a load-side encoder.
+
+use num_traits::{Bounded, One, SaturatingAdd, SaturatingSub, Signed, WrappingNeg, Zero};
+use quadrature_decoder::{Change, FullStep, StepMode};
+
+use super::ratio::scale;
+#[allow(unused_imports)]
+use crate::{
+    mode::{Blocking, LinearMovement},
+    traits::*,
+    Error, LinearEncoder, RotaryEncoder,
+};
+
+/// A dual-loop encoder, fusing a (high-resolution) motor-side rotary encoder
+/// with a load-side linear encoder, coupled via a gear or lead-screw ratio.
+///
+/// All positions are reported in number of the load encoder's pulses:
+///
+/// - The load position is the position as reported by the load encoder.
+/// - The deviation is the difference between the motor's (scaled) position
+///   and the load position, e.g. due to backlash or compliance of the drivetrain.
+/// - The blended position follows the motor encoder for fast movements and
+///   converges towards the load position over time (i.e. a complementary filter),
+///   which makes it well suited for closing a control loop.
+///
+/// The blending offset converges on every (successful) poll, i.e. while moving, as well as at rest,
+/// with the time-constant determining how many polls it takes to catch up with the deviation.
+/// Scaling gets performed in 128-bit arithmetic, so that it does not overflow
+/// for positions anywhere within the range of `T`.
+#[derive(Debug)]
+pub struct DualLoopEncoder<
+    MotorClk,
+    MotorDt,
+    LoadClk,
+    LoadDt,
+    MotorSteps = FullStep,
+    LoadSteps = MotorSteps,
+    T = i32,
+> {
+    motor: RotaryEncoder<MotorClk, MotorDt, MotorSteps, T, Blocking>,
+    load: LinearEncoder<LoadClk, LoadDt, LoadSteps, T, Blocking>,
+    motor_pulses: T,
+    load_pulses: T,
+    time_constant: T,
+    offset: T,
+}
+
+impl<MotorClk, MotorDt, LoadClk, LoadDt, MotorSteps, LoadSteps, T>
+    DualLoopEncoder<MotorClk, MotorDt, LoadClk, LoadDt, MotorSteps, LoadSteps, T>
+where
+    MotorClk: InputPin,
+    MotorDt: InputPin,
+    LoadClk: InputPin,
+    LoadDt: InputPin,
+    MotorSteps: StepMode,
+    LoadSteps: StepMode,
+    T: Copy
+        + Zero
+        + One
+        + Signed
+        + Ord
+        + SaturatingAdd
+        + SaturatingSub
+        + Bounded
+        + WrappingNeg
+        + From<i8>
+        + Into<i64>
+        + TryFrom<i64>,
+{
+    /// Creates a dual-loop encoder driver for the given motor and load encoders,
+    /// with a ratio of `1:1` and a blending time-constant of `1` (i.e. no blending).
+    pub fn new(
+        motor: RotaryEncoder<MotorClk, MotorDt, MotorSteps, T, Blocking>,
+        load: LinearEncoder<LoadClk, LoadDt, LoadSteps, T, Blocking>,
+    ) -> Self {
+        Self {
+            motor,
+            load,
+            motor_pulses: One::one(),
+            load_pulses: One::one(),
+            time_constant: One::one(),
+            offset: Zero::zero(),
+        }
+    }
+
+    /// Sets the drivetrain's ratio, i.e. the number of pulses reported by
+    /// the motor and the load encoder for the same physical movement.
+    ///
+    /// As an example, consider a motor encoder with 4096 pulses per revolution,
+    /// driving a lead-screw with a pitch of 5mm and a load encoder with
+    /// 1000 pulses per millimeter: `with_ratio(4096, 5000)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either of `motor_pulses` or `load_pulses` is not positive.
+    pub fn with_ratio(mut self, motor_pulses: T, load_pulses: T) -> Self {
+        assert!(
+            motor_pulses.is_positive() && load_pulses.is_positive(),
+            "Expected positive ratio."
+        );
+        self.motor_pulses = motor_pulses;
+        self.load_pulses = load_pulses;
+        self
+    }
+
+    /// Sets the blending time-constant, in number of polls.
+    ///
+    /// A time-constant of `1` makes the blended position follow the load position exactly,
+    /// while larger time-constants make it follow the motor position for longer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time_constant` is not positive.
+    pub fn with_time_constant(mut self, time_constant: T) -> Self {
+        assert!(
+            time_constant.is_positive(),
+            "Expected positive time-constant."
+        );
+        self.time_constant = time_constant;
+        self
+    }
+
+    /// Returns a borrow of the motor encoder.
+    pub fn motor(&self) -> &RotaryEncoder<MotorClk, MotorDt, MotorSteps, T> {
+        &self.motor
+    }
+
+    /// Returns a borrow of the load encoder.
+    pub fn load(&self) -> &LinearEncoder<LoadClk, LoadDt, LoadSteps, T> {
+        &self.load
+    }
+
+    /// Consumes self, returning the motor and load encoders.
+    #[allow(clippy::type_complexity)]
+    pub fn release(
+        self,
+    ) -> (
+        RotaryEncoder<MotorClk, MotorDt, MotorSteps, T>,
+        LinearEncoder<LoadClk, LoadDt, LoadSteps, T>,
+    ) {
+        (self.motor, self.load)
+    }
+
+    /// Updates both encoders' states based on their **clock** and **data** pins,
+    /// returning the direction if a movement of the blended position was detected,
+    /// `None` if no movement was detected, or `Err(_)` if either encoder
+    /// detected an invalid input (i.e. a positional "jump").
+    ///
+    /// Both encoders get polled on every call, even if the motor encoder reports an error,
+    /// with the blending offset only converging if neither of them does.
+    pub fn poll(&mut self) -> Result<Option<LinearMovement>, Error> {
+        let position_before = self.position();
+
+        let motor_result = self.motor.poll();
+        let load_result = self.load.poll();
+
+        motor_result?;
+        load_result?;
+
+        // Let the blending offset converge towards the current deviation,
+        // moving by at least a single pulse to avoid stalling due to truncation:
+        let delta = self.deviation().saturating_sub(&self.offset);
+        let step = match delta / self.time_constant {
+            step if step.is_zero() => delta.signum(),
+            step => step,
+        };
+        self.offset = self.offset.saturating_add(&step);
+
+        let position_after = self.position();
+
+        let change = match position_after.cmp(&position_before) {
+            core::cmp::Ordering::Less => Some(Change::Negative),
+            core::cmp::Ordering::Equal => None,
+            core::cmp::Ordering::Greater => Some(Change::Positive),
+        };
+
+        Ok(change.map(From::from))
+    }
+
+    /// Resets both encoders to their initial state.
+    pub fn reset(&mut self) {
+        self.motor.reset();
+        self.load.reset();
+        self.offset = Zero::zero();
+    }
+
+    /// Returns the motor encoder's position, scaled to the load encoder's pulses.
+    pub fn motor_position(&self) -> T {
+        scale(self.motor.position(), self.load_pulses, self.motor_pulses)
+    }
+
+    /// Returns the load encoder's position, in number of its pulses.
+    pub fn load_position(&self) -> T {
+        self.load.position()
+    }
+
+    /// Returns the deviation of the motor's (scaled) position from the load position,
+    /// in number of the load encoder's pulses.
+    pub fn deviation(&self) -> T {
+        self.motor_position().saturating_sub(&self.load_position())
+    }
+
+    /// Returns the blended position, in number of the load encoder's pulses.
+    pub fn position(&self) -> T {
+        self.motor_position().saturating_sub(&self.offset)
+    }
+
+    /// Sets the position of both encoders, in number of the load encoder's pulses.
+    pub fn set_position(&mut self, position: T) {
+        self.load.set_position(position);
+        self.motor
+            .set_position(scale(position, self.motor_pulses, self.load_pulses));
+        self.offset = self.deviation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    use crate::QuadStep;
+
+    use super::*;
+
+    fn pin(states: &[PinState]) -> PinMock {
+        let transactions: Vec<_> = states.iter().copied().map(PinTransaction::get).collect();
+        PinMock::new(&transactions)
+    }
+
+    #[test]
+    fn large_positions() {
+        use PinState::High;
+
+        let motor = RotaryEncoder::<_, _, QuadStep>::new(pin(&[High; 2]), pin(&[High; 2]));
+        let load = LinearEncoder::<_, _, QuadStep>::new(pin(&[High; 2]), pin(&[High; 2]));
+
+        let mut encoder = DualLoopEncoder::new(motor, load).with_ratio(4096, 5000);
+
+        // Far beyond the point at which `position * 5000` overflows an `i32`:
+        encoder.set_position(2_000_000_000);
+
+        assert_eq!(encoder.motor().position(), 1_638_400_000);
+        assert_eq!(encoder.motor_position(), 2_000_000_000);
+        assert_eq!(encoder.poll(), Ok(None));
+        assert_eq!(encoder.position(), 2_000_000_000);
+
+        let (motor, load) = encoder.release();
+        for (mut clk, mut dt) in [motor.release(), load.release()] {
+            clk.done();
+            dt.done();
+        }
+    }
+
+    #[test]
+    fn error_poll() {
+        use PinState::{High, Low};
+
+        // The motor encoder moves by a single pulse, while the load encoder reports an invalid jump:
+        let motor = RotaryEncoder::<_, _, QuadStep>::new(pin(&[High, Low]), pin(&[High, High]));
+        let load = LinearEncoder::<_, _, QuadStep>::new(pin(&[High, Low]), pin(&[High, Low]));
+
+        let mut encoder = DualLoopEncoder::new(motor, load);
+
+        assert!(encoder.poll().is_err());
+
+        // The blending offset did not converge towards the deviation of the failed poll:
+        assert_eq!(encoder.deviation(), 1);
+        assert_eq!(encoder.position(), 1);
+
+        let (motor, load) = encoder.release();
+        for (mut clk, mut dt) in [motor.release(), load.release()] {
+            clk.done();
+            dt.done();
+        }
+    }
+
+    #[test]
+    fn blending() {
+        use PinState::{High, Low};
+
+        // The motor encoder moves by a single pulse, while the load encoder does not (e.g. due to backlash):
+        let motor = RotaryEncoder::<_, _, QuadStep>::new(
+            pin(&[High, Low, Low, Low, Low, Low, Low]),
+            pin(&[High; 7]),
+        );
+        let load = LinearEncoder::<_, _, QuadStep>::new(pin(&[High; 7]), pin(&[High; 7]));
+
+        let mut encoder = DualLoopEncoder::new(motor, load)
+            .with_ratio(1, 16)
+            .with_time_constant(2);
+
+        let mut positions = Vec::new();
+        for _ in 0..6 {
+            assert!(encoder.poll().is_ok());
+            positions.push(encoder.position());
+        }
+
+        // The blended position follows the motor at first and then converges towards the load:
+        assert_eq!(encoder.motor_position(), 16);
+        assert_eq!(encoder.load_position(), 0);
+        assert_eq!(positions, [8, 4, 2, 1, 0, 0]);
+
+        let (motor, load) = encoder.release();
+        for (mut clk, mut dt) in [motor.release(), load.release()] {
+            clk.done();
+            dt.done();
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! A hall-assisted incremental encoder driver for brushless (BLDC) motors.
+
+use core::marker::PhantomData;
+
+use num_traits::{One, SaturatingAdd, WrappingNeg, Zero};
+use quadrature_decoder::{
+    Alignment, Change, FullStep, HallAssistedDecoder, HallAssistedError, StepMode,
+};
+
+#[allow(unused_imports)]
+use crate::{
+    mode::{Movement, OperationMode},
+    traits::*,
+    Error, InputPinError,
+};
+
+/// A hall-assisted incremental encoder for brushless (BLDC) motors, initializing
+/// its electrical angle from the motor's hall sensors (U, V, W) at startup,
+/// then tracking it via the incremental encoder and re-aligning it at the first hall edge.
+///
+/// See `HallAssistedDecoder` for details.
+#[derive(Debug)]
+pub struct HallAssistedEncoder<Mode, Clk, Dt, U, V, W, Steps = FullStep, T = i32> {
+    decoder: HallAssistedDecoder<Steps, T>,
+    pin_clk: Clk,
+    pin_dt: Dt,
+    pin_u: U,
+    pin_v: V,
+    pin_w: W,
+    is_reversed: bool,
+    _mode: PhantomData<Mode>,
+}
+
+impl<Mode, Clk, Dt, U, V, W, Steps, T> HallAssistedEncoder<Mode, Clk, Dt, U, V, W, Steps, T>
+where
+    Mode: OperationMode,
+    Clk: InputPin,
+    Dt: InputPin,
+    U: InputPin,
+    V: InputPin,
+    W: InputPin,
+    Steps: StepMode,
+    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + From<i8>,
+{
+    /// Creates a hall-assisted encoder driver for the given pins and decoder.
+    pub fn new(
+        pin_clk: Clk,
+        pin_dt: Dt,
+        pin_u: U,
+        pin_v: V,
+        pin_w: W,
+        decoder: HallAssistedDecoder<Steps, T>,
+    ) -> Self {
+        Self {
+            decoder,
+            pin_clk,
+            pin_dt,
+            pin_u,
+            pin_v,
+            pin_w,
+            is_reversed: false,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Sets the encoder's reversed mode, making it report flipped movements and positions.
+    pub fn reversed(mut self) -> Self {
+        self.is_reversed = true;
+        self
+    }
+
+    /// Returns `true` if the encoder is reversed, otherwise `false`.
+    pub fn is_reversed(&self) -> bool {
+        self.is_reversed
+    }
+
+    /// Returns a borrow of the encoder's decoder.
+    pub fn decoder(&self) -> &HallAssistedDecoder<Steps, T> {
+        &self.decoder
+    }
+
+    /// Returns mutable borrows for the signal channel pins and the hall sensor pins.
+    pub fn pins_mut(&mut self) -> (&mut Clk, &mut Dt, &mut U, &mut V, &mut W) {
+        (
+            &mut self.pin_clk,
+            &mut self.pin_dt,
+            &mut self.pin_u,
+            &mut self.pin_v,
+            &mut self.pin_w,
+        )
+    }
+
+    /// Consumes self, returning the signal channel pins and the hall sensor pins.
+    pub fn release(self) -> (Clk, Dt, U, V, W) {
+        (
+            self.pin_clk,
+            self.pin_dt,
+            self.pin_u,
+            self.pin_v,
+            self.pin_w,
+        )
+    }
+
+    /// Updates the encoder's state based on the **clock** and **data** pins,
+    /// as well as the **U**, **V** and **W** hall sensor pins,
+    /// returning the direction if a movement was detected, `None` if no movement was detected,
+    /// or `Err(_)` if an invalid input was detected.
+    ///
+    /// Upon invalid hall sensor readings the **clock** and **data** readings get discarded as well,
+    /// to be picked up by the next valid poll (see `HallAssistedDecoder::update`).
+    pub fn poll(&mut self) -> Result<Option<Mode::Movement>, Error> {
+        let clk = self
+            .pin_clk
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinClk))?;
+        let dt = self
+            .pin_dt
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinDt))?;
+        let u = self
+            .pin_u
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinU))?;
+        let v = self
+            .pin_v
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinV))?;
+        let w = self
+            .pin_w
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinW))?;
+
+        let change: Option<Change> =
+            self.decoder
+                .update(clk, dt, u, v, w)
+                .map_err(|error| match error {
+                    HallAssistedError::Quadrature(error) => Error::Quadrature(error),
+                    HallAssistedError::Hall(error) => Error::Hall(error),
+                })?;
+        let movement: Option<Mode::Movement> = change.map(From::from);
+
+        Ok(movement.map(|movement| {
+            if self.is_reversed() {
+                movement.flipped()
+            } else {
+                movement
+            }
+        }))
+    }
+
+    /// Resets the encoder to its initial (i.e. unaligned) state.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
+    /// Returns the alignment of the encoder's electrical angle.
+    pub fn alignment(&self) -> Alignment {
+        self.decoder.alignment()
+    }
+
+    /// Returns the encoder's electrical angle (in the range of `0..counts`),
+    /// or `None` if no valid hall state has been observed yet.
+    pub fn electrical_angle(&self) -> Option<u32> {
+        let counts = self.decoder.counts();
+        self.decoder
+            .electrical_angle()
+            .map(|angle| match self.is_reversed {
+                true => (counts - angle) % counts,
+                false => angle,
+            })
+    }
+
+    /// Returns the encoder's position counter relative to its initial position in number of cycles.
+    pub fn position(&self) -> T {
+        match self.is_reversed {
+            true => self.decoder.counter().wrapping_neg(),
+            false => self.decoder.counter(),
+        }
+    }
+
+    /// Sets the encoder's position.
+    pub fn set_position(&mut self, position: T) {
+        match self.is_reversed {
+            true => self.decoder.set_counter(position.wrapping_neg()),
+            false => self.decoder.set_counter(position),
+        }
+    }
+}
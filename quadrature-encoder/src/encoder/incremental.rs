@@ -3,7 +3,9 @@
 use core::marker::PhantomData;
 
 use num_traits::{One, SaturatingAdd, WrappingNeg, Zero};
-use quadrature_decoder::{Change, FullStep, IncrementalDecoder, StepMode};
+use quadrature_decoder::{
+    Change, FullStep, IncrementalDecoder, LinearityTable, Odometer, StepMode,
+};
 
 #[allow(unused_imports)]
 use crate::{
@@ -12,6 +14,8 @@ use crate::{
     Error, InputPinError, Linear, Rotary,
 };
 
+use super::backlash::Backlash;
+
 /// Rotary encoder.
 pub type RotaryEncoder<Clk, Dt, Steps = FullStep, T = i32, PM = Blocking> =
     IncrementalEncoder<Rotary, Clk, Dt, Steps, T, PM>;
@@ -28,6 +32,8 @@ pub struct IncrementalEncoder<Mode, Clk, Dt, Steps = FullStep, T = i32, PM = Blo
     pin_clk_state: bool,
     pin_dt_state: bool,
     is_reversed: bool,
+    backlash: Backlash<T>,
+    odometer: Option<Odometer>,
     _mode: PhantomData<Mode>,
     _poll_mode: PhantomData<PM>,
 }
@@ -59,6 +65,8 @@ where
             pin_clk_state,
             pin_dt_state,
             is_reversed: false,
+            backlash: Default::default(),
+            odometer: None,
             _mode: PhantomData,
             _poll_mode: PhantomData,
         }
@@ -85,6 +93,54 @@ where
         self.is_reversed
     }
 
+    /// Sets the encoder's backlash (i.e. the dead band applied after a change of direction,
+    /// in number of cycles), making the compensated position lag behind the (raw) position
+    /// by up to `backlash` cycles after a change of direction.
+    pub fn with_backlash(mut self, backlash: u32) -> Self {
+        self.backlash = Backlash::new(backlash);
+        self
+    }
+
+    /// Returns the encoder's backlash, in number of cycles.
+    pub fn backlash(&self) -> u32 {
+        self.backlash.width()
+    }
+
+    /// Attaches an odometer to the encoder, making it gather lifetime usage statistics
+    /// (i.e. total travel, reversals, maximum speed and time in motion),
+    /// with time being measured in number of polls, or in the units of the timestamps
+    /// if polled via `encoder.poll_at(timestamp)`.
+    ///
+    /// As async polls return upon every single edge (rather than periodically),
+    /// async encoders need to be polled via `encoder.poll_at(now)` for meaningful statistics.
+    pub fn with_odometer(mut self, odometer: Odometer) -> Self {
+        self.odometer = Some(odometer);
+        self
+    }
+
+    /// Returns the encoder's odometer, if any.
+    pub fn odometer(&self) -> Option<&Odometer> {
+        self.odometer.as_ref()
+    }
+
+    /// Returns the encoder's odometer mutably, if any.
+    pub fn odometer_mut(&mut self) -> Option<&mut Odometer> {
+        self.odometer.as_mut()
+    }
+
+    /// Enables or disables (aka gates) the encoder's counting, e.g. based on a gate input pin.
+    ///
+    /// While disabled the encoder keeps tracking (and validating) its pins,
+    /// but neither reports movements, nor updates its position.
+    pub fn set_enabled(&mut self, is_enabled: bool) {
+        self.decoder.set_enabled(is_enabled);
+    }
+
+    /// Returns `true` if the encoder's counting is enabled (which is the default), otherwise `false`.
+    pub fn is_enabled(&self) -> bool {
+        self.decoder.is_enabled()
+    }
+
     /// Returns mutable borrows for the signal channel pins.
     pub fn pins_mut(&mut self) -> (&mut Clk, &mut Dt) {
         (&mut self.pin_clk, &mut self.pin_dt)
@@ -97,28 +153,46 @@ where
 
     /// Updates the internal decoder state, from the latest IO readings.
     /// This is called within poll() / poll_async()
-    fn update(&mut self) -> Result<Option<Mode::Movement>, Error> {
-        let change: Option<Change> = self
-            .decoder
-            .update(self.pin_clk_state, self.pin_dt_state)
-            .map_err(Error::Quadrature)?;
+    fn update(&mut self, timestamp: Option<u32>) -> Result<Option<Mode::Movement>, Error> {
+        let (clk, dt) = (self.pin_clk_state, self.pin_dt_state);
+        let change: Option<Change> = match timestamp {
+            Some(timestamp) => self.decoder.update_at(clk, dt, timestamp),
+            None => self.decoder.update(clk, dt),
+        }
+        .map_err(Error::Quadrature)?;
+
+        if let Some(odometer) = self.odometer.as_mut() {
+            match timestamp {
+                Some(timestamp) => odometer.update_at(change, timestamp),
+                None => odometer.update(change),
+            }
+        }
+
         let movement: Option<Mode::Movement> = change.map(From::from);
 
-        Ok(movement.map(|movement| {
+        let movement = movement.map(|movement| {
             if self.is_reversed() {
                 movement.flipped()
             } else {
                 movement
             }
-        }))
+        });
+
+        if let Some(movement) = &movement {
+            let is_positive = *movement == Change::Positive.into();
+            self.backlash.update(is_positive);
+        }
+
+        Ok(movement)
     }
 
-    /// Resets the encoder to its initial state.
+    /// Resets the encoder to its initial state (retaining the odometer's statistics, if any).
     pub fn reset(&mut self) {
         self.decoder.reset();
+        self.backlash.reset();
     }
 
-    /// Returns the encoder's position counter relative to its initial position in number of cycles.
+    /// Returns the encoder's (raw) position counter relative to its initial position in number of cycles.
     pub fn position(&self) -> T {
         match self.is_reversed {
             true => self.decoder.counter().wrapping_neg(),
@@ -126,12 +200,29 @@ where
         }
     }
 
-    /// Sets the encoder's position.
+    /// Returns the encoder's backlash-compensated position counter relative to its initial position
+    /// in number of cycles, which equals the (raw) position unless a backlash has been set.
+    pub fn compensated_position(&self) -> T {
+        self.backlash.position()
+    }
+
+    /// Returns the encoder's (raw) position, corrected by the given linearity compensation `table`
+    /// (e.g. from a calibration against a laser interferometer), in number of cycles.
+    pub fn corrected_position<const N: usize>(&self, table: &LinearityTable<N>) -> i32
+    where
+        T: Into<i32>,
+    {
+        table.compensate(self.position().into())
+    }
+
+    /// Sets the encoder's (raw and backlash-compensated) position,
+    /// assuming the next movement to be engaged already (i.e. clearing the backlash's play).
     pub fn set_position(&mut self, position: T) {
         match self.is_reversed {
             true => self.decoder.set_counter(position.wrapping_neg()),
             false => self.decoder.set_counter(position),
         }
+        self.backlash.set_position(position);
     }
 }
 
@@ -152,6 +243,21 @@ where
     /// you would either call `encoder.poll()` directly, or via `encoder.poll().unwrap_or_default()`
     /// to fall back to `None` in case of `Err(_)`.
     pub fn poll(&mut self) -> Result<Option<Mode::Movement>, Error> {
+        self.read_pins()?;
+        self.update(None)
+    }
+
+    /// Updates the encoder's state based on the given **clock** and **data** pins,
+    /// sampled at the given `timestamp` (in arbitrary, but monotonic units, e.g. microseconds).
+    ///
+    /// Behaves exactly like `encoder.poll()`, but with the attached odometer (if any)
+    /// measuring time in the units of the timestamps.
+    pub fn poll_at(&mut self, timestamp: u32) -> Result<Option<Mode::Movement>, Error> {
+        self.read_pins()?;
+        self.update(Some(timestamp))
+    }
+
+    fn read_pins(&mut self) -> Result<(), Error> {
         self.pin_clk_state = self
             .pin_clk
             .is_high()
@@ -160,7 +266,7 @@ where
             .pin_dt
             .is_high()
             .map_err(|_| Error::InputPin(InputPinError::PinDt))?;
-        self.update()
+        Ok(())
     }
 }
 
@@ -186,6 +292,8 @@ where
             pin_clk_state: self.pin_clk_state,
             pin_dt_state: self.pin_dt_state,
             is_reversed: self.is_reversed,
+            backlash: self.backlash,
+            odometer: self.odometer,
             _mode: PhantomData,
             _poll_mode: PhantomData,
         }
@@ -212,6 +320,25 @@ where
     ///
     /// Waits asynchronously for any of the pins to change state, before returning.
     pub async fn poll(&mut self) -> Result<Option<Mode::Movement>, Error> {
+        self.wait_for_edge().await;
+        self.update(None)
+    }
+
+    /// Updates the encoder's state based on the given **clock** and **data** pins,
+    /// with `now` returning the current timestamp (in arbitrary, but monotonic units, e.g. microseconds),
+    /// which gets called once any of the pins changed state.
+    ///
+    /// Behaves exactly like `encoder.poll()`, but with the attached odometer (if any)
+    /// measuring time in the units of the timestamps.
+    pub async fn poll_at<F>(&mut self, now: F) -> Result<Option<Mode::Movement>, Error>
+    where
+        F: FnOnce() -> u32,
+    {
+        self.wait_for_edge().await;
+        self.update(Some(now()))
+    }
+
+    async fn wait_for_edge(&mut self) {
         let clk_fut = match self.pin_clk_state {
             true => self.pin_clk.wait_for_low().left_future(),
             false => self.pin_clk.wait_for_high().right_future(),
@@ -233,8 +360,6 @@ where
                 self.pin_dt_state = !self.pin_dt_state;
             }
         };
-
-        self.update()
     }
 
     /// Reconfigure the driver so that poll() is a blocking function
@@ -249,8 +374,65 @@ where
             pin_clk_state: self.pin_clk_state,
             pin_dt_state: self.pin_dt_state,
             is_reversed: self.is_reversed,
+            backlash: self.backlash,
+            odometer: self.odometer,
             _mode: PhantomData,
             _poll_mode: PhantomData,
         }
     }
 }
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use embassy_futures::block_on;
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    use crate::{LinearEncoder, LinearMovement, QuadStep};
+
+    use super::*;
+
+    #[test]
+    fn async_odometer() {
+        use PinState::{High, Low};
+
+        // Moving forwards, backwards, then forwards again:
+        let pin_clk = PinMock::new(&[
+            PinTransaction::get(High),
+            PinTransaction::wait_for_state(Low),
+            PinTransaction::wait_for_state(High),
+            PinTransaction::wait_for_state(Low),
+        ]);
+        let pin_dt = PinMock::new(&[PinTransaction::get(High)]);
+
+        let mut encoder = LinearEncoder::<_, _, QuadStep>::new(pin_clk, pin_dt)
+            .with_odometer(Odometer::new(1_000))
+            .into_async();
+
+        let mut timestamps = [100, 300, 600].into_iter();
+        let mut movements = vec![];
+        for _ in 0..3 {
+            let now = || timestamps.next().unwrap();
+            movements.push(block_on(encoder.poll_at(now)).unwrap());
+        }
+
+        assert_eq!(
+            movements,
+            vec![
+                Some(LinearMovement::Forward),
+                Some(LinearMovement::Backward),
+                Some(LinearMovement::Forward)
+            ]
+        );
+
+        let stats = encoder.odometer().unwrap().stats();
+        assert_eq!(stats.travel, 3);
+        assert_eq!(stats.reversals, 2);
+        assert_eq!(stats.motion, 500);
+
+        let (mut pin_clk, mut pin_dt) = encoder.release();
+        pin_clk.done();
+        pin_dt.done();
+    }
+}
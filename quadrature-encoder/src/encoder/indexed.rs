@@ -2,8 +2,10 @@
 
 use core::marker::PhantomData;
 
-use num_traits::{One, SaturatingAdd, WrappingNeg, Zero};
-use quadrature_decoder::{Change, FullStep, IndexedIncrementalDecoder, StepMode};
+use num_traits::{Bounded, One, SaturatingAdd, WrappingNeg, Zero};
+use quadrature_decoder::{
+    Change, FullStep, IndexQualifier, IndexedIncrementalDecoder, Odometer, StepMode,
+};
 
 #[allow(unused_imports)]
 use crate::{
@@ -30,6 +32,7 @@ pub struct IndexedIncrementalEncoder<Mode, Clk, Dt, Idx, Steps = FullStep, T = i
     pin_dt_state: bool,
     pin_idx_state: bool,
     is_reversed: bool,
+    odometer: Option<Odometer>,
     _mode: PhantomData<Mode>,
     _poll_mode: PhantomData<PM>,
 }
@@ -62,6 +65,7 @@ where
             pin_dt_state,
             pin_idx_state,
             is_reversed: false,
+            odometer: None,
             _mode: PhantomData,
             _poll_mode: PhantomData,
         }
@@ -75,7 +79,7 @@ where
     Dt: InputPin,
     Idx: InputPin,
     Steps: StepMode,
-    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + From<i8>,
+    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + Bounded + From<i8> + TryFrom<i32>,
     PM: PollMode,
 {
     /// Sets the encoder's reversed mode, making it report flipped movements and positions.
@@ -84,11 +88,53 @@ where
         self
     }
 
+    /// Sets the qualification criteria for accepting index pulses (idx pin),
+    /// with pulse widths being measured in number of polls.
+    pub fn with_index_qualifier(mut self, qualifier: IndexQualifier) -> Self {
+        self.decoder = self.decoder.with_index_qualifier(qualifier);
+        self
+    }
+
     /// Returns `true` if the encoder is reversed, otherwise `false`.
     pub fn is_reversed(&self) -> bool {
         self.is_reversed
     }
 
+    /// Attaches an odometer to the encoder, making it gather lifetime usage statistics
+    /// (i.e. total travel, reversals, maximum speed and time in motion),
+    /// with time being measured in number of polls, or in the units of the timestamps
+    /// if polled via `encoder.poll_at(timestamp)`.
+    ///
+    /// As async polls return upon every single edge (rather than periodically),
+    /// async encoders need to be polled via `encoder.poll_at(now)` for meaningful statistics.
+    pub fn with_odometer(mut self, odometer: Odometer) -> Self {
+        self.odometer = Some(odometer);
+        self
+    }
+
+    /// Returns the encoder's odometer, if any.
+    pub fn odometer(&self) -> Option<&Odometer> {
+        self.odometer.as_ref()
+    }
+
+    /// Returns the encoder's odometer mutably, if any.
+    pub fn odometer_mut(&mut self) -> Option<&mut Odometer> {
+        self.odometer.as_mut()
+    }
+
+    /// Enables or disables (aka gates) the encoder's counting, e.g. based on a gate input pin.
+    ///
+    /// While disabled the encoder keeps tracking (and validating) its pins,
+    /// but neither reports movements, nor updates its position.
+    pub fn set_enabled(&mut self, is_enabled: bool) {
+        self.decoder.set_enabled(is_enabled);
+    }
+
+    /// Returns `true` if the encoder's counting is enabled (which is the default), otherwise `false`.
+    pub fn is_enabled(&self) -> bool {
+        self.decoder.is_enabled()
+    }
+
     /// Returns mutable borrows for the signal channel pins.
     pub fn pins_mut(&mut self) -> (&mut Clk, &mut Dt) {
         (&mut self.pin_clk, &mut self.pin_dt)
@@ -101,11 +147,21 @@ where
 
     /// Updates the internal decoder state, from the latest IO readings.
     /// This is called within poll() / poll_async()
-    fn update(&mut self) -> Result<Option<Mode::Movement>, Error> {
-        let change: Option<Change> = self
-            .decoder
-            .update(self.pin_clk_state, self.pin_dt_state, self.pin_idx_state)
-            .map_err(Error::Quadrature)?;
+    fn update(&mut self, timestamp: Option<u32>) -> Result<Option<Mode::Movement>, Error> {
+        let (clk, dt, idx) = (self.pin_clk_state, self.pin_dt_state, self.pin_idx_state);
+        let change: Option<Change> = match timestamp {
+            Some(timestamp) => self.decoder.update_at(clk, dt, idx, timestamp),
+            None => self.decoder.update(clk, dt, idx),
+        }
+        .map_err(Error::Quadrature)?;
+
+        if let Some(odometer) = self.odometer.as_mut() {
+            match timestamp {
+                Some(timestamp) => odometer.update_at(change, timestamp),
+                None => odometer.update(change),
+            }
+        }
+
         let movement: Option<Mode::Movement> = change.map(From::from);
 
         Ok(movement.map(|movement| {
@@ -117,7 +173,7 @@ where
         }))
     }
 
-    /// Resets the encoder to its initial state.
+    /// Resets the encoder to its initial state (retaining the odometer's statistics, if any).
     pub fn reset(&mut self) {
         self.decoder.reset();
     }
@@ -146,7 +202,7 @@ where
     Dt: InputPin,
     Idx: InputPin,
     Steps: StepMode,
-    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + From<i8>,
+    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + Bounded + From<i8> + TryFrom<i32>,
 {
     /// Updates the encoder's state based on the given **clock**, **data**, and **index** pins,
     /// returning the direction if a movement was detected, `None` if no movement was detected,
@@ -157,6 +213,21 @@ where
     /// you would either call `encoder.poll()` directly, or via `encoder.poll().unwrap_or_default()`
     /// to fall back to `None` in case of `Err(_)`.
     pub fn poll(&mut self) -> Result<Option<Mode::Movement>, Error> {
+        self.read_pins()?;
+        self.update(None)
+    }
+
+    /// Updates the encoder's state based on the given **clock**, **data**, and **index** pins,
+    /// sampled at the given `timestamp` (in arbitrary, but monotonic units, e.g. microseconds).
+    ///
+    /// Behaves exactly like `encoder.poll()`, but with the attached odometer (if any)
+    /// measuring time in the units of the timestamps.
+    pub fn poll_at(&mut self, timestamp: u32) -> Result<Option<Mode::Movement>, Error> {
+        self.read_pins()?;
+        self.update(Some(timestamp))
+    }
+
+    fn read_pins(&mut self) -> Result<(), Error> {
         self.pin_clk_state = self
             .pin_clk
             .is_high()
@@ -169,7 +240,7 @@ where
             .pin_idx
             .is_high()
             .map_err(|_| Error::InputPin(InputPinError::PinIdx))?;
-        self.update()
+        Ok(())
     }
 }
 
@@ -182,7 +253,7 @@ where
     Dt: InputPin + Wait,
     Idx: InputPin + Wait,
     Steps: StepMode,
-    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + From<i8>,
+    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + Bounded + From<i8> + TryFrom<i32>,
 {
     /// Reconfigure the driver so that poll() is an async fn
     pub fn into_async(self) -> IndexedIncrementalEncoder<Mode, Clk, Dt, Idx, Steps, T, Async>
@@ -198,6 +269,7 @@ where
             pin_dt_state: self.pin_dt_state,
             pin_idx_state: self.pin_idx_state,
             is_reversed: self.is_reversed,
+            odometer: self.odometer,
             _mode: PhantomData,
             _poll_mode: PhantomData,
         }
@@ -212,7 +284,7 @@ where
     Dt: InputPin + Wait,
     Idx: InputPin + Wait,
     Steps: StepMode,
-    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + From<i8>,
+    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + Bounded + From<i8> + TryFrom<i32>,
 {
     /// Updates the encoder's state based on the given **clock**, **data**, and **index** pins,
     /// returning the direction if a movement was detected, `None` if no movement was detected,
@@ -225,6 +297,25 @@ where
     ///
     /// Waits asyncronously for any of the pins to change state, before returning.
     pub async fn poll(&mut self) -> Result<Option<Mode::Movement>, Error> {
+        self.wait_for_edge().await;
+        self.update(None)
+    }
+
+    /// Updates the encoder's state based on the given **clock**, **data**, and **index** pins,
+    /// with `now` returning the current timestamp (in arbitrary, but monotonic units, e.g. microseconds),
+    /// which gets called once any of the pins changed state.
+    ///
+    /// Behaves exactly like `encoder.poll()`, but with the attached odometer (if any)
+    /// measuring time in the units of the timestamps.
+    pub async fn poll_at<F>(&mut self, now: F) -> Result<Option<Mode::Movement>, Error>
+    where
+        F: FnOnce() -> u32,
+    {
+        self.wait_for_edge().await;
+        self.update(Some(now()))
+    }
+
+    async fn wait_for_edge(&mut self) {
         let clk_fut = match self.pin_clk_state {
             true => self.pin_clk.wait_for_low().left_future(),
             false => self.pin_clk.wait_for_high().right_future(),
@@ -254,8 +345,6 @@ where
                 self.pin_idx_state = !self.pin_idx_state;
             }
         };
-
-        self.update()
     }
 
     /// Reconfigure the driver so that poll() is a blocking function
@@ -272,6 +361,7 @@ where
             pin_dt_state: self.pin_dt_state,
             pin_idx_state: self.pin_idx_state,
             is_reversed: self.is_reversed,
+            odometer: self.odometer,
             _mode: PhantomData,
             _poll_mode: PhantomData,
         }
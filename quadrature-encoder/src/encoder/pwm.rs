@@ -0,0 +1,220 @@
+//! A PWM-output absolute encoder driver.
+
+use num_traits::{SaturatingAdd, WrappingNeg, Zero};
+use quadrature_decoder::{PwmDecoder, PwmFormat};
+
+#[allow(unused_imports)]
+use crate::{
+    mode::{Movement, RotaryMovement},
+    traits::*,
+    Error, InputPinError,
+};
+
+/// A PWM-output absolute rotary encoder (e.g. AS5600 and AS5048 magnetic angle sensors),
+/// whose duty cycle encodes its absolute angle.
+///
+/// The encoder's absolute angle gets unwrapped into a multi-turn position,
+/// which is reported just like the position of a `RotaryEncoder`.
+#[derive(Debug)]
+pub struct PwmEncoder<Pin, T = i32> {
+    decoder: PwmDecoder<T>,
+    pin: Pin,
+    pin_state: bool,
+    is_reversed: bool,
+}
+
+impl<Pin, T> PwmEncoder<Pin, T>
+where
+    Pin: InputPin,
+    T: Copy + Zero + SaturatingAdd + WrappingNeg + From<i32>,
+{
+    /// Creates a PWM encoder driver for the given pin and frame format.
+    pub fn new(mut pin: Pin, format: PwmFormat) -> Self {
+        // read the initial pin state to determine starting values
+        let pin_state = pin.is_high().unwrap_or(false);
+
+        Self {
+            decoder: PwmDecoder::new(format),
+            pin,
+            pin_state,
+            is_reversed: false,
+        }
+    }
+
+    /// Sets the decoder's tolerance for frame validation, in number of the sensor's clock periods.
+    pub fn with_tolerance(mut self, tolerance: u16) -> Self {
+        self.decoder = self.decoder.with_tolerance(tolerance);
+        self
+    }
+
+    /// Sets the encoder's reversed mode, making it report flipped movements and positions.
+    pub fn reversed(mut self) -> Self {
+        self.is_reversed = true;
+        self
+    }
+
+    /// Returns `true` if the encoder is reversed, otherwise `false`.
+    pub fn is_reversed(&self) -> bool {
+        self.is_reversed
+    }
+
+    /// Returns a mutable borrow for the PWM signal pin.
+    pub fn pin_mut(&mut self) -> &mut Pin {
+        &mut self.pin
+    }
+
+    /// Consumes self, returning the PWM signal pin.
+    pub fn release(self) -> Pin {
+        self.pin
+    }
+
+    /// Updates the encoder's state based on the **PWM** pin, sampled at the given `timestamp`
+    /// (in arbitrary, but monotonic units, e.g. microseconds), returning the direction
+    /// if a movement was detected, `None` if no movement was detected,
+    /// or `Err(_)` if an invalid frame was detected.
+    ///
+    /// The encoder needs to be polled frequently enough for detecting the duty cycle
+    /// with sufficient precision, which is why `encoder.update(level, timestamp)`
+    /// in combination with a timer's input capture is usually preferable.
+    pub fn poll(&mut self, timestamp: u32) -> Result<Option<RotaryMovement>, Error> {
+        let pin_state = self
+            .pin
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinPwm))?;
+
+        if pin_state == self.pin_state {
+            return Ok(None);
+        }
+
+        self.update(pin_state, timestamp)
+    }
+
+    /// Updates the encoder's state based on an edge of the PWM signal,
+    /// with `level` being the signal's level after the edge (i.e. `true` for a rising edge)
+    /// and `timestamp` being the edge's time (in arbitrary, but monotonic units, e.g. microseconds),
+    /// returning the direction if a movement was detected, `None` if no movement was detected,
+    /// or `Err(_)` if an invalid frame was detected.
+    pub fn update(&mut self, level: bool, timestamp: u32) -> Result<Option<RotaryMovement>, Error> {
+        self.pin_state = level;
+
+        let movement: Option<RotaryMovement> = self
+            .decoder
+            .update(level, timestamp)
+            .map_err(Error::Pwm)?
+            .map(From::from);
+
+        Ok(movement.map(|movement| {
+            if self.is_reversed() {
+                movement.flipped()
+            } else {
+                movement
+            }
+        }))
+    }
+
+    /// Resets the encoder to its initial state.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
+    /// Returns the encoder's most recent absolute angle (in the range of `0..positions`),
+    /// or `None` if no valid frame has been decoded yet.
+    pub fn angle(&self) -> Option<u16> {
+        let positions = self.decoder.format().positions;
+        self.decoder.angle().map(|angle| match self.is_reversed {
+            true => (positions - angle) % positions,
+            false => angle,
+        })
+    }
+
+    /// Returns the encoder's (multi-turn) position in number of positions.
+    pub fn position(&self) -> T {
+        match self.is_reversed {
+            true => self.decoder.position().wrapping_neg(),
+            false => self.decoder.position(),
+        }
+    }
+
+    /// Sets the encoder's position.
+    pub fn set_position(&mut self, position: T) {
+        match self.is_reversed {
+            true => self.decoder.set_position(position.wrapping_neg()),
+            false => self.decoder.set_position(position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::{
+        digital::{Mock as PinMock, State as PinState, Transaction as PinTransaction},
+        MockError,
+    };
+    use quadrature_decoder::PwmError;
+
+    use super::*;
+
+    /// A frame format with a single clock period per init and exit section, and 8 positions.
+    const FORMAT: PwmFormat = PwmFormat {
+        init: 1,
+        error: 0,
+        positions: 8,
+        exit: 1,
+    };
+
+    #[test]
+    fn polling() {
+        use PinState::{High, Low};
+
+        // Frames reporting angles 2 and 5, with the second one being decoded on the third rising edge:
+        let pin = PinMock::new(&[Low, High, Low, Low, High, Low, High].map(PinTransaction::get));
+        let mut encoder: PwmEncoder<_> = PwmEncoder::new(pin, FORMAT);
+
+        for timestamp in [0, 3, 5, 9, 15] {
+            assert_eq!(encoder.poll(timestamp), Ok(None));
+        }
+        assert_eq!(encoder.poll(18), Ok(Some(RotaryMovement::Clockwise)));
+        assert_eq!(encoder.angle(), Some(5));
+        assert_eq!(encoder.position(), 5);
+
+        encoder.release().done();
+    }
+
+    #[test]
+    fn reversed() {
+        let pin = PinMock::new(&[PinTransaction::get(PinState::Low)]);
+        let mut encoder: PwmEncoder<_> = PwmEncoder::new(pin, FORMAT).reversed();
+
+        for (level, timestamp) in [(true, 0), (false, 3), (true, 9), (false, 15)] {
+            assert_eq!(encoder.update(level, timestamp), Ok(None));
+        }
+        assert_eq!(
+            encoder.update(true, 18),
+            Ok(Some(RotaryMovement::CounterClockwise))
+        );
+        assert_eq!(encoder.angle(), Some(3));
+        assert_eq!(encoder.position(), -5);
+
+        encoder.set_position(10);
+        assert_eq!(encoder.position(), 10);
+
+        encoder.release().done();
+    }
+
+    #[test]
+    fn errors() {
+        let pin = PinMock::new(&[
+            PinTransaction::get(PinState::Low),
+            PinTransaction::get(PinState::High)
+                .with_error(MockError::Io(std::io::ErrorKind::Other)),
+        ]);
+        let mut encoder: PwmEncoder<_> = PwmEncoder::new(pin, FORMAT);
+
+        assert_eq!(encoder.poll(0), Err(Error::InputPin(InputPinError::PinPwm)));
+
+        assert_eq!(encoder.update(true, 0), Ok(None));
+        assert_eq!(encoder.update(true, 9), Err(Error::Pwm(PwmError::Edge)));
+
+        encoder.release().done();
+    }
+}
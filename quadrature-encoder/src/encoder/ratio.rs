@@ -0,0 +1,42 @@
+//! Ratio scaling, shared by drivers fusing encoders of different resolutions.
+
+use num_traits::Bounded;
+
+/// Returns `value * numerator / denominator`, computed in 128-bit arithmetic
+/// and saturated to the range of `T`.
+pub(crate) fn scale<T>(value: T, numerator: T, denominator: T) -> T
+where
+    T: Copy + Bounded + Into<i64> + TryFrom<i64>,
+{
+    let value: i64 = value.into();
+    let numerator: i64 = numerator.into();
+    let denominator: i64 = denominator.into();
+
+    let scaled = i128::from(value) * i128::from(numerator) / i128::from(denominator);
+
+    saturate(scaled.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64)
+}
+
+/// Returns `value`, saturated to the range of `T`.
+pub(crate) fn saturate<T>(value: i64) -> T
+where
+    T: Bounded + TryFrom<i64>,
+{
+    T::try_from(value).unwrap_or_else(|_| match value < 0 {
+        true => T::min_value(),
+        false => T::max_value(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaling() {
+        assert_eq!(scale(i32::MAX, 4096, 1000), i32::MAX);
+        assert_eq!(scale(i32::MIN, 4096, 1000), i32::MIN);
+        assert_eq!(scale(1_000_000, 4096, 1000), 4_096_000);
+        assert_eq!(scale(-7_i16, 3, 2), -10);
+    }
+}
@@ -0,0 +1,371 @@
+//! A redundant encoder driver, cross-checking a pair of incremental encoders.
+
+use num_traits::{Bounded, One, SaturatingAdd, SaturatingSub, Signed, WrappingNeg, Zero};
+use quadrature_decoder::{Change, FullStep, StepMode};
+
+use super::ratio::{saturate, scale};
+#[allow(unused_imports)]
+use crate::{
+    mode::{Blocking, OperationMode},
+    traits::*,
+    Error, IncrementalEncoder,
+};
+
+/// A redundant encoder, mounting two incremental encoders on a single shaft.
+///
+/// Both encoders are polled together and their positions get cross-checked continuously.
+/// The secondary encoder's position is scaled into the primary encoder's units
+/// (i.e. number of pulses) according to the configured ratio, while the
+/// "voted" position of the redundant encoder lies halfway between the two.
+///
+/// A discrepancy fault is raised whenever the two (scaled) positions diverge
+/// by more than the configured tolerance, which defaults to `ceil(primary / secondary)` pulses
+/// (i.e. the quantization of a coarser secondary encoder, in the primary encoder's pulses).
+///
+/// Scaling gets performed in 128-bit arithmetic, so that it does not overflow
+/// for positions anywhere within the range of `T`.
+#[derive(Debug)]
+pub struct RedundantEncoder<
+    Mode,
+    PrimaryClk,
+    PrimaryDt,
+    SecondaryClk,
+    SecondaryDt,
+    PrimarySteps = FullStep,
+    SecondarySteps = PrimarySteps,
+    T = i32,
+> {
+    primary: IncrementalEncoder<Mode, PrimaryClk, PrimaryDt, PrimarySteps, T, Blocking>,
+    secondary: IncrementalEncoder<Mode, SecondaryClk, SecondaryDt, SecondarySteps, T, Blocking>,
+    primary_pulses: T,
+    secondary_pulses: T,
+    tolerance: Option<T>,
+    is_faulted: bool,
+}
+
+impl<Mode, PrimaryClk, PrimaryDt, SecondaryClk, SecondaryDt, PrimarySteps, SecondarySteps, T>
+    RedundantEncoder<
+        Mode,
+        PrimaryClk,
+        PrimaryDt,
+        SecondaryClk,
+        SecondaryDt,
+        PrimarySteps,
+        SecondarySteps,
+        T,
+    >
+where
+    Mode: OperationMode,
+    PrimaryClk: InputPin,
+    PrimaryDt: InputPin,
+    SecondaryClk: InputPin,
+    SecondaryDt: InputPin,
+    PrimarySteps: StepMode,
+    SecondarySteps: StepMode,
+    T: Copy
+        + Zero
+        + One
+        + Signed
+        + Ord
+        + SaturatingAdd
+        + SaturatingSub
+        + Bounded
+        + WrappingNeg
+        + From<i8>
+        + Into<i64>
+        + TryFrom<i64>,
+{
+    /// Creates a redundant encoder driver for the given pair of encoders,
+    /// with a ratio of `1:1` and the default tolerance (see `encoder.tolerance()`).
+    pub fn new(
+        primary: IncrementalEncoder<Mode, PrimaryClk, PrimaryDt, PrimarySteps, T, Blocking>,
+        secondary: IncrementalEncoder<Mode, SecondaryClk, SecondaryDt, SecondarySteps, T, Blocking>,
+    ) -> Self {
+        Self {
+            primary,
+            secondary,
+            primary_pulses: One::one(),
+            secondary_pulses: One::one(),
+            tolerance: None,
+            is_faulted: false,
+        }
+    }
+
+    /// Sets the encoders' ratio, i.e. the number of pulses reported by
+    /// the primary and the secondary encoder for the same physical movement.
+    ///
+    /// As an example, consider a primary encoder with 4096 pulses per revolution
+    /// and a secondary encoder with 1000 pulses per revolution: `with_ratio(4096, 1000)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either of `primary_pulses` or `secondary_pulses` is not positive.
+    pub fn with_ratio(mut self, primary_pulses: T, secondary_pulses: T) -> Self {
+        assert!(
+            primary_pulses.is_positive() && secondary_pulses.is_positive(),
+            "Expected positive ratio."
+        );
+        self.primary_pulses = primary_pulses;
+        self.secondary_pulses = secondary_pulses;
+        self
+    }
+
+    /// Sets the maximum tolerated deviation between the encoders' positions,
+    /// in number of the primary encoder's pulses.
+    ///
+    /// Tolerances below `ceil(primary / secondary)` pulses (i.e. the default) result in
+    /// discrepancy faults on healthy encoders, due to the secondary encoder's quantization.
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = Some(tolerance.abs());
+        self
+    }
+
+    /// Returns the maximum tolerated deviation, in number of the primary encoder's pulses,
+    /// defaulting to `ceil(primary / secondary)` pulses, unless set explicitly.
+    pub fn tolerance(&self) -> T {
+        self.tolerance.unwrap_or_else(|| {
+            let primary: i64 = self.primary_pulses.into();
+            let secondary: i64 = self.secondary_pulses.into();
+            saturate((primary + secondary - 1) / secondary)
+        })
+    }
+
+    /// Returns a borrow of the primary encoder.
+    pub fn primary(&self) -> &IncrementalEncoder<Mode, PrimaryClk, PrimaryDt, PrimarySteps, T> {
+        &self.primary
+    }
+
+    /// Returns a borrow of the secondary encoder.
+    pub fn secondary(
+        &self,
+    ) -> &IncrementalEncoder<Mode, SecondaryClk, SecondaryDt, SecondarySteps, T> {
+        &self.secondary
+    }
+
+    /// Consumes self, returning the pair of encoders.
+    #[allow(clippy::type_complexity)]
+    pub fn release(
+        self,
+    ) -> (
+        IncrementalEncoder<Mode, PrimaryClk, PrimaryDt, PrimarySteps, T>,
+        IncrementalEncoder<Mode, SecondaryClk, SecondaryDt, SecondarySteps, T>,
+    ) {
+        (self.primary, self.secondary)
+    }
+
+    /// Updates both encoders' states based on their **clock** and **data** pins,
+    /// returning the direction if a movement of the voted position was detected,
+    /// `None` if no movement was detected, or `Err(_)` if either encoder
+    /// detected an invalid input, or the encoders' positions diverged
+    /// beyond the tolerated deviation (i.e. `Err(Error::Discrepancy)`).
+    ///
+    /// Both encoders get polled on every call, even if the primary encoder reports an error.
+    pub fn poll(&mut self) -> Result<Option<Mode::Movement>, Error> {
+        let position_before = self.position();
+
+        let primary_result = self.primary.poll();
+        let secondary_result = self.secondary.poll();
+
+        primary_result?;
+        secondary_result?;
+
+        if self.deviation().abs() > self.tolerance() {
+            self.is_faulted = true;
+            return Err(Error::Discrepancy);
+        }
+
+        let position_after = self.position();
+
+        let change = match position_after.cmp(&position_before) {
+            core::cmp::Ordering::Less => Some(Change::Negative),
+            core::cmp::Ordering::Equal => None,
+            core::cmp::Ordering::Greater => Some(Change::Positive),
+        };
+
+        Ok(change.map(From::from))
+    }
+
+    /// Resets both encoders to their initial state and clears the discrepancy fault.
+    pub fn reset(&mut self) {
+        self.primary.reset();
+        self.secondary.reset();
+        self.is_faulted = false;
+    }
+
+    /// Returns `true` if a discrepancy has been detected since the fault was last cleared.
+    pub fn is_faulted(&self) -> bool {
+        self.is_faulted
+    }
+
+    /// Clears the discrepancy fault.
+    pub fn clear_fault(&mut self) {
+        self.is_faulted = false;
+    }
+
+    /// Returns the primary encoder's position, in number of its pulses.
+    pub fn primary_position(&self) -> T {
+        self.primary.position()
+    }
+
+    /// Returns the secondary encoder's position, scaled to the primary encoder's pulses.
+    pub fn secondary_position(&self) -> T {
+        scale(
+            self.secondary.position(),
+            self.primary_pulses,
+            self.secondary_pulses,
+        )
+    }
+
+    /// Returns the deviation of the secondary encoder's (scaled) position
+    /// from the primary encoder's position, in number of the primary encoder's pulses.
+    pub fn deviation(&self) -> T {
+        self.secondary_position()
+            .saturating_sub(&self.primary_position())
+    }
+
+    /// Returns the voted position, i.e. the midpoint of both encoders' positions,
+    /// in number of the primary encoder's pulses.
+    pub fn position(&self) -> T {
+        let two: T = 2.into();
+        self.primary_position()
+            .saturating_add(&(self.deviation() / two))
+    }
+
+    /// Sets the position of both encoders, in number of the primary encoder's pulses.
+    pub fn set_position(&mut self, position: T) {
+        self.primary.set_position(position);
+        self.secondary
+            .set_position(scale(position, self.secondary_pulses, self.primary_pulses));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    use crate::{QuadStep, RotaryEncoder};
+
+    use super::*;
+
+    fn pin(states: &[PinState]) -> PinMock {
+        let transactions: Vec<_> = states.iter().copied().map(PinTransaction::get).collect();
+        PinMock::new(&transactions)
+    }
+
+    #[test]
+    fn default_tolerance() {
+        use PinState::{High, Low};
+
+        // The primary encoder moves by a single pulse, while the (coarser) secondary one does not:
+        let primary = RotaryEncoder::<_, _, QuadStep>::new(pin(&[High, Low]), pin(&[High, High]));
+        let secondary = RotaryEncoder::<_, _>::new(pin(&[High, High]), pin(&[High, High]));
+
+        let mut encoder = RedundantEncoder::new(primary, secondary).with_ratio(4, 1);
+
+        assert_eq!(encoder.tolerance(), 4);
+        assert!(encoder.poll().is_ok());
+        assert_eq!(encoder.deviation(), -1);
+        assert!(!encoder.is_faulted());
+
+        let (primary, secondary) = encoder.release();
+        for (mut clk, mut dt) in [primary.release(), secondary.release()] {
+            clk.done();
+            dt.done();
+        }
+    }
+
+    #[test]
+    fn large_positions() {
+        use PinState::High;
+
+        let primary = RotaryEncoder::<_, _, QuadStep>::new(pin(&[High; 2]), pin(&[High; 2]));
+        let secondary = RotaryEncoder::<_, _, QuadStep>::new(pin(&[High; 2]), pin(&[High; 2]));
+
+        let mut encoder = RedundantEncoder::new(primary, secondary).with_ratio(4096, 1000);
+
+        // Far beyond the point at which `position * 4096` overflows an `i32`:
+        encoder.set_position(2_000_000_000);
+
+        assert_eq!(encoder.secondary().position(), 488_281_250);
+        assert_eq!(encoder.secondary_position(), 2_000_000_000);
+        assert!(encoder.poll().is_ok());
+        assert!(!encoder.is_faulted());
+
+        let (primary, secondary) = encoder.release();
+        for (mut clk, mut dt) in [primary.release(), secondary.release()] {
+            clk.done();
+            dt.done();
+        }
+    }
+
+    #[test]
+    fn discrepancy() {
+        use PinState::{High, Low};
+
+        // The primary encoder moves by three pulses, after which the secondary one catches up:
+        let primary = RotaryEncoder::<_, _, QuadStep>::new(
+            pin(&[High, Low, Low, High, High, High, High]),
+            pin(&[High, High, Low, Low, Low, Low, Low]),
+        );
+        let secondary = RotaryEncoder::<_, _, QuadStep>::new(
+            pin(&[High, High, High, High, Low, Low, High]),
+            pin(&[High, High, High, High, High, Low, Low]),
+        );
+
+        let mut encoder = RedundantEncoder::new(primary, secondary);
+
+        assert_eq!(encoder.tolerance(), 1);
+
+        assert!(encoder.poll().is_ok());
+        assert!(!encoder.is_faulted());
+
+        assert_eq!(encoder.poll(), Err(Error::Discrepancy));
+        assert!(encoder.is_faulted());
+        assert_eq!(encoder.poll(), Err(Error::Discrepancy));
+        assert_eq!(encoder.deviation().abs(), 3);
+
+        assert_eq!(encoder.poll(), Err(Error::Discrepancy));
+
+        // The fault stays latched, even once the encoders agree again:
+        assert!(encoder.poll().is_ok());
+        assert!(encoder.is_faulted());
+        assert!(encoder.poll().is_ok());
+        assert_eq!(encoder.deviation(), 0);
+        assert!(encoder.is_faulted());
+
+        encoder.clear_fault();
+        assert!(!encoder.is_faulted());
+
+        let (primary, secondary) = encoder.release();
+        for (mut clk, mut dt) in [primary.release(), secondary.release()] {
+            clk.done();
+            dt.done();
+        }
+    }
+
+    #[test]
+    fn within_tolerance() {
+        use PinState::{High, Low};
+
+        // The primary encoder moves by two pulses, while the secondary one does not:
+        let primary =
+            RotaryEncoder::<_, _, QuadStep>::new(pin(&[High, Low, Low]), pin(&[High, High, Low]));
+        let secondary =
+            RotaryEncoder::<_, _, QuadStep>::new(pin(&[High, High, High]), pin(&[High; 3]));
+
+        let mut encoder = RedundantEncoder::new(primary, secondary).with_tolerance(2);
+
+        assert!(encoder.poll().is_ok());
+        assert!(encoder.poll().is_ok());
+        assert_eq!(encoder.deviation().abs(), 2);
+        assert!(!encoder.is_faulted());
+
+        let (primary, secondary) = encoder.release();
+        for (mut clk, mut dt) in [primary.release(), secondary.release()] {
+            clk.done();
+            dt.done();
+        }
+    }
+}
@@ -0,0 +1,330 @@
+//! An SSI (synchronous serial interface) absolute encoder driver.
+
+use num_traits::{SaturatingAdd, WrappingNeg, Zero};
+use quadrature_decoder::{SsiDecoder, SsiError};
+
+#[allow(unused_imports)]
+use crate::{
+    mode::{Movement, RotaryMovement},
+    traits::*,
+    Error, InputPinError,
+};
+
+/// A bus for clocking in the frames of SSI absolute encoders.
+pub trait SsiBus {
+    /// Clocks in a frame of `bits` bits (at most 64),
+    /// returning them with the first received bit being the most significant one.
+    fn read_frame(&mut self, bits: u8) -> Result<u64, Error>;
+}
+
+/// An SSI bus, bit-banged via a **clock** output pin and a **data** input pin.
+///
+/// The clock idles high, with each bit getting sampled after the clock's rising edge.
+#[derive(Debug)]
+pub struct SsiPins<Clk, Data, Delay> {
+    pin_clk: Clk,
+    pin_data: Data,
+    delay: Delay,
+    half_period: u32,
+}
+
+impl<Clk, Data, Delay> SsiPins<Clk, Data, Delay>
+where
+    Clk: OutputPin,
+    Data: InputPin,
+    Delay: DelayNs,
+{
+    /// The default half-period of the clock, in nanoseconds (i.e. 500 kHz).
+    pub const DEFAULT_HALF_PERIOD: u32 = 1_000;
+
+    /// Creates a bit-banged SSI bus for the given pins.
+    pub fn new(pin_clk: Clk, pin_data: Data, delay: Delay) -> Self {
+        Self {
+            pin_clk,
+            pin_data,
+            delay,
+            half_period: Self::DEFAULT_HALF_PERIOD,
+        }
+    }
+
+    /// Sets the half-period of the clock, in nanoseconds.
+    pub fn with_half_period(mut self, half_period: u32) -> Self {
+        self.half_period = half_period;
+        self
+    }
+
+    /// Returns the half-period of the clock, in nanoseconds.
+    pub fn half_period(&self) -> u32 {
+        self.half_period
+    }
+
+    /// Consumes self, returning the pins and the delay.
+    pub fn release(self) -> (Clk, Data, Delay) {
+        (self.pin_clk, self.pin_data, self.delay)
+    }
+
+    fn read_data(&mut self) -> Result<bool, Error> {
+        self.pin_data
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinDt))
+    }
+}
+
+impl<Clk, Data, Delay> SsiBus for SsiPins<Clk, Data, Delay>
+where
+    Clk: OutputPin,
+    Data: InputPin,
+    Delay: DelayNs,
+{
+    fn read_frame(&mut self, bits: u8) -> Result<u64, Error> {
+        // An idle data line being low indicates that the encoder's monoflop
+        // has not timed out yet since the previous frame:
+        if !self.read_data()? {
+            return Err(Error::Ssi(SsiError::Monoflop));
+        }
+
+        let mut frame: u64 = 0;
+
+        for _ in 0..bits {
+            self.pin_clk.set_low().map_err(|_| Error::Bus)?;
+            self.delay.delay_ns(self.half_period);
+            self.pin_clk.set_high().map_err(|_| Error::Bus)?;
+            self.delay.delay_ns(self.half_period);
+            frame = (frame << 1) | u64::from(self.read_data()?);
+        }
+
+        Ok(frame)
+    }
+}
+
+/// An SSI bus, driven by an SPI peripheral.
+///
+/// The SPI device is expected to be configured with a clock idling high (i.e. `CPOL = 1`),
+/// with the clock phase depending on the encoder's timing (usually `CPHA = 1`).
+/// As frames get read in whole bytes, the encoder's monoflop time needs to exceed
+/// the duration of the (up to 7) surplus clock cycles.
+#[derive(Debug)]
+pub struct SsiSpi<Spi> {
+    spi: Spi,
+}
+
+impl<Spi> SsiSpi<Spi>
+where
+    Spi: SpiDevice,
+{
+    /// Creates an SPI-driven SSI bus for the given SPI device.
+    pub fn new(spi: Spi) -> Self {
+        Self { spi }
+    }
+
+    /// Consumes self, returning the SPI device.
+    pub fn release(self) -> Spi {
+        self.spi
+    }
+}
+
+impl<Spi> SsiBus for SsiSpi<Spi>
+where
+    Spi: SpiDevice,
+{
+    fn read_frame(&mut self, bits: u8) -> Result<u64, Error> {
+        let bytes = usize::from(bits.div_ceil(8));
+        let mut buffer = [0_u8; 8];
+
+        self.spi
+            .read(&mut buffer[..bytes])
+            .map_err(|_| Error::Bus)?;
+
+        let frame = buffer[..bytes]
+            .iter()
+            .fold(0_u64, |frame, &byte| (frame << 8) | u64::from(byte));
+
+        // Discard the surplus bits of the last byte:
+        Ok(frame >> (bytes as u32 * 8 - u32::from(bits)))
+    }
+}
+
+/// An SSI (synchronous serial interface) absolute rotary encoder.
+///
+/// The encoder's absolute angle gets unwrapped into a multi-turn position,
+/// which is reported just like the position of a `RotaryEncoder`.
+#[derive(Debug)]
+pub struct SsiEncoder<Bus, T = i32> {
+    decoder: SsiDecoder<T>,
+    bus: Bus,
+    is_reversed: bool,
+}
+
+impl<Bus, T> SsiEncoder<Bus, T>
+where
+    Bus: SsiBus,
+    T: Copy + Zero + SaturatingAdd + WrappingNeg + From<i32>,
+{
+    /// Creates an SSI encoder driver for the given bus and frame decoder.
+    pub fn new(bus: Bus, decoder: SsiDecoder<T>) -> Self {
+        Self {
+            decoder,
+            bus,
+            is_reversed: false,
+        }
+    }
+
+    /// Sets the encoder's reversed mode, making it report flipped movements and positions.
+    pub fn reversed(mut self) -> Self {
+        self.is_reversed = true;
+        self
+    }
+
+    /// Returns `true` if the encoder is reversed, otherwise `false`.
+    pub fn is_reversed(&self) -> bool {
+        self.is_reversed
+    }
+
+    /// Returns a borrow of the encoder's frame decoder.
+    pub fn decoder(&self) -> &SsiDecoder<T> {
+        &self.decoder
+    }
+
+    /// Returns a mutable borrow for the bus.
+    pub fn bus_mut(&mut self) -> &mut Bus {
+        &mut self.bus
+    }
+
+    /// Consumes self, returning the bus.
+    pub fn release(self) -> Bus {
+        self.bus
+    }
+
+    /// Reads a frame from the encoder, returning the direction if a movement was detected,
+    /// `None` if no movement was detected, or `Err(_)` if an invalid frame was detected.
+    pub fn poll(&mut self) -> Result<Option<RotaryMovement>, Error> {
+        let frame = self.bus.read_frame(self.decoder.frame_bits())?;
+
+        let movement: Option<RotaryMovement> = self
+            .decoder
+            .update(frame)
+            .map_err(Error::Ssi)?
+            .map(From::from);
+
+        Ok(movement.map(|movement| {
+            if self.is_reversed() {
+                movement.flipped()
+            } else {
+                movement
+            }
+        }))
+    }
+
+    /// Resets the encoder to its initial state.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
+    /// Returns the encoder's most recent absolute angle (in the range of `0..resolution`),
+    /// or `None` if no valid frame has been read yet.
+    pub fn angle(&self) -> Option<u32> {
+        let resolution = self.decoder.resolution();
+        self.decoder.angle().map(|angle| match self.is_reversed {
+            true => (resolution - angle) % resolution,
+            false => angle,
+        })
+    }
+
+    /// Returns the encoder's (multi-turn) position in number of positions.
+    pub fn position(&self) -> T {
+        match self.is_reversed {
+            true => self.decoder.position().wrapping_neg(),
+            false => self.decoder.position(),
+        }
+    }
+
+    /// Sets the encoder's position.
+    pub fn set_position(&mut self, position: T) {
+        match self.is_reversed {
+            true => self.decoder.set_position(position.wrapping_neg()),
+            false => self.decoder.set_position(position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        digital::{Mock as PinMock, State as PinState, Transaction as PinTransaction},
+        spi::{Mock as SpiMock, Transaction as SpiTransaction},
+    };
+    use quadrature_decoder::Coding;
+
+    use super::*;
+
+    /// Returns the transactions of reading 11-bit frames reporting positions 1000 and 1001
+    /// (followed by the monoflop bit and surplus bits).
+    fn frames() -> Vec<SpiTransaction<u8>> {
+        [0b_0000_0000, 0b_0100_0000]
+            .into_iter()
+            .flat_map(|last| {
+                [
+                    SpiTransaction::transaction_start(),
+                    SpiTransaction::read_vec(vec![0b_1111_1010, last | 0b_0001_1111]),
+                    SpiTransaction::transaction_end(),
+                ]
+            })
+            .collect()
+    }
+
+    fn decoder() -> SsiDecoder {
+        SsiDecoder::new(10).with_coding(Coding::Binary)
+    }
+
+    #[test]
+    fn spi() {
+        let bus = SsiSpi::new(SpiMock::new(&frames()));
+        let mut encoder: SsiEncoder<_> = SsiEncoder::new(bus, decoder());
+
+        assert_eq!(encoder.poll(), Ok(None));
+        assert_eq!(encoder.poll(), Ok(Some(RotaryMovement::Clockwise)));
+        assert_eq!(encoder.angle(), Some(1001));
+        assert_eq!(encoder.position(), 1001);
+
+        encoder.release().release().done();
+    }
+
+    #[test]
+    fn reversed() {
+        let bus = SsiSpi::new(SpiMock::new(&frames()));
+        let mut encoder: SsiEncoder<_> = SsiEncoder::new(bus, decoder()).reversed();
+
+        assert_eq!(encoder.poll(), Ok(None));
+        assert_eq!(encoder.poll(), Ok(Some(RotaryMovement::CounterClockwise)));
+        assert_eq!(encoder.angle(), Some(23));
+        assert_eq!(encoder.position(), -1001);
+
+        encoder.set_position(10);
+        assert_eq!(encoder.position(), 10);
+
+        encoder.release().release().done();
+    }
+
+    #[test]
+    fn pins() {
+        use PinState::{High, Low};
+
+        // Clocking in a 3-bit frame (i.e. position 2, followed by the monoflop bit):
+        let clk = PinMock::new(&[Low, High, Low, High, Low, High].map(PinTransaction::set));
+        // The idle data line, followed by the frame's bits, and the monoflop not having timed out yet:
+        let data = PinMock::new(&[High, High, Low, Low, Low].map(PinTransaction::get));
+
+        let bus = SsiPins::new(clk, data, NoopDelay::new());
+        let decoder = SsiDecoder::new(2).with_coding(Coding::Binary);
+        let mut encoder: SsiEncoder<_> = SsiEncoder::new(bus, decoder);
+
+        assert_eq!(encoder.poll(), Ok(None));
+        assert_eq!(encoder.angle(), Some(2));
+        assert_eq!(encoder.poll(), Err(Error::Ssi(SsiError::Monoflop)));
+
+        let (mut clk, mut data, _) = encoder.release().release();
+        clk.done();
+        data.done();
+    }
+}
@@ -0,0 +1,205 @@
+//! A single-channel (aka tachometer) encoder driver.
+
+use core::marker::PhantomData;
+
+use num_traits::{One, SaturatingAdd, WrappingNeg, Zero};
+use quadrature_decoder::{Change, IncrementalDecoder, Tachometer};
+
+#[allow(unused_imports)]
+use crate::{
+    mode::{Movement, OperationMode},
+    traits::*,
+    Error, InputPinError, Linear, Rotary,
+};
+
+/// Rotary tachometer encoder.
+pub type TachometerRotaryEncoder<Pin, T = i32> = TachometerEncoder<Rotary, Pin, T>;
+/// Linear tachometer encoder.
+pub type TachometerLinearEncoder<Pin, T = i32> = TachometerEncoder<Linear, Pin, T>;
+
+/// A single-channel (aka tachometer) encoder, counting both edges of a single **pulse** pin,
+/// with the direction of movement being provided externally (e.g. a commanded direction),
+/// or being constant for unidirectional movement (which is the default).
+///
+/// For sensors providing a direction signal use an `IncrementalEncoder`
+/// in `Tachometer` step-mode instead, with `dt` being the direction pin.
+///
+/// Unlike the reported movements, the provided direction is not affected by `reversed()`:
+/// it states the direction of the sensor's (unreversed) pulses, just like a direction pin would,
+/// so a reversed encoder with a direction of `Clockwise` reports `CounterClockwise` movements.
+#[derive(Debug)]
+pub struct TachometerEncoder<Mode, Pin, T = i32>
+where
+    Mode: OperationMode,
+{
+    decoder: IncrementalDecoder<Tachometer, T>,
+    pin: Pin,
+    direction: Mode::Movement,
+    is_reversed: bool,
+    _mode: PhantomData<Mode>,
+}
+
+impl<Mode, Pin, T> TachometerEncoder<Mode, Pin, T>
+where
+    Mode: OperationMode,
+    Pin: InputPin,
+    T: Copy + Zero + One + SaturatingAdd + WrappingNeg + From<i8>,
+{
+    /// Creates a tachometer encoder driver for the given pin,
+    /// with a constant direction of `Change::Positive`.
+    pub fn new(pin: Pin) -> Self {
+        Self {
+            decoder: Default::default(),
+            pin,
+            direction: Change::Positive.into(),
+            is_reversed: false,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Sets the encoder's (unreversed) direction of movement.
+    pub fn with_direction(mut self, direction: Mode::Movement) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the encoder's reversed mode, making it report flipped movements and positions.
+    pub fn reversed(mut self) -> Self {
+        self.is_reversed = true;
+        self
+    }
+
+    /// Returns `true` if the encoder is reversed, otherwise `false`.
+    pub fn is_reversed(&self) -> bool {
+        self.is_reversed
+    }
+
+    /// Returns a mutable borrow for the pulse pin.
+    pub fn pin_mut(&mut self) -> &mut Pin {
+        &mut self.pin
+    }
+
+    /// Consumes self, returning the pulse pin.
+    pub fn release(self) -> Pin {
+        self.pin
+    }
+
+    /// Updates the encoder's state based on the **pulse** pin,
+    /// returning the direction if a movement was detected, `None` if no movement was detected,
+    /// or `Err(_)` if the pin could not be read.
+    pub fn poll(&mut self) -> Result<Option<Mode::Movement>, Error> {
+        let pulse = self
+            .pin
+            .is_high()
+            .map_err(|_| Error::InputPin(InputPinError::PinPulse))?;
+
+        let change = match self.direction == Change::Positive.into() {
+            true => Change::Positive,
+            false => Change::Negative,
+        };
+
+        let movement: Option<Mode::Movement> = self
+            .decoder
+            .update_pulse(pulse, change)
+            .map_err(Error::Quadrature)?
+            .map(From::from);
+
+        Ok(movement.map(|movement| {
+            if self.is_reversed() {
+                movement.flipped()
+            } else {
+                movement
+            }
+        }))
+    }
+
+    /// Resets the encoder to its initial state.
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
+    /// Returns the encoder's (unreversed) direction of movement.
+    pub fn direction(&self) -> Mode::Movement
+    where
+        Mode::Movement: Copy,
+    {
+        self.direction
+    }
+
+    /// Sets the encoder's (unreversed) direction of movement (e.g. upon a change of the commanded direction).
+    pub fn set_direction(&mut self, direction: Mode::Movement) {
+        self.direction = direction;
+    }
+
+    /// Returns the encoder's position counter relative to its initial position in number of edges.
+    pub fn position(&self) -> T {
+        match self.is_reversed {
+            true => self.decoder.counter().wrapping_neg(),
+            false => self.decoder.counter(),
+        }
+    }
+
+    /// Sets the encoder's position.
+    pub fn set_position(&mut self, position: T) {
+        match self.is_reversed {
+            true => self.decoder.set_counter(position.wrapping_neg()),
+            false => self.decoder.set_counter(position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    use crate::RotaryMovement;
+
+    use super::*;
+
+    fn pin(states: &[PinState]) -> PinMock {
+        let transactions: Vec<_> = states.iter().copied().map(PinTransaction::get).collect();
+        PinMock::new(&transactions)
+    }
+
+    #[test]
+    fn reversed() {
+        use PinState::{High, Low};
+
+        for is_reversed in [false, true] {
+            let mut encoder = TachometerRotaryEncoder::<_>::new(pin(&[Low, High, Low]));
+            if is_reversed {
+                encoder = encoder.reversed();
+            }
+
+            let (expected, position) = match is_reversed {
+                false => (RotaryMovement::Clockwise, 2),
+                true => (RotaryMovement::CounterClockwise, -2),
+            };
+
+            let movements: Vec<_> = (0..3).map(|_| encoder.poll().unwrap()).collect();
+            assert_eq!(movements[1..], [Some(expected), Some(expected)]);
+            assert_eq!(encoder.position(), position);
+
+            encoder.release().done();
+        }
+    }
+
+    #[test]
+    fn reversed_direction() {
+        use PinState::{High, Low};
+
+        let mut encoder = TachometerRotaryEncoder::<_>::new(pin(&[Low, High]))
+            .with_direction(RotaryMovement::CounterClockwise)
+            .reversed();
+
+        // The direction refers to the unreversed pulses:
+        assert_eq!(encoder.poll(), Ok(None));
+        assert_eq!(encoder.poll(), Ok(Some(RotaryMovement::Clockwise)));
+        assert_eq!(encoder.direction(), RotaryMovement::CounterClockwise);
+        assert_eq!(encoder.position(), 1);
+
+        encoder.release().done();
+    }
+}
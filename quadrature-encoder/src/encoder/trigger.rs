@@ -0,0 +1,99 @@
+//! A position-synchronized output (PSO) driver.
+
+use quadrature_decoder::PositionTrigger;
+
+#[allow(unused_imports)]
+use crate::{traits::*, Error};
+
+/// A position-synchronized output (PSO) driver, pulsing an output **pin**
+/// every time a `PositionTrigger` fires (e.g. for triggering line-scan cameras or laser pulses).
+///
+/// The pin gets driven high on the update a target is crossed,
+/// and driven back low on the subsequent update,
+/// i.e. the pulse width corresponds to the update interval.
+/// Targets fired on consecutive updates produce a brief low glitch between their pulses.
+#[derive(Debug)]
+pub struct TriggerOutput<Pin> {
+    trigger: PositionTrigger,
+    pin: Pin,
+    is_high: bool,
+}
+
+impl<Pin> TriggerOutput<Pin>
+where
+    Pin: OutputPin,
+{
+    /// Creates a trigger output driver for the given trigger and (initially low) pin.
+    pub fn new(trigger: PositionTrigger, pin: Pin) -> Self {
+        Self {
+            trigger,
+            pin,
+            is_high: false,
+        }
+    }
+
+    /// Returns the trigger.
+    pub fn trigger(&self) -> &PositionTrigger {
+        &self.trigger
+    }
+
+    /// Returns the trigger, mutably (e.g. for re-arming it via `trigger.reset()`).
+    pub fn trigger_mut(&mut self) -> &mut PositionTrigger {
+        &mut self.trigger
+    }
+
+    /// Updates the trigger based on the given `position` (e.g. `encoder.position()`),
+    /// driving the pin accordingly and returning the index of the fired target
+    /// if a target was crossed, otherwise `None`.
+    pub fn update<T>(&mut self, position: T) -> Result<Option<u32>, Error>
+    where
+        T: Into<i32>,
+    {
+        let fired = self.trigger.update(position.into());
+
+        if self.is_high {
+            self.pin.set_low().map_err(|_| Error::Bus)?;
+            self.is_high = false;
+        }
+
+        if fired.is_some() {
+            self.pin.set_high().map_err(|_| Error::Bus)?;
+            self.is_high = true;
+        }
+
+        Ok(fired)
+    }
+
+    /// Consumes self, returning the trigger and the pin.
+    pub fn release(self) -> (PositionTrigger, Pin) {
+        (self.trigger, self.pin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    use super::*;
+
+    #[test]
+    fn pulses() {
+        use PinState::{High, Low};
+
+        let pin = PinMock::new(&[High, Low, High, Low, High, Low].map(PinTransaction::set));
+        let mut output = TriggerOutput::new(PositionTrigger::new(10, 5, 3), pin);
+
+        let fired: Vec<_> = [8, 10, 15, 16, 17, 20, 21]
+            .into_iter()
+            .map(|position| output.update(position).unwrap())
+            .collect();
+
+        assert_eq!(fired, [None, Some(0), Some(1), None, None, Some(2), None]);
+        assert!(output.trigger().is_done());
+
+        let (_, mut pin) = output.release();
+        pin.done();
+    }
+}
@@ -6,12 +6,24 @@
 mod encoder;
 mod mode;
 mod traits;
-pub use quadrature_decoder::{Error as QuadratureError, FullStep, HalfStep, QuadStep};
+pub use quadrature_decoder::{
+    Alignment, BissDecoder, BissError, BissFrame, Change, Coding, DifferentialOdometry,
+    DistanceCodedDecoder, Error as QuadratureError, FullStep, HalfStep, HallAssistedDecoder,
+    HallError, IndexEdge, IndexQualifier, LinearityError, LinearityTable, Odometer, OdometerStats,
+    Parity, Pose, PositionTrigger, PwmError, PwmFormat, QuadStep, ReferenceError, SsiDecoder,
+    SsiError, StepDirection, Tachometer, UpDown, Vernier, VernierError,
+};
+
+#[cfg(feature = "float")]
+pub use quadrature_decoder::{DifferentialOdometryF32, PoseF32};
 
 pub use self::{
     encoder::{
-        IncrementalEncoder, IndexedIncrementalEncoder, IndexedLinearEncoder, IndexedRotaryEncoder,
-        LinearEncoder, RotaryEncoder,
+        BissEncoder, DistanceCodedEncoder, DistanceCodedLinearEncoder, DistanceCodedRotaryEncoder,
+        DualLoopEncoder, HallAssistedEncoder, IncrementalEncoder, IndexedIncrementalEncoder,
+        IndexedLinearEncoder, IndexedRotaryEncoder, LinearEncoder, PwmEncoder, RedundantEncoder,
+        RotaryEncoder, SsiBus, SsiEncoder, SsiPins, SsiSpi, TachometerEncoder,
+        TachometerLinearEncoder, TachometerRotaryEncoder, TriggerOutput,
     },
     mode::{
         Async, Blocking, Linear, LinearMovement, OperationMode, PollMode, Rotary, RotaryMovement,
@@ -20,6 +32,7 @@ pub use self::{
 
 /// An error indicating an input pin issue.
 #[repr(u8)]
+#[non_exhaustive]
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum InputPinError {
     /// Failed reading clock pin.
@@ -28,14 +41,39 @@ pub enum InputPinError {
     PinDt,
     /// Failed reading index pin.
     PinIdx,
+    /// Failed reading PWM pin.
+    PinPwm,
+    /// Failed reading hall sensor U pin.
+    PinU,
+    /// Failed reading hall sensor V pin.
+    PinV,
+    /// Failed reading hall sensor W pin.
+    PinW,
+    /// Failed reading pulse pin.
+    PinPulse,
 }
 
 /// An error indicating quadrature or input pin issues.
 #[repr(u8)]
+#[non_exhaustive]
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Error {
     /// Quadrature error.
     Quadrature(QuadratureError),
     /// Input pin error.
     InputPin(InputPinError),
+    /// Discrepancy between redundant encoders.
+    Discrepancy,
+    /// PWM frame error.
+    Pwm(PwmError),
+    /// SSI frame error.
+    Ssi(SsiError),
+    /// BiSS-C frame error.
+    Biss(BissError),
+    /// Hall sensor error.
+    Hall(HallError),
+    /// Distance-coded reference mark error.
+    Reference(ReferenceError),
+    /// Bus error (e.g. failed driving a clock pin, or a failed SPI transfer).
+    Bus,
 }
@@ -49,6 +49,7 @@ impl Movement for LinearMovement {
 }
 
 /// The mode of a linear quadrature encoder.
+#[derive(Debug)]
 pub struct Linear;
 
 impl OperationMode for Linear {
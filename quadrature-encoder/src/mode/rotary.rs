@@ -39,6 +39,7 @@ impl Movement for RotaryMovement {
 }
 
 /// The mode of a rotary quadrature encoder.
+#[derive(Debug)]
 pub struct Rotary;
 
 impl OperationMode for Rotary {
@@ -1,6 +1,9 @@
 // Polled pins must implement the `InputPin` trait from embedded-hal v1.0.0,
 // either directly or via `embedded-hal-compat` forward-ing.
 pub use eh1::digital::InputPin;
+
+// Clocked absolute encoders (e.g. SSI) additionally require output pins, delays or SPI devices.
+pub use eh1::{delay::DelayNs, digital::OutputPin, spi::SpiDevice};
 use embedded_hal_compat::eh1_0 as eh1;
 
 // exported async traits